@@ -6,11 +6,214 @@ use std::env;
 /// - Checks if mimalloc can compile (GCC version, stdatomic.h availability)
 /// - Stops compilation on incompatible systems with clear error messages
 /// - Provides upgrade guidance for legacy systems
-
 fn main() {
     println!("cargo:rerun-if-changed=build.rs");
     
     validate_platform_compatibility();
+    validate_embedded_atomics();
+    emit_no_std_settings();
+    emit_compile_time_allocator_cfg();
+    emit_no_libc_cfg();
+    emit_mimalloc_cfg();
+
+    emit_target_triple();
+
+    if env::var("CARGO_FEATURE_NO_GLOBAL").is_ok() {
+        println!("cargo:warning=  → no-global: #[global_allocator] not installed, the downstream allocator stays in effect");
+    }
+}
+
+/// Stops no_std builds on targets without compare-and-swap atomics
+///
+/// Allocator selection publishes its choice with `AtomicU8::compare_exchange`, which
+/// 8-bit AVR (and MSP430 / Cortex-M0) lack; failing here replaces a wall of
+/// "no method named `compare_exchange`" errors with one actionable message.
+/// `embedded-alloc` itself builds there once the firmware provides a
+/// `critical-section` implementation (e.g. `avr-device` with `critical-section-impl`).
+fn validate_embedded_atomics() {
+    let target_os = env::var("CARGO_CFG_TARGET_OS").unwrap_or_default();
+    let target_arch = env::var("CARGO_CFG_TARGET_ARCH").unwrap_or_default();
+    // Widths with compare-and-swap; load/store-only targets don't list them
+    let has_cas = env::var("CARGO_CFG_TARGET_HAS_ATOMIC")
+        .unwrap_or_default()
+        .split(',')
+        .any(|width| width == "8");
+
+    if target_os != "none" || has_cas {
+        return;
+    }
+
+    let hint = if target_arch == "avr" {
+        "use embedded-alloc directly with a `critical-section` implementation (e.g. avr-device's \
+         `critical-section-impl` feature) and a heap that leaves at least 1KB of the 2KB SRAM for the stack"
+    } else {
+        "use embedded-alloc directly with a `critical-section` implementation for this target"
+    };
+    panic!(
+        "\n\nauto-allocator: target `{}` has no compare-and-swap atomics, which allocator selection requires.\n\
+         → {}.\n\n",
+        env::var("TARGET").unwrap_or(target_arch),
+        hint
+    );
+}
+
+/// Forwards the no_std build-time settings to src/lib.rs
+///
+/// `AUTO_ALLOCATOR_HEAP_SIZE` (bytes) sets the embedded heap size and
+/// `AUTO_ALLOCATOR_CPU_CORES` the reported core count on bare-metal targets.
+/// Validated here so a typo fails with a message instead of a const-eval error
+/// in src/lib.rs, which reads the values with `option_env!`.
+fn emit_no_std_settings() {
+    emit_numeric_setting("AUTO_ALLOCATOR_HEAP_SIZE", "Embedded heap size", "bytes");
+    emit_numeric_setting("AUTO_ALLOCATOR_CPU_CORES", "Bare-metal CPU cores", "cores");
+
+    // `esp-psram`: external PSRAM as a second embedded heap on ESP32 (Xtensa)
+    emit_numeric_setting("AUTO_ALLOCATOR_PSRAM_BASE", "PSRAM heap base address", "bytes");
+    emit_numeric_setting("AUTO_ALLOCATOR_PSRAM_SIZE", "PSRAM heap size", "bytes");
+    emit_numeric_setting("AUTO_ALLOCATOR_PSRAM_THRESHOLD", "PSRAM allocation threshold", "bytes");
+    // `embedded-pool`: small-block pool in front of the embedded heap
+    emit_numeric_setting("AUTO_ALLOCATOR_POOL_SMALL", "Pool small block size", "bytes");
+    emit_numeric_setting("AUTO_ALLOCATOR_POOL_MEDIUM", "Pool medium block size", "bytes");
+    emit_numeric_setting("AUTO_ALLOCATOR_POOL_LARGE", "Pool large block size", "bytes");
+    emit_numeric_setting("AUTO_ALLOCATOR_POOL_BLOCKS", "Pool blocks per class", "blocks");
+    if env::var("CARGO_FEATURE_EMBEDDED_POOL").is_ok() && env::var("CARGO_CFG_TARGET_OS").unwrap_or_default() != "none" {
+        println!("cargo:warning=  → embedded-pool has no effect outside no_std (target_os = \"none\") targets");
+    }
+
+    if env::var("CARGO_FEATURE_ESP_PSRAM").is_ok() {
+        let target_arch = env::var("CARGO_CFG_TARGET_ARCH").unwrap_or_default();
+        let target_os = env::var("CARGO_CFG_TARGET_OS").unwrap_or_default();
+        if target_arch != "xtensa" || target_os != "none" {
+            println!("cargo:warning=  → esp-psram has no effect outside bare-metal Xtensa (ESP32) targets");
+        } else if env::var("AUTO_ALLOCATOR_PSRAM_BASE").is_ok() != env::var("AUTO_ALLOCATOR_PSRAM_SIZE").is_ok() {
+            panic!("\n\nauto-allocator: set both AUTO_ALLOCATOR_PSRAM_BASE and AUTO_ALLOCATOR_PSRAM_SIZE, or neither (then call init_psram_heap())\n\n");
+        }
+    }
+}
+
+fn emit_numeric_setting(name: &str, description: &str, unit: &str) {
+    println!("cargo:rerun-if-env-changed={}", name);
+
+    let Ok(value) = env::var(name) else {
+        return;
+    };
+    // Decimal, or 0x-prefixed hex for addresses; lib.rs only ever sees decimal
+    let trimmed = value.trim();
+    let parsed = match trimmed.strip_prefix("0x").or_else(|| trimmed.strip_prefix("0X")) {
+        Some(hex) => usize::from_str_radix(hex, 16),
+        None => trimmed.parse::<usize>(),
+    };
+    match parsed {
+        Ok(number) if number > 0 => {
+            println!("cargo:rustc-env={}={}", name, number);
+            if env::var("CARGO_CFG_TARGET_OS").unwrap_or_default() == "none" {
+                println!("cargo:warning=  → {}: {} {} ({})", description, trimmed, unit, name);
+            }
+        }
+        _ => panic!(
+            "\n\nauto-allocator: {} must be a positive number of {} (decimal or 0x hex), got `{}`\n\n",
+            name, unit, value
+        ),
+    }
+}
+
+/// Forwards the target triple cargo builds for, exposed as `auto_allocator::TARGET`
+///
+/// Cargo always sets `TARGET` for build scripts (the host triple without `--target`).
+fn emit_target_triple() {
+    let target = env::var("TARGET").expect("cargo sets TARGET for build scripts");
+    println!("cargo:rustc-env=AUTO_ALLOCATOR_TARGET={}", target);
+}
+
+/// Emits a cfg when the allocator is fully determined at compile time
+///
+/// Mirrors `get_compile_time_allocator()` in src/lib.rs (a const assertion there
+/// fails the build if they disagree): embedded targets use embedded-alloc; WASM,
+/// debug builds and platforms with a preferred native allocator use the system
+/// allocator unless an opt-in feature selects mimalloc. Everything else keeps the
+/// runtime hardware detection.
+fn emit_compile_time_allocator_cfg() {
+    println!("cargo:rustc-check-cfg=cfg(auto_allocator_compile_time_system)");
+    println!("cargo:rustc-check-cfg=cfg(auto_allocator_compile_time_embedded)");
+
+    let target_os = env::var("CARGO_CFG_TARGET_OS").unwrap_or_default();
+    let target_arch = env::var("CARGO_CFG_TARGET_ARCH").unwrap_or_default();
+    let debug_assertions = env::var("CARGO_CFG_DEBUG_ASSERTIONS").is_ok();
+    let feature = |name: &str| env::var(format!("CARGO_FEATURE_{}", name)).is_ok();
+
+    if target_os == "none" {
+        // Without `_embedded` there is no heap arm to collapse to
+        if feature("_EMBEDDED") {
+            println!("cargo:rustc-cfg=auto_allocator_compile_time_embedded");
+        }
+        return;
+    }
+
+    let mimalloc_opt_in = match target_os.as_str() {
+        "android" => feature("ANDROID_MIMALLOC"),
+        "freebsd" | "netbsd" => feature("BSD_MIMALLOC"),
+        _ => false,
+    };
+    let native_allocator_platform = matches!(
+        target_os.as_str(),
+        "android" | "ios" | "freebsd" | "netbsd" | "openbsd" | "solaris" | "illumos" | "nto" | "haiku"
+    );
+
+    if target_arch == "wasm32" || debug_assertions || (native_allocator_platform && !mimalloc_opt_in) {
+        println!("cargo:rustc-cfg=auto_allocator_compile_time_system");
+    }
+}
+
+/// Emits `auto_allocator_no_libc` when Linux detection and logging use raw syscalls
+///
+/// `libc` is optional on Linux x86_64/aarch64 (the default `_libc` feature pulls it
+/// in), so a build without it takes the raw-syscall paths just like `no-libc` does.
+fn emit_no_libc_cfg() {
+    println!("cargo:rustc-check-cfg=cfg(auto_allocator_no_libc)");
+
+    let target_os = env::var("CARGO_CFG_TARGET_OS").unwrap_or_default();
+    let target_arch = env::var("CARGO_CFG_TARGET_ARCH").unwrap_or_default();
+    if target_os != "linux" || !matches!(target_arch.as_str(), "x86_64" | "aarch64") {
+        return;
+    }
+
+    let no_libc = env::var("CARGO_FEATURE_NO_LIBC").is_ok();
+    let libc = env::var("CARGO_FEATURE__LIBC").is_ok();
+    if no_libc || !libc {
+        println!("cargo:rustc-cfg=auto_allocator_no_libc");
+    }
+    if no_libc && libc {
+        println!("cargo:warning=  → no-libc: raw syscalls in use, but default features still link libc - set default-features = false to drop it");
+    }
+}
+
+/// Emits `auto_allocator_mimalloc` when a mimalloc backend is compiled in and usable
+///
+/// Requires `_mimalloc` or `_mimalloc_secure`, a release build, and Windows, macOS or
+/// Linux - or Android / FreeBSD / NetBSD with `android-mimalloc` / `bsd-mimalloc`.
+/// src/lib.rs narrows it with `feature = "_mimalloc"` / `"_mimalloc_secure"` where
+/// the variant matters.
+fn emit_mimalloc_cfg() {
+    println!("cargo:rustc-check-cfg=cfg(auto_allocator_mimalloc)");
+
+    let target_os = env::var("CARGO_CFG_TARGET_OS").unwrap_or_default();
+    let target_arch = env::var("CARGO_CFG_TARGET_ARCH").unwrap_or_default();
+    let debug_assertions = env::var("CARGO_CFG_DEBUG_ASSERTIONS").is_ok();
+    let feature = |name: &str| env::var(format!("CARGO_FEATURE_{}", name)).is_ok();
+
+    let platform = match target_os.as_str() {
+        "windows" | "macos" | "linux" => true,
+        "android" => feature("ANDROID_MIMALLOC"),
+        "freebsd" | "netbsd" => feature("BSD_MIMALLOC"),
+        _ => false,
+    };
+    if (feature("_MIMALLOC") || feature("_MIMALLOC_SECURE"))
+        && platform
+        && target_arch != "wasm32"
+        && !debug_assertions
+    {
+        println!("cargo:rustc-cfg=auto_allocator_mimalloc");
+    }
 }
 
 /// Validates that the current platform can compile mimalloc
@@ -34,13 +237,14 @@ fn validate_platform_compatibility() {
                 }
         }
         
-        // Other Linux environments
+        // Linux musl (static binaries) - mimalloc is enabled exactly like glibc,
+        // replacing musl's comparatively slow malloc in release builds
         ("linux", "musl", _) => {
                 println!("cargo:warning=Auto-allocator: Linux musl platform detected");
                 if is_debug {
                     println!("cargo:warning=  → Will use system allocator (debug build)");
                 } else {
-                    println!("cargo:warning=  → Will use mimalloc (release build)");
+                    println!("cargo:warning=  → Will use mimalloc (release build, replaces musl malloc)");
                 }
         }
 
@@ -74,7 +278,11 @@ fn print_platform_info(target_os: &str, target_env: &str, target_arch: &str, is_
         // Mobile platforms
         ("android", _, _) => {
             println!("cargo:warning=Auto-allocator: Android platform detected");
-            println!("cargo:warning=  → Will use system allocator (Scudo) per Android security policy");
+            if env::var("CARGO_FEATURE_ANDROID_MIMALLOC").is_ok() && !is_debug {
+                println!("cargo:warning=  → Will use mimalloc (android-mimalloc opt-in overrides Scudo, release build)");
+            } else {
+                println!("cargo:warning=  → Will use system allocator (Scudo) per Android security policy");
+            }
         }
         ("ios", _, _) => {
             println!("cargo:warning=Auto-allocator: iOS platform detected");
@@ -84,7 +292,11 @@ fn print_platform_info(target_os: &str, target_env: &str, target_arch: &str, is_
         // BSD systems  
         ("freebsd", _, _) | ("netbsd", _, _) => {
             println!("cargo:warning=Auto-allocator: BSD platform detected ({})", target_os);
-            println!("cargo:warning=  → Will use system allocator (native jemalloc)");
+            if env::var("CARGO_FEATURE_BSD_MIMALLOC").is_ok() && !is_debug {
+                println!("cargo:warning=  → Will use mimalloc (bsd-mimalloc opt-in overrides native jemalloc, release build)");
+            } else {
+                println!("cargo:warning=  → Will use system allocator (native jemalloc)");
+            }
         }
         ("openbsd", _, _) => {
             println!("cargo:warning=Auto-allocator: OpenBSD platform detected");
@@ -94,7 +306,19 @@ fn print_platform_info(target_os: &str, target_env: &str, target_arch: &str, is_
         // Solaris systems
         ("solaris", _, _) | ("illumos", _, _) => {
             println!("cargo:warning=Auto-allocator: Solaris platform detected ({})", target_os);
-            println!("cargo:warning=  → Will use system allocator (libumem)");
+            println!("cargo:warning=  → Will use system allocator (libumem when linked, otherwise libc malloc)");
+        }
+
+        // QNX Neutrino RTOS - mimalloc has no QNX port
+        ("nto", _, _) => {
+            println!("cargo:warning=Auto-allocator: QNX Neutrino platform detected");
+            println!("cargo:warning=  → Will use system allocator (QNX native allocator, real-time friendly)");
+        }
+
+        // Haiku
+        ("haiku", _, _) => {
+            println!("cargo:warning=Auto-allocator: Haiku platform detected");
+            println!("cargo:warning=  → Will use system allocator (libroot)");
         }
 
         // High-performance platforms that support mimalloc
@@ -0,0 +1,186 @@
+//! Allocator Shootout Example
+//!
+//! Runs the same allocation workload under every allocator available in this build
+//! and prints a comparison table, so the choice can be checked on real hardware
+//! instead of trusting the selection heuristic.
+//!
+//! The allocator is selected once per process on the first allocation and memory
+//! can't move between backends, so each allocator gets a fresh child process of this
+//! example, pinned with `AUTO_ALLOCATOR_PREFER`. Nothing allocated under one
+//! allocator is ever freed by another.
+//!
+//! ```bash
+//! cargo run --release --example allocator_shootout
+//! ```
+
+use auto_allocator::AllocatorType;
+use std::hint::black_box;
+use std::process::Command;
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Set in child processes: run the workload and report instead of orchestrating
+const CHILD_ENV: &str = "AUTO_ALLOCATOR_SHOOTOUT_CHILD";
+
+/// Candidates in the order they are compared, with their `AUTO_ALLOCATOR_PREFER` names
+const CANDIDATES: [(AllocatorType, &str); 3] = [
+    (AllocatorType::System, "system"),
+    (AllocatorType::Mimalloc, "mimalloc"),
+    (AllocatorType::MimallocSecure, "mimalloc-secure"),
+];
+
+const PHASES: [&str; 4] = ["small boxes", "vec growth", "mixed sizes", "4 threads"];
+
+fn main() {
+    if std::env::var_os(CHILD_ENV).is_some() {
+        run_child();
+        return;
+    }
+
+    println!("=== Allocator Shootout ===");
+    if cfg!(debug_assertions) {
+        println!("Debug build: only the system allocator is available - run with --release");
+    }
+    println!();
+
+    let exe = std::env::current_exe().expect("path of this example");
+    let mut rows = Vec::new();
+    for (allocator, name) in CANDIDATES {
+        if !auto_allocator::is_allocator_available(allocator) {
+            continue;
+        }
+        let output = Command::new(&exe)
+            .env(CHILD_ENV, "1")
+            .env("AUTO_ALLOCATOR_PREFER", name)
+            .env("AUTO_ALLOCATOR_LOG", "off")
+            .output()
+            .expect("spawn shootout child");
+        let report = String::from_utf8_lossy(&output.stdout);
+        match report.lines().find_map(parse_report) {
+            // The preference is skipped when the hardware doesn't suit it (e.g. one core)
+            Some((active, _, _)) if active != name => {
+                println!("{}: not selectable here (got {})", name, active);
+            }
+            Some(row) => rows.push(row),
+            None => println!("{}: child failed ({})", name, output.status),
+        }
+    }
+
+    print!("{:<16}", "allocator");
+    for phase in PHASES {
+        print!("{:>14}", phase);
+    }
+    println!("{:>12}", "RSS");
+    for (name, timings, rss) in &rows {
+        print!("{:<16}", name);
+        for nanos in timings {
+            print!("{:>12.2}ms", *nanos as f64 / 1e6);
+        }
+        match rss {
+            Some(bytes) => println!("{:>12}", auto_allocator::format_memory_size(*bytes)),
+            None => println!("{:>12}", "n/a"),
+        }
+    }
+
+    println!();
+    println!(
+        "auto-allocator picks: {} - {}",
+        auto_allocator::allocator_name(),
+        auto_allocator::get_allocator_info().reason
+    );
+}
+
+/// Parses a child's `RESULT <allocator> <nanos>... <rss>` line
+fn parse_report(line: &str) -> Option<(String, Vec<u128>, Option<u64>)> {
+    let mut fields = line.strip_prefix("RESULT ")?.split_whitespace();
+    let name = fields.next()?.to_string();
+    let timings = (0..PHASES.len())
+        .map(|_| fields.next()?.parse().ok())
+        .collect::<Option<Vec<u128>>>()?;
+    let rss = fields.next()?.parse().ok();
+    Some((name, timings, rss))
+}
+
+fn run_child() {
+    let timings: Vec<Duration> = vec![
+        time(small_boxes),
+        time(vec_growth),
+        time(mixed_sizes),
+        time(threaded),
+    ];
+    let rss = auto_allocator::process_memory_usage();
+
+    print!("RESULT {}", auto_allocator::allocator_name());
+    for elapsed in &timings {
+        print!(" {}", elapsed.as_nanos());
+    }
+    match rss {
+        Some(bytes) => println!(" {}", bytes),
+        None => println!(" -"),
+    }
+}
+
+/// Best of three runs, to keep one-off page faults out of the comparison
+fn time(workload: fn()) -> Duration {
+    workload(); // warm up
+    (0..3)
+        .map(|_| {
+            let start = Instant::now();
+            workload();
+            start.elapsed()
+        })
+        .min()
+        .unwrap_or_default()
+}
+
+/// Many small, short-lived allocations
+fn small_boxes() {
+    for i in 0..200_000u64 {
+        black_box(Box::new(i));
+    }
+}
+
+/// Vectors growing by doubling, as in builders and collect()
+fn vec_growth() {
+    for _ in 0..200 {
+        let mut values = Vec::new();
+        for i in 0..10_000u32 {
+            values.push(i);
+        }
+        black_box(values);
+    }
+}
+
+/// Interleaved sizes with a partly retained working set, which fragments the heap
+fn mixed_sizes() {
+    let sizes = [16, 64, 256, 1024, 4096, 16384];
+    let mut retained = Vec::with_capacity(5_000);
+    for i in 0..50_000usize {
+        let block = vec![0u8; sizes[i % sizes.len()]];
+        if i % 10 == 0 {
+            retained.push(block);
+        } else {
+            black_box(block);
+        }
+    }
+    black_box(retained);
+}
+
+/// The same small-allocation churn on several threads at once
+fn threaded() {
+    let shared = Arc::new(vec![0u8; 1024]);
+    let handles: Vec<_> = (0..4)
+        .map(|_| {
+            let shared = Arc::clone(&shared);
+            thread::spawn(move || {
+                for i in 0..50_000usize {
+                    black_box(vec![shared[i % 1024]; 64]);
+                }
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+}
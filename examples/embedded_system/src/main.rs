@@ -123,6 +123,9 @@ pub extern "C" fn _start() -> ! {
         auto_allocator::AllocatorType::MimallocSecure => {
             print_str(b"MimallocSecure (ERROR: not available in no_std!) [ERROR]\n")
         },
+        auto_allocator::AllocatorType::Custom => {
+            print_str(b"Custom (registered via register_custom_allocator) [OK]\n")
+        },
     }
     
     print_str(b"Selection Reason: ");
@@ -15,6 +15,9 @@ fn main() {
     println!("Current Allocator:");
     println!("  Type: {:?}", info.allocator_type);
     println!("  Reason: {}", info.reason);
+    if let Some(version) = auto_allocator::backend_version() {
+        println!("  Backend Version: {}", version);
+    }
     println!();
 
     // Get recommendations for current runtime environment
@@ -41,13 +44,47 @@ fn main() {
     println!("System Information:");
     println!("  OS: {}", info.system_info.os_type);
     println!("  CPU Cores: {}", info.system_info.cpu_cores);
+    if info.system_info.efficiency_cores > 0 {
+        println!(
+            "  Core Topology: {} performance + {} efficiency",
+            info.system_info.performance_cores, info.system_info.efficiency_cores
+        );
+    }
+    if let Some(hypervisor) = info.system_info.hypervisor {
+        println!("  Hypervisor: {}", hypervisor);
+    }
+    if info.system_info.is_pid1 {
+        println!("  Running as PID 1 (container entrypoint without an init process?)");
+    }
     println!(
         "  Total Memory: {}",
         auto_allocator::format_memory_size(info.system_info.total_memory_bytes)
     );
+    if info.system_info.available_memory_bytes > 0 {
+        println!(
+            "  Available Memory: {}",
+            auto_allocator::format_memory_size(info.system_info.available_memory_bytes)
+        );
+    }
+    if info.system_info.commit_limit_bytes > 0 {
+        println!(
+            "  Commit: {} available of {}",
+            auto_allocator::format_memory_size(info.system_info.available_commit_bytes),
+            auto_allocator::format_memory_size(info.system_info.commit_limit_bytes)
+        );
+    }
+    println!(
+        "  Swap: {} free of {}",
+        auto_allocator::format_memory_size(info.system_info.free_swap_bytes),
+        auto_allocator::format_memory_size(info.system_info.total_swap_bytes)
+    );
     println!("  WASM: {}", info.system_info.is_wasm);
     println!("  Debug Build: {}", info.system_info.is_debug);
     println!("  Architecture: {}", info.system_info.target_arch);
+    println!("  CPU Features: {:?}", info.system_info.cpu_features);
+    println!("  Page Size: {} bytes", info.system_info.page_size);
+    println!("  CPU Caches: {:?}", info.system_info.cache_sizes);
+    println!("  Huge Pages: {}", info.system_info.huge_pages_available);
 
     println!();
     println!("=== Performance Guidelines ===");
@@ -83,6 +120,10 @@ fn main() {
             println!("   • Memory-constrained applications");
             println!("   • Real-time systems requiring deterministic allocation");
         }
+        auto_allocator::AllocatorType::Custom => {
+            println!("🧩 a custom allocator was registered:");
+            println!("   • Selection is the application's explicit choice");
+        }
     }
 
     println!();
@@ -1,10 +1,10 @@
-/// 🚀 Auto-Allocator Basic Usage Demo
-///
-/// This example demonstrates the core functionality of auto-allocator:
-/// 1. 🎯 Zero-configuration automatic allocator selection
-/// 2. 📊 System information viewing
-/// 3. ⚙️ Environment variable control methods
-/// 4. 🧪 Basic memory allocation testing
+//! 🚀 Auto-Allocator Basic Usage Demo
+//!
+//! This example demonstrates the core functionality of auto-allocator:
+//! 1. 🎯 Zero-configuration automatic allocator selection
+//! 2. 📊 System information viewing
+//! 3. ⚙️ Environment variable control methods
+//! 4. 🧪 Basic memory allocation testing
 
 // This is the core usage of auto-allocator: just one use statement enables automatic allocator selection
 #[allow(clippy::single_component_path_imports)]
@@ -61,4 +61,5 @@ fn main() {
     println!("  🌐 WASM: {}", info.system_info.is_wasm);
     println!("  🐛 Debug Build: {}", info.system_info.is_debug);
     println!("  🏗️  Architecture: {}", info.system_info.target_arch);
+    println!("  🎯 Target: {} ({}-bit, {}-endian)", info.system_info.target_triple, info.system_info.pointer_width, info.system_info.endian.name());
 }
@@ -0,0 +1,66 @@
+//! # Auto-Allocator StaticHeap Example (Cortex-M)
+//!
+//! Declares the embedded heap in the firmware instead of sizing the built-in one with
+//! `AUTO_ALLOCATOR_HEAP_SIZE`: a 16KB `StaticHeap` placed in its own linker section,
+//! registered before the first allocation.
+//!
+//! Build for a Cortex-M4F board (memory.x must define the `.heap` section, or drop the
+//! `#[link_section]` to keep the heap in `.bss`):
+//!
+//! ```bash
+//! cargo build --example static_heap --target thumbv7em-none-eabihf --release
+//! ```
+
+#![cfg_attr(target_os = "none", no_std)]
+#![cfg_attr(target_os = "none", no_main)]
+
+/// Main function for non-embedded environments (std)
+///
+/// `StaticHeap` only exists on no_std targets; here the built-in selection applies.
+#[cfg(not(target_os = "none"))]
+fn main() {
+    let info = auto_allocator::get_allocator_info();
+    println!("=== auto-allocator StaticHeap Example ===");
+    println!("StaticHeap is for no_std targets. Build with:");
+    println!("  cargo build --example static_heap --target thumbv7em-none-eabihf --release");
+    println!("Allocator in this environment: {:?} - {}", info.allocator_type, info.reason);
+}
+
+#[cfg(all(target_arch = "arm", target_os = "none"))]
+mod firmware {
+    extern crate alloc;
+
+    use alloc::vec::Vec;
+    use auto_allocator::StaticHeap;
+    use cortex_m_rt::entry;
+    use panic_halt as _;
+
+    /// Heap size chosen here, not through a build-time environment variable
+    const HEAP_SIZE: usize = 16 * 1024;
+
+    // A dedicated section lets the linker script put the heap in a specific RAM bank
+    #[link_section = ".heap"]
+    static HEAP: StaticHeap<HEAP_SIZE> = StaticHeap::new();
+
+    #[entry]
+    fn main() -> ! {
+        // Before anything allocates: afterwards the built-in heap stays in use
+        if !HEAP.as_global() {
+            panic!("StaticHeap registered too late");
+        }
+
+        let mut samples: Vec<u16> = Vec::with_capacity(256);
+        let mut reading = 0u16;
+        loop {
+            reading = reading.wrapping_add(1);
+            if samples.len() == samples.capacity() {
+                samples.clear();
+            }
+            samples.push(reading);
+
+            // All of it comes from HEAP
+            let _in_use = HEAP.used();
+            cortex_m::asm::wfi();
+        }
+    }
+}
@@ -81,6 +81,9 @@ fn main() {
         auto_allocator::AllocatorType::EmbeddedHeap => {
             println!("embedded allocator automatically selected - optimized for constrained environments!");
         }
+        auto_allocator::AllocatorType::Custom => {
+            println!("custom allocator registered - application-supplied backend!");
+        }
     }
 }
 
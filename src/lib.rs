@@ -47,19 +47,39 @@
 //! ```toml
 //! auto-allocator = { version = "*", features = ["secure"] }
 //! ```
+//!
+//! ## Configuration Timing
+//!
+//! The allocator is selected once, on the first allocation - in std binaries that is
+//! inside the Rust runtime, before `main`. Settings that feed the selection
+//! ([`set_allocator_profile()`], [`set_denied_allocators()`],
+//! [`set_allocator_preference()`], [`AutoAllocatorConfig::apply()`],
+//! [`register_custom_allocator()`]) are fully effective only before that point, i.e.
+//! from a pre-main constructor; environment variables are read at selection time and
+//! are the easy route. Setters are safe to call concurrently with the first selection
+//! and report whether they made it in time: `true` / `Ok` means the selection saw
+//! them. Once selection has started, the setters still update what
+//! [`get_recommended_allocator()`] reports (`apply()` applies nothing), and hooks and
+//! callbacks (profiling, memory pressure) work at any time - only the choice of
+//! allocator is fixed.
+//!
+//! ## Testing Selection
+//!
+//! Because the choice is fixed per process, tests can't re-run the live selection
+//! for a debug, WASM or embedded scenario. With the `testing` feature, describe the
+//! scenario as a [`SystemInfo`] and check the decision with `testing::select_for()`
+//! instead - see the `testing` module for the pattern.
 
 #![cfg_attr(target_os = "none", no_std)]
 
 // Conditional imports for std vs no_std
-#[cfg(not(target_os = "none"))]
+#[cfg(all(feature = "log", not(feature = "no-log"), not(target_os = "none")))]
 use log::info;
 #[cfg(not(target_os = "none"))]
-use once_cell::sync::Lazy;
+use std::sync::LazyLock;
 
 use core::alloc::{GlobalAlloc, Layout};
-use core::sync::atomic::{AtomicU8, Ordering};
-#[cfg(not(target_os = "none"))]
-use core::sync::atomic::AtomicBool;
+use core::sync::atomic::{AtomicBool, AtomicU16, AtomicU32, AtomicU8, AtomicUsize, Ordering};
 
 // Import std-specific modules conditionally
 #[cfg(not(target_os = "none"))]
@@ -78,6 +98,7 @@ use std::alloc;
 /// - [`AllocatorType::Mimalloc`] - Microsoft-developed allocator, optimal multi-threaded performance  
 /// - [`AllocatorType::EmbeddedHeap`] - Lightweight allocator for resource-constrained environments
 /// - [`AllocatorType::System`] - Operating system default allocator, maximum compatibility
+/// - [`AllocatorType::Custom`] - User-supplied allocator from [`register_custom_allocator()`]
 ///
 /// # Automatic Selection Logic
 ///
@@ -131,6 +152,53 @@ pub enum AllocatorType {
     /// Operating system provided allocator, maximum compatibility.
     /// Selected for debug builds, WASM, mobile, and platforms with optimized native allocators.
     System,
+
+    /// User-supplied allocator
+    ///
+    /// Registered with [`register_custom_allocator()`] before the first allocation;
+    /// takes precedence over automatic selection.
+    Custom,
+}
+
+impl AllocatorType {
+    /// Stable numeric ID of this allocator type, as returned by [`allocator_id()`]
+    ///
+    /// `1` system, `2` mimalloc, `4` embedded-alloc, `5` mimalloc-secure, `6` custom;
+    /// `3` is reserved for jemalloc. The scheme is part of the public API, so IDs are
+    /// safe to pass over FFI or store in compact serialized formats.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use auto_allocator::AllocatorType;
+    ///
+    /// assert_eq!(AllocatorType::Mimalloc.id(), 2);
+    /// assert_eq!(AllocatorType::from_id(AllocatorType::System.id()), Some(AllocatorType::System));
+    /// ```
+    pub const fn id(self) -> u8 {
+        match self {
+            AllocatorType::System => 1,
+            AllocatorType::Mimalloc => 2,
+            AllocatorType::EmbeddedHeap => 4,
+            AllocatorType::MimallocSecure => 5,
+            AllocatorType::Custom => 6,
+        }
+    }
+
+    /// Allocator type for a stable ID (see [`AllocatorType::id()`])
+    ///
+    /// Returns `None` for `0` (not yet selected), the reserved `3` and unknown IDs,
+    /// rather than falling back to a default.
+    pub const fn from_id(id: u8) -> Option<AllocatorType> {
+        match id {
+            1 => Some(AllocatorType::System),
+            2 => Some(AllocatorType::Mimalloc),
+            4 => Some(AllocatorType::EmbeddedHeap),
+            5 => Some(AllocatorType::MimallocSecure),
+            6 => Some(AllocatorType::Custom),
+            _ => None,
+        }
+    }
 }
 
 /// Allocator information structure
@@ -142,6 +210,7 @@ pub enum AllocatorType {
 ///
 /// - `allocator_type` - Currently used allocator type
 /// - `reason` - Detailed reason for allocator selection, including hardware information
+/// - `reason_detail` - Structured form of `reason` for programmatic consumption
 /// - `system_info` - System hardware and environment information
 ///
 /// # Example
@@ -162,12 +231,21 @@ pub struct AllocatorInfo {
     /// Detailed reason for allocator selection
     ///
     /// Contains hardware detection results and selection logic explanation, for example:
-    /// "mimalloc selected by runtime hardware analysis (16 cores, 128GB total RAM)"
+    /// "high-performance multi-threaded environment - runtime detected (16 cores, 128GB total RAM)"
+    ///
+    /// On std targets this is the `Display` output of [`AllocatorInfo::reason_detail`];
+    /// with the `no-log` feature it is a fixed summary without the detected values.
     #[cfg(not(target_os = "none"))]
     pub reason: String,
     #[cfg(target_os = "none")]
     pub reason: &'static str,
 
+    /// Structured selection reason
+    ///
+    /// Carries the same decision as `reason` with its inputs as typed fields,
+    /// so consumers don't need to parse the human-readable string.
+    pub reason_detail: SelectionReason,
+
     /// System hardware and environment information
     pub system_info: SystemInfo,
 }
@@ -180,11 +258,20 @@ pub struct AllocatorInfo {
 /// # Fields
 ///
 /// - `os_type` - Operating system type (linux, macos, windows, etc.)
-/// - `cpu_cores` - CPU core count (including hyperthreaded cores)
+/// - `cpu_cores` - CPU core count (including hyperthreaded cores), clamped to any cgroup CPU quota
+/// - `cpu_quota_cores` - Core count allowed by a cgroup CPU quota, when it clamped `cpu_cores`
 /// - `total_memory_bytes` - Total memory in bytes
+/// - `total_swap_bytes` / `free_swap_bytes` - Swap space size and free amount in bytes
 /// - `is_debug` - Whether this is a Debug build
 /// - `is_wasm` - Whether this is a WASM environment
 /// - `target_arch` - Target architecture (x86_64, aarch64, etc.)
+/// - `target_triple` - Full target triple, including vendor and environment (gnu, musl, msvc)
+/// - `pointer_width` / `endian` - Pointer width in bits and byte order of the target
+/// - `cpu_features` - Detected CPU SIMD capabilities (SSE2/AVX/AVX2/AVX-512/NEON)
+/// - `huge_pages_available` - Whether transparent huge pages are enabled (Linux)
+/// - `page_size` - OS memory page size in bytes
+/// - `cache_sizes` - CPU cache sizes (L1 data, L2, L3)
+/// - `performance_cores` / `efficiency_cores` - Heterogeneous (big.LITTLE) core split
 ///
 /// # Example
 ///
@@ -210,15 +297,63 @@ pub struct SystemInfo {
 
     /// CPU core count
     ///
-    /// Detected via `std::thread::available_parallelism()`, includes hyperthreaded core count
+    /// Detected via `std::thread::available_parallelism()`, includes hyperthreaded core count.
+    /// On Linux this is clamped to `ceil(quota / period)` of the cgroup CPU quota
+    /// (`cpu.max` or `cpu.cfs_quota_us`), see `cpu_quota_cores`.
+    /// On no_std targets: `AUTO_ALLOCATOR_CPU_CORES` at build time, CPUID on x86_64, else 1.
     pub cpu_cores: usize,
 
+    /// Core count allowed by the cgroup CPU quota, when it is lower than the detected cores
+    ///
+    /// `Some(n)` when the quota clamped `cpu_cores` to `n` (e.g. `Some(1)` for a 0.5 CPU pod),
+    /// `None` when there is no quota, it isn't lower, or the platform isn't Linux.
+    pub cpu_quota_cores: Option<usize>,
+
     /// Total memory in bytes
     ///
     /// System total physical memory, used for hardware specification assessment.
+    /// `AUTO_ALLOCATOR_TOTAL_MEMORY` (e.g. `512MB`, see [`parse_memory_size()`]) replaces it.
     /// Use [`format_memory_size()`] to format as human-readable string.
     pub total_memory_bytes: u64,
 
+    /// Memory actually available to the process in bytes
+    ///
+    /// `total_memory_bytes` capped by the cgroup memory limit on Linux (`memory.max` or
+    /// `memory.limit_in_bytes`), equal to it elsewhere. Allocator selection uses this value,
+    /// so a small container on a large host is treated as memory-constrained.
+    /// Precedence: `AUTO_ALLOCATOR_TOTAL_MEMORY` > cgroup limit > physical memory.
+    pub effective_memory_bytes: u64,
+
+    /// Physical memory currently available in bytes, informational for now
+    ///
+    /// `MemAvailable` from `/proc/meminfo` on Linux, free + inactive pages from
+    /// `host_statistics64()` on macOS and `ullAvailPhys` on Windows.
+    /// `0` when it can't be detected. A point-in-time value read together with
+    /// `total_memory_bytes` (never above it) and not refreshed - see [`memory_snapshot()`].
+    pub available_memory_bytes: u64,
+
+    /// Total swap space in bytes
+    ///
+    /// From `sysinfo()` on Linux, `vm.swapusage` on macOS and the page-file size
+    /// beyond physical memory on Windows. `0` when there is no swap or it can't be detected.
+    pub total_swap_bytes: u64,
+
+    /// Free swap space in bytes
+    ///
+    /// `0` when there is no swap or it can't be detected.
+    pub free_swap_bytes: u64,
+
+    /// Commit limit in bytes (Windows)
+    ///
+    /// `ullTotalPageFile`: physical memory plus page files, the most memory the system
+    /// can commit before allocations fail. `0` on other platforms.
+    pub commit_limit_bytes: u64,
+
+    /// Commit charge still available in bytes (Windows)
+    ///
+    /// `ullAvailPageFile`, the room left under `commit_limit_bytes`. `0` on other platforms.
+    pub available_commit_bytes: u64,
+
     /// Whether this is a Debug build
     ///
     /// Debug builds automatically select system allocator for faster compilation
@@ -236,6 +371,564 @@ pub struct SystemInfo {
     pub target_arch: String,
     #[cfg(target_os = "none")]
     pub target_arch: &'static str,
+
+    /// Full target triple the crate was built for
+    ///
+    /// Examples: "x86_64-unknown-linux-gnu", "x86_64-unknown-linux-musl", "x86_64-pc-windows-msvc",
+    /// "thumbv7em-none-eabihf". Includes the vendor and environment that `os_type` and
+    /// `target_arch` leave out - gnu vs musl, msvc vs gnu - which is what bug reports need.
+    pub target_triple: &'static str,
+
+    /// Pointer width of the target in bits
+    ///
+    /// 64 on x86_64/aarch64, 32 on wasm32 and most MCUs, 16 on AVR and MSP430. A compile-time
+    /// constant; explains memory figures on 32-bit targets, where a process can address at
+    /// most 4GB whatever `total_memory_bytes` says.
+    pub pointer_width: u8,
+
+    /// Byte order of the target, a compile-time constant
+    pub endian: Endianness,
+
+    /// CPU SIMD capabilities
+    ///
+    /// Detected at runtime on x86/x86_64 and aarch64, informational for now.
+    /// All flags are `false` on architectures without feature detection.
+    pub cpu_features: CpuFeatures,
+
+    /// Whether transparent huge pages are enabled
+    ///
+    /// `true` on Linux when `/sys/kernel/mm/transparent_hugepage/enabled` is set to
+    /// `always` or `madvise`. Always `false` on other platforms.
+    pub huge_pages_available: bool,
+
+    /// Transparent huge page policy
+    ///
+    /// The bracketed mode in `/sys/kernel/mm/transparent_hugepage/enabled` on Linux.
+    /// `None` on other platforms or when the file is missing (kernel without THP).
+    pub thp_mode: Option<ThpMode>,
+
+    /// OS memory page size in bytes
+    ///
+    /// From `sysconf(_SC_PAGESIZE)` on Unix and `GetSystemInfo()` on Windows, 64KB on WASM.
+    /// No_std targets report a conservative 4KB default.
+    pub page_size: usize,
+
+    /// CPU cache sizes, informational for now
+    ///
+    /// Fields are `None` where the platform doesn't report them.
+    pub cache_sizes: CacheSizes,
+
+    /// Performance ("big") cores among `cpu_cores`
+    ///
+    /// Detected from `cpu_capacity` in sysfs on Linux/Android and `hw.perflevel*` on macOS.
+    /// Equals `cpu_cores` on homogeneous systems or when detection isn't available.
+    pub performance_cores: usize,
+
+    /// Efficiency ("LITTLE") cores among `cpu_cores`
+    ///
+    /// `0` on homogeneous systems or when detection isn't available.
+    pub efficiency_cores: usize,
+
+    /// Hypervisor the process runs under, informational for now
+    ///
+    /// From the CPUID hypervisor-present bit and vendor leaf on x86/x86_64: `"KVM"`,
+    /// `"VMware"`, `"Hyper-V"`, `"Xen"`, `"QEMU"`, `"VirtualBox"`, `"Parallels"`,
+    /// `"bhyve"`, or `"unknown"` for an unrecognized vendor. KVM guests with Hyper-V
+    /// enlightenments report `"Hyper-V"`. `None` on bare metal and non-x86 targets.
+    pub hypervisor: Option<&'static str>,
+
+    /// Whether the process runs as PID 1
+    ///
+    /// `getpid() == 1` on Unix, typically the entrypoint of a minimal container without
+    /// an init process (no default signal handling, nothing reaping zombies).
+    /// Always `false` on other platforms and no_std targets.
+    pub is_pid1: bool,
+}
+
+impl SystemInfo {
+    /// Compares the selection-relevant hardware of two snapshots
+    ///
+    /// Reports OS, core count and memory size changes from `self` (before) to
+    /// `other` (after); point-in-time values such as available memory and free swap
+    /// are ignored, since they change all the time. See
+    /// [`environment_changed_since_init()`] for the common use.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let before = auto_allocator::get_allocator_info().system_info.clone();
+    /// let mut after = before.clone();
+    /// after.cpu_cores = before.cpu_cores * 2;
+    ///
+    /// let diff = before.diff(&after);
+    /// assert_eq!(diff.cpu_cores, Some((before.cpu_cores, after.cpu_cores)));
+    /// assert!(before.diff(&before).is_empty());
+    /// ```
+    pub fn diff(&self, other: &SystemInfo) -> SystemInfoDiff {
+        fn changed<T: PartialEq + Copy>(before: T, after: T) -> Option<(T, T)> {
+            (before != after).then_some((before, after))
+        }
+        SystemInfoDiff {
+            os_changed: self.os_type != other.os_type,
+            cpu_cores: changed(self.cpu_cores, other.cpu_cores),
+            total_memory_bytes: changed(self.total_memory_bytes, other.total_memory_bytes),
+            effective_memory_bytes: changed(self.effective_memory_bytes, other.effective_memory_bytes),
+        }
+    }
+}
+
+/// Hardware changes between two [`SystemInfo`] snapshots, from [`SystemInfo::diff()`]
+///
+/// Changed values are `Some((before, after))`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SystemInfoDiff {
+    /// Whether `os_type` differs
+    pub os_changed: bool,
+
+    /// Effective CPU core count, when it changed (hotplug, a new cgroup CPU quota, another host)
+    pub cpu_cores: Option<(usize, usize)>,
+
+    /// Total memory in bytes, when it changed
+    pub total_memory_bytes: Option<(u64, u64)>,
+
+    /// Memory available to the process in bytes, when it changed (e.g. a resized cgroup limit)
+    pub effective_memory_bytes: Option<(u64, u64)>,
+}
+
+impl SystemInfoDiff {
+    /// Whether nothing selection-relevant changed
+    pub fn is_empty(&self) -> bool {
+        *self == SystemInfoDiff::default()
+    }
+}
+
+/// Linux transparent huge page policy, see [`SystemInfo::thp_mode`]
+///
+/// # Example
+///
+/// ```rust
+/// use auto_allocator::ThpMode;
+///
+/// match auto_allocator::get_allocator_info().system_info.thp_mode {
+///     Some(ThpMode::Never) => println!("huge pages disabled by the kernel"),
+///     Some(mode) => println!("THP: {:?}", mode),
+///     None => println!("THP not supported here"),
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ThpMode {
+    /// Huge pages back all anonymous memory where possible
+    Always,
+    /// Only regions marked with `madvise(MADV_HUGEPAGE)` (how mimalloc requests them)
+    Madvise,
+    /// Huge pages are disabled; the `huge-pages` feature has no effect
+    Never,
+}
+
+/// Byte order of the build target, see [`SystemInfo::endian`]
+///
+/// # Example
+///
+/// ```rust
+/// use auto_allocator::Endianness;
+///
+/// if auto_allocator::get_allocator_info().system_info.endian == Endianness::Big {
+///     println!("big-endian target: swap bytes when reading little-endian files");
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Endianness {
+    /// Least significant byte first (x86, most ARM and RISC-V targets, wasm32)
+    Little,
+    /// Most significant byte first (s390x, big-endian PowerPC and MIPS)
+    Big,
+}
+
+impl Endianness {
+    /// Byte order of the target this crate was compiled for
+    pub const fn native() -> Endianness {
+        if cfg!(target_endian = "big") {
+            Endianness::Big
+        } else {
+            Endianness::Little
+        }
+    }
+
+    /// Lowercase name, as in `cfg(target_endian = "...")`
+    pub const fn name(self) -> &'static str {
+        match self {
+            Endianness::Little => "little",
+            Endianness::Big => "big",
+        }
+    }
+}
+
+/// CPU cache sizes in bytes
+///
+/// Read from `/sys/devices/system/cpu/cpu0/cache/` on Linux/Android, `hw.l*cachesize`
+/// sysctls on macOS and `GetLogicalProcessorInformation()` on Windows.
+/// Each field is `None` when unavailable (always on no_std and WASM).
+///
+/// # Example
+///
+/// ```rust
+/// let caches = auto_allocator::get_allocator_info().system_info.cache_sizes;
+/// if let Some(l2) = caches.l2 {
+///     println!("L2: {}", auto_allocator::format_memory_size(l2));
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheSizes {
+    /// L1 data cache size (per core)
+    pub l1d: Option<u64>,
+    /// L2 cache size
+    pub l2: Option<u64>,
+    /// L3 cache size (usually shared)
+    pub l3: Option<u64>,
+}
+
+/// Total and available physical memory read together, returned by [`memory_snapshot()`]
+///
+/// Both values come from one platform call where there is one (`/proc/meminfo` on
+/// Linux, `GlobalMemoryStatusEx()` on Windows), so `available_bytes` never exceeds
+/// `total_bytes`. `available_bytes` is `0` when it can't be detected.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MemorySnapshot {
+    /// Total physical memory in bytes
+    pub total_bytes: u64,
+    /// Physical memory currently available in bytes
+    pub available_bytes: u64,
+}
+
+/// CPU SIMD capability flags
+///
+/// Populated via `is_x86_feature_detected!` / `is_aarch64_feature_detected!` on std targets,
+/// and from compile-time `target_feature` flags in no_std environments.
+/// Flags that do not apply to the current architecture are always `false`.
+///
+/// # Example
+///
+/// ```rust
+/// use auto_allocator;
+///
+/// let features = auto_allocator::get_allocator_info().system_info.cpu_features;
+/// if features.avx2 {
+///     println!("AVX2 available for wide memory copies");
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CpuFeatures {
+    /// x86 SSE2 (baseline on x86_64)
+    pub sse2: bool,
+
+    /// x86 AVX
+    pub avx: bool,
+
+    /// x86 AVX2
+    pub avx2: bool,
+
+    /// x86 AVX-512 Foundation
+    pub avx512f: bool,
+
+    /// ARM NEON (Advanced SIMD)
+    pub neon: bool,
+}
+
+/// Structured allocator selection reason
+///
+/// Machine-readable counterpart of [`AllocatorInfo::reason`]. The `Display`
+/// implementation produces the human-readable reason string.
+///
+/// # Example
+///
+/// ```rust
+/// use auto_allocator::SelectionReason;
+///
+/// let info = auto_allocator::get_allocator_info();
+/// match info.reason_detail {
+///     SelectionReason::MultiCore { cores, total_memory_bytes, .. } => {
+///         println!("{} cores, {} bytes", cores, total_memory_bytes);
+///     }
+///     SelectionReason::Debug => println!("debug build - rebuild with --release"),
+///     other => println!("{}", other),
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SelectionReason {
+    /// Debug build - system allocator for fast compilation
+    Debug,
+
+    /// WASM environment - system allocator for browser compatibility
+    Wasm,
+
+    /// Embedded (`target_os = "none"`) - embedded-alloc
+    Embedded,
+
+    /// Platform with a superior native allocator (Android, iOS, BSD, Solaris, QNX, Haiku)
+    PlatformPolicy {
+        /// Operating system whose policy applied, e.g. "android"
+        os: &'static str,
+    },
+
+    /// Multi-core system - high-performance allocator selected by runtime detection
+    MultiCore {
+        /// Effective CPU core count (after any cgroup CPU quota)
+        cores: usize,
+        /// Detected total memory in bytes
+        total_memory_bytes: u64,
+        /// Whether a cgroup CPU quota reduced `cores` below the online core count
+        cpu_quota_limited: bool,
+    },
+
+    /// High-performance platform that fell back to the system allocator
+    /// (single-core or mimalloc unavailable)
+    Fallback {
+        /// Effective CPU core count (after any cgroup CPU quota)
+        cores: usize,
+        /// Detected total memory in bytes
+        total_memory_bytes: u64,
+        /// Whether a cgroup CPU quota reduced `cores` below the online core count
+        cpu_quota_limited: bool,
+    },
+
+    /// Effective memory is too small for mimalloc (under 512MB, or under 2GB
+    /// with the `LowMemory` profile), so the system allocator was chosen
+    LowMemory {
+        /// Memory available to the process in bytes, see `SystemInfo::effective_memory_bytes`
+        effective_memory_bytes: u64,
+    },
+
+    /// `Footprint` profile chose the system allocator to minimize committed memory
+    Footprint,
+
+    /// User-supplied allocator registered with [`register_custom_allocator()`]
+    Custom,
+
+    /// First available entry of the user's preference order
+    /// (`AUTO_ALLOCATOR_PREFER` / [`set_allocator_preference()`])
+    Preference,
+
+    /// The preferred allocator was on the deny list; the next allowed one was selected
+    Denied {
+        /// Name of the skipped allocator, e.g. "mimalloc-secure"
+        skipped: &'static str,
+    },
+
+    /// Explicit user override of the default choice
+    Override {
+        /// What requested the override, e.g. "android-mimalloc feature"
+        source: &'static str,
+    },
+
+    /// mimalloc was chosen but couldn't allocate at startup (e.g. a seccomp or
+    /// address-space limit blocked its first arena), so the system allocator took over
+    ProbeFailed,
+}
+
+impl SelectionReason {
+    /// Why a runtime-detected platform ended up on the system allocator, if it did
+    ///
+    /// `None` for every other decision, including the system allocator chosen by
+    /// design (debug, WASM, platform policy, `Footprint` profile, deny list).
+    pub const fn fallback_reason(&self) -> Option<FallbackReason> {
+        match self {
+            SelectionReason::Fallback { cores, .. } if *cores < MIN_MIMALLOC_CORES => {
+                Some(FallbackReason::SingleCore)
+            }
+            SelectionReason::Fallback { .. } => Some(FallbackReason::MimallocUnavailable),
+            SelectionReason::LowMemory { .. } => Some(FallbackReason::LowMemory),
+            SelectionReason::ProbeFailed => Some(FallbackReason::ProbeFailed),
+            _ => None,
+        }
+    }
+
+    /// Fixed description for `AllocatorInfo::reason` under `no-log`, without formatting
+    #[cfg(all(feature = "no-log", not(target_os = "none")))]
+    const fn summary(&self) -> &'static str {
+        match self {
+            SelectionReason::Debug => "debug build - compile-time selected",
+            SelectionReason::Wasm => "WASM environment - compile-time selected",
+            SelectionReason::Embedded => "embedded platform - compile-time selected",
+            SelectionReason::PlatformPolicy { .. } => "platform policy - native system allocator",
+            SelectionReason::MultiCore { .. } => "high-performance multi-threaded environment - runtime detected",
+            SelectionReason::Fallback { .. } => "runtime fallback - system allocator",
+            SelectionReason::LowMemory { .. } => "low memory - system allocator on constrained system",
+            SelectionReason::Footprint => "footprint profile - system allocator keeps per-process RSS minimal",
+            SelectionReason::Preference => "first available entry in user preference order",
+            SelectionReason::Denied { .. } => "deny list - next allowed allocator selected",
+            SelectionReason::Custom => "custom allocator - registered via register_custom_allocator()",
+            SelectionReason::Override { .. } => "explicit override - compile-time selected",
+            SelectionReason::ProbeFailed => "mimalloc init probe failed - using system",
+        }
+    }
+}
+
+/// Why a platform that runtime-detects its allocator fell back to the system allocator
+///
+/// Returned by [`fallback_reason()`]. The cases call for different responses: a
+/// single-core VM is expected, a build without mimalloc is a packaging mistake, and
+/// a failed probe means the environment (seccomp, address-space limits) is blocking it.
+///
+/// # Example
+///
+/// ```rust
+/// use auto_allocator::FallbackReason;
+///
+/// match auto_allocator::fallback_reason() {
+///     Some(FallbackReason::ProbeFailed) => eprintln!("mimalloc failed to initialize - check sandbox limits"),
+///     Some(reason) => println!("system allocator: {}", reason),
+///     None => {}
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FallbackReason {
+    /// Fewer cores than mimalloc needs (2, or 1 with `allow-single-core-mimalloc`)
+    SingleCore,
+
+    /// mimalloc isn't compiled in (default features disabled)
+    MimallocUnavailable,
+
+    /// Effective memory is below mimalloc's threshold for the profile
+    LowMemory,
+
+    /// mimalloc was selected but couldn't allocate at startup
+    ProbeFailed,
+}
+
+impl core::fmt::Display for FallbackReason {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(match self {
+            FallbackReason::SingleCore => "single-core",
+            FallbackReason::MimallocUnavailable => "mimalloc unavailable",
+            FallbackReason::LowMemory => "low memory",
+            FallbackReason::ProbeFailed => "mimalloc init probe failed",
+        })
+    }
+}
+
+/// Allocator selection profile
+///
+/// Tunes automatic selection toward a workload's priorities. Set through the
+/// `AUTO_ALLOCATOR_PROFILE` environment variable (`throughput`, `low-latency`,
+/// `low-memory`, `footprint`) or [`set_allocator_profile()`]. The default is [`AllocatorProfile::Throughput`].
+///
+/// # Example
+///
+/// ```rust
+/// use auto_allocator::AllocatorProfile;
+///
+/// assert_eq!(AllocatorProfile::from_name("low-memory"), Some(AllocatorProfile::LowMemory));
+/// println!("Active profile: {}", auto_allocator::get_allocator_profile().name());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AllocatorProfile {
+    /// Maximum allocation throughput - the standard selection rules
+    #[default]
+    Throughput,
+
+    /// Predictable allocation latency - selects like `Throughput` (mimalloc on multi-core systems)
+    ///
+    /// When mimalloc is selected it is tuned to keep page faults and `madvise` calls off
+    /// the allocation path: eager commit from each thread's first segment, and freed pages
+    /// stay committed (no page or segment reset). Costs RSS in exchange for steadier latency.
+    LowLatency,
+
+    /// Minimal memory footprint - prefers the system allocator on constrained systems
+    /// (less than 2GB total RAM), avoiding mimalloc's segment reservations
+    LowMemory,
+
+    /// Lowest per-process RSS regardless of hardware - for hosts packed with many processes
+    ///
+    /// Selects the system allocator on every runtime-detected platform. If mimalloc is still
+    /// chosen (listed first in the preference order, or a compile-time opt-in such as
+    /// `android-mimalloc`), it is tuned to return freed memory immediately: reset delay 0,
+    /// page and segment reset on, no eager commit.
+    Footprint,
+}
+
+impl AllocatorProfile {
+    /// Parses a profile name as accepted by `AUTO_ALLOCATOR_PROFILE` (case-insensitive)
+    ///
+    /// Accepts `throughput`, `low-latency` / `latency`, `low-memory` / `memory`, and `footprint`.
+    pub fn from_name(name: &str) -> Option<AllocatorProfile> {
+        let name = name.trim();
+        if name.eq_ignore_ascii_case("throughput") {
+            Some(AllocatorProfile::Throughput)
+        } else if name.eq_ignore_ascii_case("low-latency") || name.eq_ignore_ascii_case("latency") {
+            Some(AllocatorProfile::LowLatency)
+        } else if name.eq_ignore_ascii_case("low-memory") || name.eq_ignore_ascii_case("memory") {
+            Some(AllocatorProfile::LowMemory)
+        } else if name.eq_ignore_ascii_case("footprint") {
+            Some(AllocatorProfile::Footprint)
+        } else {
+            None
+        }
+    }
+
+    /// Canonical profile name, as accepted by [`AllocatorProfile::from_name()`]
+    pub const fn name(self) -> &'static str {
+        match self {
+            AllocatorProfile::Throughput => "throughput",
+            AllocatorProfile::LowLatency => "low-latency",
+            AllocatorProfile::LowMemory => "low-memory",
+            AllocatorProfile::Footprint => "footprint",
+        }
+    }
+}
+
+impl core::fmt::Display for SelectionReason {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            SelectionReason::Debug => f.write_str("debug build - compile-time selected"),
+            SelectionReason::Wasm => f.write_str("WASM environment - compile-time selected"),
+            SelectionReason::Embedded => f.write_str("embedded platform - compile-time selected"),
+            SelectionReason::PlatformPolicy { os } => match *os {
+                "android" => f.write_str("Android platform - Scudo allocator (security-first, use-after-free protection)"),
+                "ios" => f.write_str("iOS platform - libmalloc allocator (Apple-optimized, memory pressure handling)"),
+                "freebsd" | "netbsd" => f.write_str("BSD platform - native jemalloc (highly optimized, deep system integration)"),
+                "openbsd" => f.write_str("OpenBSD platform - security-hardened allocator (exploit mitigation, aggressive hardening)"),
+                "solaris" | "illumos" if libumem_linked() => f.write_str("Solaris platform - libumem allocator (NUMA-aware, enterprise-grade performance)"),
+                "solaris" | "illumos" => f.write_str("Solaris platform - libc malloc (libumem not linked - link -lumem or LD_PRELOAD=libumem.so.1 for its scalability)"),
+                "nto" => f.write_str("QNX Neutrino platform - native allocator (real-time friendly, integrated with the QNX memory manager)"),
+                "haiku" => f.write_str("Haiku platform - libroot allocator (Hoard-derived, tuned for Haiku's threading)"),
+                other => write!(f, "{} platform - native system allocator", other),
+            },
+            SelectionReason::MultiCore { cores, total_memory_bytes, cpu_quota_limited } => write!(
+                f,
+                "high-performance multi-threaded environment - runtime detected ({} cores{}, {} total RAM)",
+                cores,
+                if *cpu_quota_limited { " effective by cgroup CPU quota" } else { "" },
+                format_memory_size(*total_memory_bytes)
+            ),
+            SelectionReason::Fallback { cores, total_memory_bytes, cpu_quota_limited } => write!(
+                f,
+                "runtime fallback - {} ({} cores{}, {} total RAM)",
+                self.fallback_reason().unwrap_or(FallbackReason::MimallocUnavailable),
+                cores,
+                if *cpu_quota_limited { " effective by cgroup CPU quota" } else { "" },
+                format_memory_size(*total_memory_bytes)
+            ),
+            SelectionReason::LowMemory { effective_memory_bytes } => write!(
+                f,
+                "low memory - system allocator on constrained system ({} effective RAM)",
+                format_memory_size(*effective_memory_bytes)
+            ),
+            SelectionReason::Footprint => f.write_str(
+                "footprint profile - system allocator keeps per-process RSS minimal (no mimalloc segment reservations)",
+            ),
+            SelectionReason::Preference => {
+                f.write_str("first available entry in user preference order")
+            }
+            SelectionReason::Denied { skipped } => write!(
+                f,
+                "{} skipped by deny list - next allowed allocator selected",
+                skipped
+            ),
+            SelectionReason::Custom => f.write_str("custom allocator - registered via register_custom_allocator()"),
+            SelectionReason::Override { source } => write!(f, "{} - compile-time selected", source),
+            SelectionReason::ProbeFailed => write!(f, "{} - using system", FallbackReason::ProbeFailed),
+        }
+    }
 }
 
 // ========== Memory Formatting Utilities ==========
@@ -299,8 +992,9 @@ pub struct SystemInfo {
 ///
 /// # Precision Notes
 ///
-/// For performance considerations, decimal places are limited to 1 digit. For scenarios
-/// requiring higher precision, it is recommended to calculate directly using byte counts.
+/// For performance considerations, decimal places are limited to 1 digit, rounded to
+/// nearest (`1932735283` bytes is `1.8GB`). For scenarios requiring higher precision,
+/// it is recommended to calculate directly using byte counts.
 #[cfg(not(target_os = "none"))]
 pub fn format_memory_size(bytes: u64) -> String {
     use std::format;
@@ -343,21 +1037,87 @@ pub fn format_memory_size(bytes: u64) -> String {
         format!("{}B", bytes)
     } else {
         let shift = unit_index * 10; // Each unit is 2^10
-        let value = bytes >> shift;
+        let mut value = bytes >> shift;
         let remainder = bytes & ((1u64 << shift) - 1);
 
-        // Calculate decimal part (retain only 1 decimal place for performance)
-        if remainder == 0 {
+        // Decimal part rounded to nearest (add half a tenth before the shift)
+        let mut fraction = (remainder * 10 + (1u64 << (shift - 1))) >> shift;
+        if fraction == 10 {
+            value += 1;
+            fraction = 0;
+        }
+
+        // Rounding up to 1024 of a unit carries into the next one (1023.96KB -> 1MB)
+        let mut unit_index = unit_index;
+        if value == 1024 && unit_index < UNITS.len() - 1 {
+            value = 1;
+            unit_index += 1;
+        }
+
+        if fraction == 0 {
             format!("{}{}", value, UNITS[unit_index])
         } else {
-            let fraction = (remainder * 10) >> shift;
-            if fraction == 0 {
-                format!("{}{}", value, UNITS[unit_index])
-            } else {
-                format!("{}.{}{}", value, fraction, UNITS[unit_index])
+            format!("{}.{}{}", value, fraction, UNITS[unit_index])
+        }
+    }
+}
+
+/// Parses a memory size such as `"512MB"`, `"1.5GB"`, `"2G"` or `"1048576"` into bytes
+///
+/// The inverse of [`format_memory_size()`]: units are binary (1KB = 1024 bytes) and
+/// case-insensitive, the `B` / `iB` suffix is optional and whitespace may separate the
+/// number from the unit, so `"4GB"`, `"4 GiB"` and `"4g"` are all `4 << 30`. A plain
+/// number is bytes. Returns `None` for anything else - including fractional bytes and
+/// values that overflow `u64`. Allocation-free and available in no_std.
+///
+/// # Example
+///
+/// ```rust
+/// assert_eq!(auto_allocator::parse_memory_size("512MB"), Some(512 << 20));
+/// assert_eq!(auto_allocator::parse_memory_size("1.5 GiB"), Some(3 << 29));
+/// assert_eq!(auto_allocator::parse_memory_size("4096"), Some(4096));
+/// assert_eq!(auto_allocator::parse_memory_size("lots"), None);
+/// ```
+pub fn parse_memory_size(text: &str) -> Option<u64> {
+    let text = text.trim();
+    let number_end = text
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(text.len());
+    let (number, unit) = text.split_at(number_end);
+
+    let unit = unit.trim_start();
+    let shift = match unit.as_bytes().first().map(u8::to_ascii_lowercase) {
+        None => 0,
+        Some(b'b') if unit.len() == 1 => 0,
+        Some(prefix) => {
+            // `get` rather than slicing: a multi-byte first character is not a unit
+            let suffix = unit.get(1..)?;
+            if !(suffix.is_empty() || suffix.eq_ignore_ascii_case("b") || suffix.eq_ignore_ascii_case("ib")) {
+                return None;
+            }
+            match prefix {
+                b'k' => 10,
+                b'm' => 20,
+                b'g' => 30,
+                b't' => 40,
+                b'p' => 50,
+                _ => return None,
             }
         }
+    };
+
+    let (whole, fraction) = number.split_once('.').unwrap_or((number, ""));
+    if whole.is_empty() || (shift == 0 && number.contains('.')) || fraction.contains('.') {
+        return None;
     }
+    let mut bytes = (whole.parse::<u64>().ok()? as u128) << shift;
+    if !fraction.is_empty() {
+        // Up to 9 decimals; more precision than that doesn't change a memory size
+        let digits = &fraction[..fraction.len().min(9)];
+        let scale = 10u128.pow(digits.len() as u32);
+        bytes += ((digits.parse::<u64>().ok()? as u128) << shift) / scale;
+    }
+    u64::try_from(bytes).ok()
 }
 
 /// Simplified memory size formatting for no_std environments
@@ -389,541 +1149,5022 @@ const fn is_embedded_target() -> bool {
 }
 
 /// Checks if mimalloc can be used on this platform
+///
+/// Linux covers both glibc and musl targets: mimalloc builds against musl and is
+/// used for musl release builds (the common case for static binaries).
+/// Android and FreeBSD/NetBSD are only included when their opt-in features
+/// (`android-mimalloc` / `bsd-mimalloc`) are enabled. build.rs encodes these rules
+/// as the `auto_allocator_mimalloc` cfg.
 const fn can_use_mimalloc() -> bool {
-    cfg!(all(
-        feature = "_mimalloc",
-        any(target_os = "windows", target_os = "macos", target_os = "linux"),
-        not(target_arch = "wasm32"),
-        not(debug_assertions)
-    ))
+    cfg!(all(auto_allocator_mimalloc, feature = "_mimalloc"))
 }
 
 /// Checks if secure mimalloc can be used on this platform
 const fn can_use_mimalloc_secure() -> bool {
-    cfg!(all(
-        feature = "_mimalloc_secure",
-        any(target_os = "windows", target_os = "macos", target_os = "linux"),
-        not(target_arch = "wasm32"),
-        not(debug_assertions)
-    ))
+    cfg!(all(auto_allocator_mimalloc, feature = "_mimalloc_secure"))
 }
 
+/// Describes the opt-in feature that moved a native-allocator platform onto mimalloc
+///
+/// Returns `None` unless `android-mimalloc` (Android) or `bsd-mimalloc` (FreeBSD/NetBSD)
+/// is enabled for the current target. Used as [`SelectionReason::Override`] source.
+const fn mimalloc_opt_in_override() -> Option<&'static str> {
+    if cfg!(all(target_os = "android", feature = "android-mimalloc")) {
+        Some("Android opt-in via android-mimalloc feature (overrides Scudo)")
+    } else if cfg!(all(any(target_os = "freebsd", target_os = "netbsd"), feature = "bsd-mimalloc")) {
+        Some("BSD opt-in via bsd-mimalloc feature (overrides native jemalloc)")
+    } else {
+        None
+    }
+}
 
+/// Checks if mimalloc was explicitly opted into on a native-allocator platform
+const fn is_mimalloc_opt_in() -> bool {
+    mimalloc_opt_in_override().is_some()
+}
 
-// ========== Runtime Allocator Selection ==========
 
-// Global state for allocator selection and logging  
-// ID mapping: 0=uninitialized, 1=system, 2=mimalloc, 3=jemalloc, 4=embedded, 5=mimalloc-secure
-static RUNTIME_ALLOCATOR_ID: AtomicU8 = AtomicU8::new(0);
-#[cfg(not(target_os = "none"))]
-static ALLOCATOR_LOGGED: AtomicBool = AtomicBool::new(false);
-#[cfg(not(target_os = "none"))]
-static LOG_FLUSHED: AtomicBool = AtomicBool::new(false);
 
-/// Returns allocator ID for platforms with compile-time determinable choices
-///
-/// Returns `None` for platforms requiring runtime hardware detection (desktop systems).
-/// This optimization avoids unnecessary runtime checks for 90% of platforms.
-const fn get_compile_time_allocator() -> Option<u8> {
-    if is_embedded_target() {
-        return Some(4); // embedded-alloc
-    }
+// ========== Selection Profile ==========
 
-    if cfg!(target_arch = "wasm32") {
-        return Some(1); // system
-    }
+// Programmatic profile: 0 = unset (use AUTO_ALLOCATOR_PROFILE), otherwise profile index + 1
+static PROFILE_OVERRIDE: AtomicU8 = AtomicU8::new(0);
 
-    if cfg!(debug_assertions) {
-        return Some(1); // system (debug builds)
-    }
+/// Systems with less effective memory count as constrained for [`AllocatorProfile::LowMemory`]
+const LOW_MEMORY_PROFILE_THRESHOLD_BYTES: u64 = 2u64 << 30;
 
-    // Platforms with superior native allocators
-    if cfg!(target_os = "android") {
-        return Some(1); // Scudo
-    }
+/// Below this much effective memory mimalloc is skipped under every profile
+const MIN_MIMALLOC_MEMORY_BYTES: u64 = 512u64 << 20;
 
-    if cfg!(target_os = "ios") {
-        return Some(1); // libmalloc
+/// Fewest cores for mimalloc; the `allow-single-core-mimalloc` feature lowers it to 1
+const MIN_MIMALLOC_CORES: usize = if cfg!(feature = "allow-single-core-mimalloc") { 1 } else { 2 };
+
+/// Whether the system allocator should be used to spare memory
+const fn is_memory_constrained(profile: AllocatorProfile, effective_memory_bytes: u64) -> bool {
+    effective_memory_bytes < MIN_MIMALLOC_MEMORY_BYTES
+        || (matches!(profile, AllocatorProfile::LowMemory)
+            && effective_memory_bytes < LOW_MEMORY_PROFILE_THRESHOLD_BYTES)
+}
+
+/// Sets the allocator selection profile, taking precedence over `AUTO_ALLOCATOR_PROFILE`
+///
+/// Selection happens on the first allocation, and the Rust runtime allocates
+/// before `main` runs - so in std binaries this only influences selection when
+/// called from a pre-main constructor. Prefer the `AUTO_ALLOCATOR_PROFILE`
+/// environment variable otherwise. Later calls still affect
+/// [`get_recommended_allocator()`] and [`check_allocator_optimization()`].
+///
+/// Returns `true` if the profile is guaranteed to be seen by the initial selection,
+/// `false` if selection had already started (see [Configuration Timing](crate#configuration-timing)).
+pub fn set_allocator_profile(profile: AllocatorProfile) -> bool {
+    write_selection_config(true, || store_profile(profile))
+}
+
+fn store_profile(profile: AllocatorProfile) {
+    let index = match profile {
+        AllocatorProfile::Throughput => 1,
+        AllocatorProfile::LowLatency => 2,
+        AllocatorProfile::LowMemory => 3,
+        AllocatorProfile::Footprint => 4,
+    };
+    PROFILE_OVERRIDE.store(index, Ordering::Release);
+}
+
+// Selection settings (profile, deny list, preference) vs. the first selection:
+// setters hold CONFIG_WRITING while storing, selection moves OPEN -> SEALED before
+// reading them, so a setter that finished while OPEN is always seen by selection
+static SELECTION_CONFIG_STATE: AtomicU8 = AtomicU8::new(CONFIG_OPEN);
+const CONFIG_OPEN: u8 = 0;
+const CONFIG_WRITING: u8 = 1;
+const CONFIG_SEALED: u8 = 2;
+
+/// Runs `store` under the config state; returns whether it happened before selection
+///
+/// After sealing, `store` still runs when `when_sealed` is set (the value keeps
+/// feeding recommendations) and the result is `false`. Never allocates.
+fn write_selection_config(when_sealed: bool, store: impl FnOnce()) -> bool {
+    loop {
+        match SELECTION_CONFIG_STATE.compare_exchange_weak(
+            CONFIG_OPEN,
+            CONFIG_WRITING,
+            Ordering::Acquire,
+            Ordering::Acquire,
+        ) {
+            Ok(_) => {
+                store();
+                SELECTION_CONFIG_STATE.store(CONFIG_OPEN, Ordering::Release);
+                return true;
+            }
+            Err(CONFIG_SEALED) => {
+                if when_sealed {
+                    store();
+                }
+                return false;
+            }
+            Err(_) => core::hint::spin_loop(),
+        }
     }
+}
 
-    if cfg!(any(target_os = "freebsd", target_os = "netbsd", target_os = "openbsd")) {
-        return Some(1); // native jemalloc/security-hardened
+/// Marks the selection settings as read by the first selection, waiting out a concurrent setter
+fn seal_selection_config() {
+    loop {
+        match SELECTION_CONFIG_STATE.compare_exchange_weak(
+            CONFIG_OPEN,
+            CONFIG_SEALED,
+            Ordering::AcqRel,
+            Ordering::Acquire,
+        ) {
+            Ok(_) | Err(CONFIG_SEALED) => return,
+            Err(_) => core::hint::spin_loop(),
+        }
     }
+}
 
-    if cfg!(any(target_os = "solaris", target_os = "illumos")) {
-        return Some(1); // libumem
+/// Returns the active allocator selection profile
+///
+/// Resolution order: [`set_allocator_profile()`] > `AUTO_ALLOCATOR_PROFILE` > `Throughput`.
+/// Allocation-free, so it is safe to call from the allocator selection path.
+pub fn get_allocator_profile() -> AllocatorProfile {
+    match PROFILE_OVERRIDE.load(Ordering::Acquire) {
+        1 => return AllocatorProfile::Throughput,
+        2 => return AllocatorProfile::LowLatency,
+        3 => return AllocatorProfile::LowMemory,
+        4 => return AllocatorProfile::Footprint,
+        _ => {}
     }
 
-    None // High-performance platforms need runtime detection
+    let mut buf = [0u8; 32];
+    read_env_no_alloc(c"AUTO_ALLOCATOR_PROFILE", &mut buf)
+        .and_then(|value| core::str::from_utf8(value).ok())
+        .and_then(AllocatorProfile::from_name)
+        .unwrap_or_default()
 }
 
-/// Selects allocator using compile-time rules and runtime hardware detection
-fn select_allocator_by_hardware() -> u8 {
-    if let Some(allocator_id) = get_compile_time_allocator() {
-        return allocator_id;
+/// Reads an environment variable without allocating (safe inside the global allocator)
+///
+/// Copies the value into `buf` and returns the filled part, or `None` if unset or too long.
+#[cfg(unix)]
+fn read_env_no_alloc<'a>(name: &core::ffi::CStr, buf: &'a mut [u8]) -> Option<&'a [u8]> {
+    #[cfg(auto_allocator_no_libc)]
+    let value = raw_syscall::getenv(name)?;
+    #[cfg(not(auto_allocator_no_libc))]
+    let value = unsafe {
+        let ptr = libc::getenv(name.as_ptr());
+        if ptr.is_null() {
+            return None;
+        }
+        core::ffi::CStr::from_ptr(ptr).to_bytes()
+    };
+    let target = buf.get_mut(..value.len())?;
+    target.copy_from_slice(value);
+    Some(target)
+}
+
+#[cfg(windows)]
+fn read_env_no_alloc<'a>(name: &core::ffi::CStr, buf: &'a mut [u8]) -> Option<&'a [u8]> {
+    use winapi::um::processenv::GetEnvironmentVariableA;
+    let len = unsafe { GetEnvironmentVariableA(name.as_ptr(), buf.as_mut_ptr() as *mut _, buf.len() as u32) } as usize;
+    // 0 = unset, >= buf.len() = required size when the buffer is too small
+    if len == 0 || len >= buf.len() {
+        return None;
     }
+    Some(&buf[..len])
+}
 
-    // Only high-performance platforms reach here - need CPU core detection
-    // Use zero-allocation CPU detection to avoid infinite recursion
-    let cpu_cores = get_cpu_cores_safe();
+#[cfg(not(any(unix, windows)))]
+fn read_env_no_alloc<'a>(_name: &core::ffi::CStr, _buf: &'a mut [u8]) -> Option<&'a [u8]> {
+    None
+}
 
-    // Multi-core systems: prefer mimalloc (secure > regular > system)
-    if cpu_cores >= 2 && can_use_mimalloc_secure() {
-        return 5; // mimalloc-secure
-    }
+/// `AUTO_ALLOCATOR_TOTAL_MEMORY`, e.g. `512MB`: replaces the detected memory size
+///
+/// Takes precedence over both physical memory and the cgroup limit, for orchestrators
+/// that only pass the limit through the environment and for testing the low-memory
+/// rules. Read without allocating; invalid or zero values are ignored.
+fn total_memory_override() -> Option<u64> {
+    let mut buf = [0u8; 32];
+    read_env_no_alloc(c"AUTO_ALLOCATOR_TOTAL_MEMORY", &mut buf)
+        .and_then(|value| core::str::from_utf8(value).ok())
+        .and_then(parse_memory_size)
+        .filter(|&bytes| bytes > 0)
+}
 
-    // Check if mimalloc is available
-    // Since build script ensures compatibility, mimalloc is available if feature is enabled
-    if cpu_cores >= 2 && can_use_mimalloc() {
-        return 2; // mimalloc
-    }
+// ========== Allocator Deny List ==========
 
-    1 // system (single-core or all high-performance allocators unavailable)
+// Programmatic deny list: 0 = unset (use AUTO_ALLOCATOR_DENY), otherwise DENY_LIST_SET | (1 << id)...
+static DENY_OVERRIDE: AtomicU16 = AtomicU16::new(0);
+const DENY_LIST_SET: u16 = 1 << 15;
+
+/// Sets allocators that must never be selected, taking precedence over `AUTO_ALLOCATOR_DENY`
+///
+/// A denied allocator falls back to the next allowed choice (mimalloc-secure → mimalloc →
+/// system). The system allocator is the final fallback and can't be denied; only
+/// [`AllocatorType::Mimalloc`] and [`AllocatorType::MimallocSecure`] have an effect.
+/// Pass an empty slice to deny nothing.
+///
+/// Like [`set_allocator_profile()`], this only influences the initial selection when
+/// called before the first allocation (a pre-main constructor in std binaries). The
+/// environment variable is the practical route:
+///
+/// ```bash
+/// AUTO_ALLOCATOR_DENY=mimalloc-secure,mimalloc ./my-service
+/// ```
+///
+/// Returns `true` if the initial selection is guaranteed to see the list, `false` if
+/// it had already started (the list still applies to recommendations).
+pub fn set_denied_allocators(denied: &[AllocatorType]) -> bool {
+    let mask = DENY_LIST_SET | deny_mask_for(denied);
+    write_selection_config(true, || DENY_OVERRIDE.store(mask, Ordering::Release))
 }
 
-/// Get CPU core count without allocating memory (to avoid infinite recursion)
-fn get_cpu_cores_safe() -> usize {
-    #[cfg(unix)]
-    {
-        // Use direct libc calls to avoid std allocation
-        unsafe {
-            let cores = libc::sysconf(libc::_SC_NPROCESSORS_ONLN);
-            if cores > 0 {
-                cores as usize
+/// Deny-list bitmask for `denied`, without the DENY_LIST_SET flag
+fn deny_mask_for(denied: &[AllocatorType]) -> u16 {
+    denied
+        .iter()
+        .fold(0, |mask, &allocator| mask | deny_bit(allocator.id()))
+}
+
+const fn deny_bit(allocator_id: u8) -> u16 {
+    match allocator_id {
+        2 | 5 => 1 << allocator_id,
+        _ => 0, // only mimalloc variants can be denied
+    }
+}
+
+/// Active deny list as a bitmask of allocator IDs, allocation-free
+fn denied_allocator_mask() -> u16 {
+    let mask = DENY_OVERRIDE.load(Ordering::Acquire);
+    if mask & DENY_LIST_SET != 0 {
+        return mask & !DENY_LIST_SET;
+    }
+
+    let mut buf = [0u8; 128];
+    let Some(value) = read_env_no_alloc(c"AUTO_ALLOCATOR_DENY", &mut buf) else {
+        return 0;
+    };
+    value
+        .split(|&b| b == b',')
+        .filter_map(|name| core::str::from_utf8(name).ok())
+        .map(str::trim)
+        .fold(0, |mask, name| {
+            if name.eq_ignore_ascii_case("mimalloc-secure") {
+                mask | deny_bit(5)
+            } else if name.eq_ignore_ascii_case("mimalloc") {
+                mask | deny_bit(2)
             } else {
-                1
+                mask
             }
-        }
-    }
-    
-    #[cfg(windows)]
-    {
-        // Windows: Use direct WinAPI to avoid std allocation
-        use winapi::um::sysinfoapi::{GetSystemInfo, SYSTEM_INFO};
-        unsafe {
-            let mut sysinfo: SYSTEM_INFO = std::mem::zeroed();
-            GetSystemInfo(&mut sysinfo);
-            sysinfo.dwNumberOfProcessors as usize
-        }
+        })
+}
+
+/// Walks the fallback chain (mimalloc-secure → mimalloc → system) past denied allocators
+const fn next_allowed_allocator(allocator_id: u8, denied_mask: u16) -> u8 {
+    let mut allocator_id = allocator_id;
+    if allocator_id == 5 && denied_mask & deny_bit(5) != 0 {
+        allocator_id = 2;
     }
-    
-    #[cfg(not(any(unix, windows)))]
-    {
-        // Fallback: assume multi-core for unknown platforms
-        4
+    if allocator_id == 2 && denied_mask & deny_bit(2) != 0 {
+        allocator_id = 1;
     }
+    allocator_id
 }
 
-// ========== Embedded Heap Configuration ==========
+// ========== Allocator Preference Order ==========
 
-// Embedded heap configuration for all no_std targets
-#[cfg(target_os = "none")]
-mod embedded_heap_config {
-    use embedded_alloc::Heap;
-    #[cfg(not(target_os = "none"))]
-    use once_cell::sync::Lazy;
+// Programmatic order: 0 = unset (use AUTO_ALLOCATOR_PREFER), otherwise
+// PREFERENCE_SET | one 4-bit allocator ID per entry, first entry in the lowest bits
+static PREFERENCE_OVERRIDE: AtomicU32 = AtomicU32::new(0);
+const PREFERENCE_SET: u32 = 1 << 31;
+const MAX_PREFERENCES: usize = 7;
 
-    // Architecture-specific heap sizes based on typical available memory
-    // These are conservative defaults that work well for most embedded applications
-    // Users can override by defining custom heap sizes in their own code
+/// Sets the order in which allocators are tried, replacing the default
+/// (mimalloc-secure > mimalloc > system) and taking precedence over `AUTO_ALLOCATOR_PREFER`
+///
+/// Selection picks the first entry that is available in this build
+/// ([`is_allocator_available()`]) and valid for the detected hardware - mimalloc
+/// variants need a multi-core system that isn't memory-constrained.
+/// If no entry qualifies, the default order applies. Compile-time decisions (debug
+/// builds, WASM, embedded, platform policy) are not affected. Pass an empty slice to
+/// restore the default order; entries beyond the seventh are ignored.
+///
+/// Only influences the initial selection when called before the first allocation
+/// (a pre-main constructor in std binaries); the environment variable is the practical route:
+///
+/// ```bash
+/// AUTO_ALLOCATOR_PREFER=mimalloc,jemalloc,system ./my-service
+/// ```
+///
+/// Unknown names in the variable (such as the not-yet-supported `jemalloc`) are skipped.
+///
+/// Returns `true` if the initial selection is guaranteed to see the order, `false` if
+/// it had already started (the order still applies to recommendations).
+pub fn set_allocator_preference(order: &[AllocatorType]) -> bool {
+    let packed = PREFERENCE_SET | pack_preference(order);
+    write_selection_config(true, || PREFERENCE_OVERRIDE.store(packed, Ordering::Release))
+}
 
-    #[cfg(target_arch = "avr")]
-    pub const HEAP_SIZE: usize = 512; // AVR (Arduino Uno): 2KB total, use 512B heap (25%)
+/// Packs up to MAX_PREFERENCES allocator IDs, first entry in the lowest bits, without the flag
+fn pack_preference(order: &[AllocatorType]) -> u32 {
+    order
+        .iter()
+        .take(MAX_PREFERENCES)
+        .enumerate()
+        .fold(0, |packed, (index, &allocator)| {
+            packed | (allocator.id() as u32) << (index * 4)
+        })
+}
 
-    #[cfg(target_arch = "msp430")]
-    pub const HEAP_SIZE: usize = 256; // MSP430: 1KB total, use 256B heap (25%)
+/// Reports whether an allocator is compiled into this build and usable on this target
+///
+/// Custom is available once [`register_custom_allocator()`] has succeeded.
+///
+/// # Example
+///
+/// ```rust
+/// use auto_allocator::{is_allocator_available, AllocatorType};
+///
+/// if is_allocator_available(AllocatorType::MimallocSecure) {
+///     println!("the secure feature is enabled for this target");
+/// }
+/// ```
+pub fn is_allocator_available(allocator_type: AllocatorType) -> bool {
+    match allocator_type {
+        AllocatorType::Custom => RUNTIME_ALLOCATOR_ID.load(Ordering::Acquire) == 6,
+        _ => has_dispatch_arm(allocator_type.id()),
+    }
+}
 
-    #[cfg(target_arch = "riscv32")]
-    pub const HEAP_SIZE: usize = 2048; // RISC-V 32-bit: typically 32KB+, use 2KB heap (6%)
+/// Active preference order, packed as in PREFERENCE_OVERRIDE without the flag; allocation-free
+fn preference_order() -> u32 {
+    let packed = PREFERENCE_OVERRIDE.load(Ordering::Acquire);
+    if packed & PREFERENCE_SET != 0 {
+        return packed & !PREFERENCE_SET;
+    }
 
-    #[cfg(target_arch = "riscv64")]
-    pub const HEAP_SIZE: usize = 4096; // RISC-V 64-bit: typically 128KB+, use 4KB heap (3%)
+    let mut buf = [0u8; 128];
+    let Some(value) = read_env_no_alloc(c"AUTO_ALLOCATOR_PREFER", &mut buf) else {
+        return 0;
+    };
+    value
+        .split(|&b| b == b',')
+        .filter_map(|name| core::str::from_utf8(name).ok())
+        .filter_map(|name| allocator_id_for_name(name.trim()))
+        .take(MAX_PREFERENCES)
+        .enumerate()
+        .fold(0, |packed, (index, allocator_id)| {
+            packed | (allocator_id as u32) << (index * 4)
+        })
+}
 
-    #[cfg(target_arch = "xtensa")]
-    pub const HEAP_SIZE: usize = 4096; // Xtensa (ESP32): 256KB+, use 4KB heap (1.5%)
+/// Parses a canonical allocator name (see [`allocator_name()`]), case-insensitive
+fn allocator_id_for_name(name: &str) -> Option<u8> {
+    [1u8, 2, 4, 5]
+        .into_iter()
+        .find(|&allocator_id| name.eq_ignore_ascii_case(allocator_name_for_id(allocator_id)))
+}
 
-    #[cfg(target_arch = "arm")]
-    pub const HEAP_SIZE: usize = 1024; // ARM Cortex-M: typically 16KB+, use 1KB heap (6%)
+/// First entry of the preference order that is available and suits the hardware
+fn select_by_preference(cpu_cores: usize, memory_constrained: bool) -> Option<u8> {
+    let mut packed = preference_order();
+    while packed != 0 {
+        let allocator_id = (packed & 0xF) as u8;
+        packed >>= 4;
+        let suitable = match allocator_id {
+            1 => !is_embedded_target(),
+            2 => can_use_mimalloc() && cpu_cores >= MIN_MIMALLOC_CORES && !memory_constrained,
+            5 => can_use_mimalloc_secure() && cpu_cores >= MIN_MIMALLOC_CORES && !memory_constrained,
+            _ => false, // embedded and custom are never chosen by preference
+        };
+        if suitable {
+            return Some(allocator_id);
+        }
+    }
+    None
+}
 
-    // Default heap size for other embedded architectures (LoongArch, Hexagon, BPF, SPARC, etc.)
-    #[cfg(not(any(
-        target_arch = "avr",
-        target_arch = "msp430", 
-        target_arch = "riscv32",
-        target_arch = "riscv64",
-        target_arch = "xtensa",
-        target_arch = "arm"
-    )))]
-    pub const HEAP_SIZE: usize = 2048; // Conservative default for unknown architectures
+// ========== Configuration Builder ==========
 
-    // Static memory pool for embedded heap
-    // This is a conservative allocation that should work on most embedded systems
-    pub static mut HEAP_MEMORY: [u8; HEAP_SIZE] = [0; HEAP_SIZE];
+/// Why [`AutoAllocatorConfig::apply()`] rejected a configuration
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigError {
+    /// The allocator was already selected (the first allocation happened), so nothing was applied
+    AlreadySelected,
 
-    // Singleton heap instance - different implementations for std vs no_std
-    #[cfg(not(target_os = "none"))]
-    pub static EMBEDDED_HEAP: Lazy<Heap> = Lazy::new(|| unsafe { Heap::new(&mut HEAP_MEMORY[..]) });
-    
-    #[cfg(target_os = "none")]
-    static mut EMBEDDED_HEAP_INSTANCE: Option<Heap> = None;
-    
-    /// Gets the embedded heap instance for no_std environments
-    /// 
-    /// This function provides access to the global embedded heap used in no_std 
-    /// environments. The heap is lazily initialized on first access with 
-    /// architecture-appropriate size defaults.
-    /// 
-    /// # Returns
-    /// 
-    /// A reference to the static embedded heap instance
-    /// 
-    /// # Safety
-    /// 
-    /// This function is only available in no_std environments (`target_os = "none"`).
-    /// The heap initialization is done safely using static guarantees.
-    #[cfg(target_os = "none")]
-    pub fn get_embedded_heap() -> &'static Heap {
-        unsafe {
-            if EMBEDDED_HEAP_INSTANCE.is_none() {
-                let heap = Heap::empty();
-                heap.init(HEAP_MEMORY.as_mut_ptr() as usize, HEAP_SIZE);
-                EMBEDDED_HEAP_INSTANCE = Some(heap);
+    /// A preferred allocator isn't available in this build ([`is_allocator_available()`])
+    Unavailable(AllocatorType),
+
+    /// An allocator is both preferred and denied
+    PreferredAndDenied(AllocatorType),
+
+    /// The preference order has more entries than the seven that are kept
+    TooManyPreferences(usize),
+}
+
+impl core::fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ConfigError::AlreadySelected => {
+                f.write_str("allocator already selected - apply the configuration before the first allocation")
             }
-            EMBEDDED_HEAP_INSTANCE.as_ref().unwrap()
+            ConfigError::Unavailable(allocator) => {
+                write!(f, "preferred allocator {:?} is not available in this build", allocator)
+            }
+            ConfigError::PreferredAndDenied(allocator) => {
+                write!(f, "allocator {:?} is both preferred and denied", allocator)
+            }
+            ConfigError::TooManyPreferences(count) => write!(
+                f,
+                "{} preference entries given, at most {} are supported",
+                count, MAX_PREFERENCES
+            ),
         }
     }
 }
 
-// ========== Safe Runtime Allocator Implementation ==========
-
-pub struct RuntimeAllocator;
+#[cfg(not(target_os = "none"))]
+impl std::error::Error for ConfigError {}
 
-impl RuntimeAllocator {
-    #[inline]
-    fn get_allocator_id() -> u8 {
-        let current_id = RUNTIME_ALLOCATOR_ID.load(Ordering::Acquire);
+/// All selection settings in one place, validated and applied together
+///
+/// Collects what [`set_allocator_profile()`], [`set_denied_allocators()`],
+/// [`set_allocator_preference()`] and (with `mimalloc-options`) `configure_mimalloc()`
+/// set individually, checks that they fit together and writes them in one go.
+/// Unset fields leave the matching `AUTO_ALLOCATOR_*` environment variable in charge.
+///
+/// [`apply()`](AutoAllocatorConfig::apply) must run before the first allocation, which in
+/// std binaries means a pre-main constructor (the Rust runtime allocates before `main`);
+/// later it returns [`ConfigError::AlreadySelected`] and changes nothing. With the
+/// `eager-init` feature, selection itself happens in a constructor and may win that race.
+///
+/// # Example
+///
+/// ```rust
+/// use auto_allocator::{AllocatorProfile, AllocatorType, AutoAllocatorConfig, ConfigError};
+///
+/// let config = AutoAllocatorConfig::new()
+///     .profile(AllocatorProfile::LowLatency)
+///     .deny(&[AllocatorType::MimallocSecure]);
+///
+/// // From main the runtime has already allocated, so the selection is settled
+/// assert_eq!(config.apply(), Err(ConfigError::AlreadySelected));
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AutoAllocatorConfig {
+    profile: Option<AllocatorProfile>,
+    deny_mask: Option<u16>,
+    // Packed like PREFERENCE_OVERRIDE, plus the number of entries given
+    preference: Option<(u32, usize)>,
+    #[cfg(all(feature = "mimalloc-options", not(target_os = "none")))]
+    mimalloc_options: Option<MimallocOptions>,
+}
 
-        if unlikely(current_id == 0) {
-            // First call, perform hardware detection and selection
-            let selected_id = select_allocator_by_hardware();
-            RUNTIME_ALLOCATOR_ID.store(selected_id, Ordering::Release);
+impl AutoAllocatorConfig {
+    /// Empty configuration: every setting comes from its environment variable or default
+    pub fn new() -> AutoAllocatorConfig {
+        AutoAllocatorConfig::default()
+    }
 
-            // Record selection information (ensure only logged once)
-            Self::log_allocator_selection(selected_id);
+    /// Selection profile, as with [`set_allocator_profile()`]
+    pub fn profile(mut self, profile: AllocatorProfile) -> AutoAllocatorConfig {
+        self.profile = Some(profile);
+        self
+    }
 
-            selected_id
-        } else {
-            current_id
-        }
+    /// Allocators that must never be selected, as with [`set_denied_allocators()`]
+    pub fn deny(mut self, denied: &[AllocatorType]) -> AutoAllocatorConfig {
+        self.deny_mask = Some(deny_mask_for(denied));
+        self
     }
 
-    #[cold]
-    #[cfg(not(target_os = "none"))]
-    fn log_allocator_selection(allocator_id: u8) {
-        if ALLOCATOR_LOGGED
-            .compare_exchange(false, true, Ordering::AcqRel, Ordering::Acquire)
-            .is_ok()
-        {
-            let (name, reason) = Self::get_allocator_log_info(allocator_id);
-            record_allocator_selection(name, &reason);
-        }
+    /// Order in which allocators are tried, as with [`set_allocator_preference()`]
+    ///
+    /// Every entry must be available in this build. Debug builds never contain
+    /// mimalloc, so a mimalloc preference fails validation there.
+    pub fn prefer(mut self, order: &[AllocatorType]) -> AutoAllocatorConfig {
+        self.preference = Some((pack_preference(order), order.len()));
+        self
     }
 
-    #[cold]
-    #[cfg(target_os = "none")]
-    fn log_allocator_selection(_allocator_id: u8) {
-        // No logging in no_std environments
+    /// mimalloc tunables, applied right after selection when mimalloc is chosen
+    #[cfg(all(feature = "mimalloc-options", not(target_os = "none")))]
+    pub fn mimalloc_options(mut self, options: MimallocOptions) -> AutoAllocatorConfig {
+        self.mimalloc_options = Some(options);
+        self
     }
 
-    /// Get logging information based on allocator ID and compile-time platform detection
-    #[cfg(not(target_os = "none"))]
-    fn get_allocator_log_info(allocator_id: u8) -> (&'static str, String) {
-        match allocator_id {
-            5 => {
-                let system_info = collect_system_info();
-                ("mimalloc-secure", format!(
-                    "security-hardened choice - runtime detected ({} cores, {} total RAM)",
-                    system_info.cpu_cores,
-                    format_memory_size(system_info.total_memory_bytes)
-                ))
-            },
-            2 => {
-                let system_info = collect_system_info();
-                ("mimalloc", format!(
-                    "optimal performance choice - runtime detected ({} cores, {} total RAM)",
-                    system_info.cpu_cores,
-                    format_memory_size(system_info.total_memory_bytes)
-                ))
-            },
-            4 => {
-                let system_info = collect_system_info();
-                ("embedded-alloc", format!(
-                    "embedded platform - compile-time selected ({} total RAM)",
-                    format_memory_size(system_info.total_memory_bytes)
-                ))
-            },
-            _ => {
-                // System allocator - determine reason based on compile-time platform detection
-                if cfg!(debug_assertions) {
-                    let system_info = collect_system_info();
-                    ("system", format!(
-                        "debug build - compile-time selected ({} cores, {} total RAM)",
-                        system_info.cpu_cores,
-                        format_memory_size(system_info.total_memory_bytes)
-                    ))
-                } else if cfg!(target_arch = "wasm32") {
-                    let system_info = collect_system_info();
-                    ("system", format!(
-                        "WASM environment - compile-time selected ({} total RAM)",
-                        format_memory_size(system_info.total_memory_bytes)
-                    ))
-                } else if cfg!(target_os = "android") {
-                    let system_info = collect_system_info();
-                    ("system", format!(
-                        "Android Scudo allocator - compile-time selected (security-first policy) ({} cores, {} total RAM)",
-                        system_info.cpu_cores,
-                        format_memory_size(system_info.total_memory_bytes)
-                    ))
-                } else if cfg!(target_os = "ios") {
-                    let system_info = collect_system_info();
-                    ("system", format!(
-                        "iOS libmalloc allocator - compile-time selected (Apple optimized) ({} cores, {} total RAM)",
-                        system_info.cpu_cores,
-                        format_memory_size(system_info.total_memory_bytes)
-                    ))
-                } else if cfg!(any(target_os = "freebsd", target_os = "netbsd")) {
-                    let system_info = collect_system_info();
-                    ("system", format!(
-                        "BSD native jemalloc - compile-time selected (platform optimized) ({} cores, {} total RAM)",
-                        system_info.cpu_cores,
-                        format_memory_size(system_info.total_memory_bytes)
-                    ))
-                } else if cfg!(target_os = "openbsd") {
-                    let system_info = collect_system_info();
-                    ("system", format!(
-                        "OpenBSD security-hardened allocator - compile-time selected ({} cores, {} total RAM)",
-                        system_info.cpu_cores,
-                        format_memory_size(system_info.total_memory_bytes)
-                    ))
-                } else if cfg!(any(target_os = "solaris", target_os = "illumos")) {
-                    let system_info = collect_system_info();
-                    ("system", format!(
-                        "Solaris libumem allocator - compile-time selected (enterprise grade) ({} cores, {} total RAM)",
-                        system_info.cpu_cores,
-                        format_memory_size(system_info.total_memory_bytes)
-                    ))
-                } else {
-                    // High-performance platforms that fell back to system (single-core or mimalloc unavailable)
-                    let system_info = collect_system_info();
-                    ("system", format!(
-                        "runtime fallback - single-core or mimalloc unavailable ({} cores, {} total RAM)",
-                        system_info.cpu_cores,
-                        format_memory_size(system_info.total_memory_bytes)
-                    ))
-                }
-            },
+    /// Checks the settings against each other and this build, without applying them
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let Some((mut packed, count)) = self.preference else {
+            return Ok(());
+        };
+        if count > MAX_PREFERENCES {
+            return Err(ConfigError::TooManyPreferences(count));
+        }
+        while packed != 0 {
+            let allocator_id = (packed & 0xF) as u8;
+            packed >>= 4;
+            let Some(allocator) = AllocatorType::from_id(allocator_id) else {
+                continue;
+            };
+            if !is_allocator_available(allocator) {
+                return Err(ConfigError::Unavailable(allocator));
+            }
+            if self.deny_mask.unwrap_or(0) & deny_bit(allocator_id) != 0 {
+                return Err(ConfigError::PreferredAndDenied(allocator));
+            }
         }
+        Ok(())
     }
-}
 
-// Branch prediction optimization
-#[inline(always)]
-fn unlikely(b: bool) -> bool {
-    #[cold]
-    fn cold() {}
-    if b {
-        cold();
+    /// Validates and applies the configuration; must run before the first allocation
+    ///
+    /// Nothing is applied when an error is returned.
+    pub fn apply(&self) -> Result<(), ConfigError> {
+        self.validate()?;
+
+        // All or nothing, even against a selection starting on another thread
+        let applied = write_selection_config(false, || {
+            if let Some(profile) = self.profile {
+                store_profile(profile);
+            }
+            if let Some(mask) = self.deny_mask {
+                DENY_OVERRIDE.store(DENY_LIST_SET | mask, Ordering::Release);
+            }
+            if let Some((packed, _)) = self.preference {
+                PREFERENCE_OVERRIDE.store(PREFERENCE_SET | packed, Ordering::Release);
+            }
+        });
+        if !applied {
+            return Err(ConfigError::AlreadySelected);
+        }
+
+        // configure_mimalloc() selects with the settings above, then tunes mimalloc if chosen
+        #[cfg(all(feature = "mimalloc-options", not(target_os = "none")))]
+        if let Some(options) = self.mimalloc_options {
+            configure_mimalloc(options);
+        }
+
+        Ok(())
     }
-    b
 }
 
-// ========== Global Allocator Implementation - Platform-specific VTable handling ==========
+// ========== Runtime Allocator Selection ==========
 
-unsafe impl GlobalAlloc for RuntimeAllocator {
-    #[inline]
-    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
-        match Self::get_allocator_id() {
+// Global state for allocator selection and logging  
+// ID mapping: 0=uninitialized, 1=system, 2=mimalloc, 3=jemalloc, 4=embedded, 5=mimalloc-secure, 6=custom
+static RUNTIME_ALLOCATOR_ID: AtomicU8 = AtomicU8::new(0);
+#[cfg(all(not(feature = "no-log"), not(target_os = "none")))]
+static ALLOCATOR_LOGGED: AtomicBool = AtomicBool::new(false);
+#[cfg(all(feature = "log", not(feature = "no-log"), not(target_os = "none")))]
+static LOG_FLUSHED: AtomicBool = AtomicBool::new(false);
+// Time spent selecting and logging on the first allocation; Instant panics on wasm32-unknown-unknown
+#[cfg(not(any(target_os = "none", all(target_arch = "wasm32", target_os = "unknown"))))]
+static SELECTION_DURATION: std::sync::OnceLock<core::time::Duration> = std::sync::OnceLock::new();
+
+/// Returns allocator ID for platforms with compile-time determinable choices
+///
+/// Returns `None` for platforms requiring runtime hardware detection (desktop systems).
+/// This optimization avoids unnecessary runtime checks for 90% of platforms.
+const fn get_compile_time_allocator() -> Option<u8> {
+    if is_embedded_target() {
+        return Some(4); // embedded-alloc
+    }
+
+    if cfg!(target_arch = "wasm32") {
+        return Some(1); // system
+    }
+
+    if cfg!(debug_assertions) {
+        return Some(1); // system (debug builds)
+    }
+
+    // Opt-in overrides: android-mimalloc / bsd-mimalloc trade the native allocator for speed
+    if is_mimalloc_opt_in() && can_use_mimalloc_secure() {
+        return Some(5); // mimalloc-secure
+    }
+    if is_mimalloc_opt_in() && can_use_mimalloc() {
+        return Some(2); // mimalloc
+    }
+
+    // Platforms with superior native allocators
+    if cfg!(target_os = "android") {
+        return Some(1); // Scudo
+    }
+
+    if cfg!(target_os = "ios") {
+        return Some(1); // libmalloc
+    }
+
+    if cfg!(any(target_os = "freebsd", target_os = "netbsd", target_os = "openbsd")) {
+        return Some(1); // native jemalloc/security-hardened
+    }
+
+    if cfg!(any(target_os = "solaris", target_os = "illumos")) {
+        return Some(1); // libumem (when linked) or libc malloc
+    }
+
+    // mimalloc has no QNX port, so there is no opt-in like android-mimalloc
+    if cfg!(target_os = "nto") {
+        return Some(1); // QNX native allocator
+    }
+
+    if cfg!(target_os = "haiku") {
+        return Some(1); // libroot allocator
+    }
+
+    None // High-performance platforms need runtime detection
+}
+
+// Allocator fixed at build time: build.rs emits `auto_allocator_compile_time_system` /
+// `auto_allocator_compile_time_embedded` when get_compile_time_allocator() is known to
+// return Some, letting the backend dispatch collapse to a single arm
+#[cfg(auto_allocator_compile_time_system)]
+const COMPILE_TIME_ALLOCATOR_ID: Option<u8> = Some(1);
+#[cfg(auto_allocator_compile_time_embedded)]
+const COMPILE_TIME_ALLOCATOR_ID: Option<u8> = Some(4);
+#[cfg(not(any(auto_allocator_compile_time_system, auto_allocator_compile_time_embedded)))]
+const COMPILE_TIME_ALLOCATOR_ID: Option<u8> = None;
+
+// build.rs duplicates the platform rules; fail the build if the two drift apart
+const _: () = assert!(match (COMPILE_TIME_ALLOCATOR_ID, get_compile_time_allocator()) {
+    (Some(fixed_id), Some(allocator_id)) => fixed_id == allocator_id,
+    (Some(_), None) => false,
+    (None, _) => true,
+});
+
+/// Whether the backend dispatch `match` has a live arm for `allocator_id` in this build
+///
+/// Mirrors the `#[cfg]` on the arms of `backend_alloc` and friends - keep the two in
+/// sync. An ID without an arm silently falls through to the system fallback (or,
+/// on no_std, to a null return), so selection must never produce one.
+const fn has_dispatch_arm(allocator_id: u8) -> bool {
+    match allocator_id {
+        1 => !is_embedded_target(),
+        2 => cfg!(all(auto_allocator_mimalloc, feature = "_mimalloc")),
+        4 => cfg!(all(feature = "_embedded", target_os = "none")),
+        5 => cfg!(all(auto_allocator_mimalloc, feature = "_mimalloc_secure")),
+        6 => true,
+        _ => false,
+    }
+}
+
+// The selector returns 5 / 2 whenever these checks pass; fail the build if the
+// feature gates on the dispatch arms drift away from them
+const _: () = assert!(!can_use_mimalloc_secure() || has_dispatch_arm(5));
+const _: () = assert!(!can_use_mimalloc() || has_dispatch_arm(2));
+const _: () = assert!(match COMPILE_TIME_ALLOCATOR_ID {
+    Some(allocator_id) => has_dispatch_arm(allocator_id),
+    None => true,
+});
+
+/// The selection the first allocation runs, without publishing the result
+fn detect_allocator_id() -> u8 {
+    match COMPILE_TIME_ALLOCATOR_ID {
+        Some(allocator_id) => allocator_id,
+        None => probe_mimalloc(select_allocator_by_hardware(
+            get_allocator_profile(),
+            get_effective_memory_safe(),
+        )),
+    }
+}
+
+// Set once the selection probe found that mimalloc can't allocate in this process
+static MIMALLOC_PROBE_FAILED: AtomicBool = AtomicBool::new(false);
+
+/// Falls back to the system allocator when mimalloc can't hand out memory here
+///
+/// mimalloc reserves its first arena lazily, so a sandbox that blocks large mappings
+/// would otherwise only show up as a null from the first real allocation.
+fn probe_mimalloc(allocator_id: u8) -> u8 {
+    if !matches!(allocator_id, 2 | 5) || mimalloc_probe_succeeds() {
+        return allocator_id;
+    }
+    MIMALLOC_PROBE_FAILED.store(true, Ordering::Relaxed);
+    1 // system
+}
+
+// Calls mi_malloc directly: the global allocator is mid-selection and must not be re-entered
+#[cfg(auto_allocator_mimalloc)]
+#[cold]
+fn mimalloc_probe_succeeds() -> bool {
+    use mimalloc_rust::raw::basic_allocation::{mi_free, mi_malloc};
+
+    unsafe {
+        let ptr = mi_malloc(16);
+        if ptr.is_null() {
+            return false;
+        }
+        mi_free(ptr);
+    }
+    true
+}
+
+// mimalloc is never selected here
+#[cfg(not(auto_allocator_mimalloc))]
+fn mimalloc_probe_succeeds() -> bool {
+    true
+}
+
+/// Selects allocator using compile-time rules, runtime hardware detection and the deny list
+fn select_allocator_by_hardware(profile: AllocatorProfile, effective_memory_bytes: u64) -> u8 {
+    next_allowed_allocator(
+        select_preferred_allocator(profile, effective_memory_bytes),
+        denied_allocator_mask(),
+    )
+}
+
+/// Allocator the rules would pick before the deny list is applied
+fn select_preferred_allocator(profile: AllocatorProfile, effective_memory_bytes: u64) -> u8 {
+    if let Some(allocator_id) = get_compile_time_allocator() {
+        return allocator_id;
+    }
+
+    // Only high-performance platforms reach here - need CPU core detection
+    // Use zero-allocation CPU detection to avoid infinite recursion
+    let cpu_cores = get_cpu_cores_safe();
+    let memory_constrained = is_memory_constrained(profile, effective_memory_bytes);
+
+    // User preference order replaces the default order below
+    if let Some(allocator_id) = select_by_preference(cpu_cores, memory_constrained) {
+        return allocator_id;
+    }
+
+    // Constrained systems (or containers): skip mimalloc's segment reservations
+    if memory_constrained || matches!(profile, AllocatorProfile::Footprint) {
+        return 1; // system
+    }
+
+    // Multi-core systems: prefer mimalloc (secure > regular > system)
+    if cpu_cores >= MIN_MIMALLOC_CORES && can_use_mimalloc_secure() {
+        return 5; // mimalloc-secure
+    }
+
+    // Check if mimalloc is available
+    // Since build script ensures compatibility, mimalloc is available if feature is enabled
+    if cpu_cores >= MIN_MIMALLOC_CORES && can_use_mimalloc() {
+        return 2; // mimalloc
+    }
+
+    1 // system (single-core or all high-performance allocators unavailable)
+}
+
+// no-libc: every libc call the crate makes on Linux, as raw syscalls
+#[cfg(auto_allocator_no_libc)]
+mod raw_syscall {
+    #[cfg(target_arch = "x86_64")]
+    mod nr {
+        pub const READ: usize = 0;
+        pub const WRITE: usize = 1;
+        pub const CLOSE: usize = 3;
+        #[cfg(all(feature = "realtime", debug_assertions))]
+        pub const GETRUSAGE: usize = 98;
+        pub const SYSINFO: usize = 99;
+        pub const MLOCKALL: usize = 151;
+        pub const SCHED_GETAFFINITY: usize = 204;
+        pub const OPENAT: usize = 257;
+    }
+
+    #[cfg(target_arch = "aarch64")]
+    mod nr {
+        pub const OPENAT: usize = 56;
+        pub const CLOSE: usize = 57;
+        pub const READ: usize = 63;
+        pub const WRITE: usize = 64;
+        pub const SCHED_GETAFFINITY: usize = 123;
+        #[cfg(all(feature = "realtime", debug_assertions))]
+        pub const GETRUSAGE: usize = 165;
+        pub const SYSINFO: usize = 179;
+        pub const MLOCKALL: usize = 230;
+    }
+
+    const AT_FDCWD: isize = -100;
+    const O_RDONLY_CLOEXEC: usize = 0o2000000;
+    #[cfg(all(feature = "realtime", debug_assertions))]
+    const RUSAGE_THREAD: usize = 1;
+    const AT_PAGESZ: u64 = 6;
+
+    // Returns the raw kernel result: a negative errno on failure
+    #[cfg(target_arch = "x86_64")]
+    unsafe fn syscall4(number: usize, arg1: usize, arg2: usize, arg3: usize, arg4: usize) -> isize {
+        let ret: isize;
+        core::arch::asm!(
+            "syscall",
+            inlateout("rax") number as isize => ret,
+            in("rdi") arg1,
+            in("rsi") arg2,
+            in("rdx") arg3,
+            in("r10") arg4,
+            lateout("rcx") _,
+            lateout("r11") _,
+            options(nostack),
+        );
+        ret
+    }
+
+    #[cfg(target_arch = "aarch64")]
+    unsafe fn syscall4(number: usize, arg1: usize, arg2: usize, arg3: usize, arg4: usize) -> isize {
+        let ret: isize;
+        core::arch::asm!(
+            "svc 0",
+            in("x8") number,
+            inlateout("x0") arg1 as isize => ret,
+            in("x1") arg2,
+            in("x2") arg3,
+            in("x3") arg4,
+            options(nostack),
+        );
+        ret
+    }
+
+    unsafe fn syscall3(number: usize, arg1: usize, arg2: usize, arg3: usize) -> isize {
+        syscall4(number, arg1, arg2, arg3, 0)
+    }
+
+    // struct sysinfo on 64-bit Linux; the trailing padding is zero-sized there
+    #[repr(C)]
+    struct SysInfo {
+        uptime: i64,
+        loads: [u64; 3],
+        totalram: u64,
+        freeram: u64,
+        sharedram: u64,
+        bufferram: u64,
+        totalswap: u64,
+        freeswap: u64,
+        procs: u16,
+        pad: u16,
+        totalhigh: u64,
+        freehigh: u64,
+        mem_unit: u32,
+    }
+
+    const _: () = assert!(core::mem::size_of::<SysInfo>() == 112);
+
+    // struct rusage on 64-bit Linux: two timevals, then 14 longs starting with ru_maxrss
+    #[cfg(all(feature = "realtime", debug_assertions))]
+    #[repr(C)]
+    struct RUsage {
+        utime: [i64; 2],
+        stime: [i64; 2],
+        maxrss: i64,
+        ixrss: i64,
+        idrss: i64,
+        isrss: i64,
+        minflt: i64,
+        majflt: i64,
+        rest: [i64; 8],
+    }
+
+    #[cfg(all(feature = "realtime", debug_assertions))]
+    const _: () = assert!(core::mem::size_of::<RUsage>() == 144);
+
+    fn sysinfo() -> Option<SysInfo> {
+        let mut info = core::mem::MaybeUninit::<SysInfo>::zeroed();
+        let ret = unsafe { syscall3(nr::SYSINFO, info.as_mut_ptr() as usize, 0, 0) };
+        (ret == 0).then(|| unsafe { info.assume_init() })
+    }
+
+    /// CPUs in this thread's affinity mask (up to 1024), `None` if the syscall fails
+    pub(crate) fn cpu_cores() -> Option<usize> {
+        let mut mask = [0u64; 16];
+        // pid 0 = calling thread; on success the kernel returns the bytes it wrote
+        let written = unsafe {
+            syscall3(
+                nr::SCHED_GETAFFINITY,
+                0,
+                core::mem::size_of_val(&mask),
+                mask.as_mut_ptr() as usize,
+            )
+        };
+        if written <= 0 {
+            return None;
+        }
+        let words = (written as usize / 8).min(mask.len());
+        let cores = mask[..words].iter().map(|word| word.count_ones() as usize).sum();
+        (cores > 0).then_some(cores)
+    }
+
+    /// Total RAM in bytes, `None` if the syscall fails
+    pub(crate) fn total_memory() -> Option<u64> {
+        let info = sysinfo()?;
+        Some(info.totalram * info.mem_unit.max(1) as u64)
+    }
+
+    /// (total, free) swap in bytes, `None` if the syscall fails
+    pub(crate) fn swap() -> Option<(u64, u64)> {
+        let info = sysinfo()?;
+        let unit = info.mem_unit.max(1) as u64;
+        Some((info.totalswap * unit, info.freeswap * unit))
+    }
+
+    /// Reads up to `buf.len()` bytes of a file, `None` if it can't be opened or read
+    pub(crate) fn read_file(path: &core::ffi::CStr, buf: &mut [u8]) -> Option<usize> {
+        let fd = unsafe { syscall4(nr::OPENAT, AT_FDCWD as usize, path.as_ptr() as usize, O_RDONLY_CLOEXEC, 0) };
+        if fd < 0 {
+            return None;
+        }
+        let len = unsafe { syscall3(nr::READ, fd as usize, buf.as_mut_ptr() as usize, buf.len()) };
+        unsafe { syscall3(nr::CLOSE, fd as usize, 0, 0) };
+        (len >= 0).then_some(len as usize)
+    }
+
+    /// Writes `bytes` to `fd`, ignoring errors like the libc path does
+    pub(crate) fn write(fd: i32, bytes: &[u8]) {
+        unsafe { syscall3(nr::WRITE, fd as usize, bytes.as_ptr() as usize, bytes.len()) };
+    }
+
+    /// Page size from the auxiliary vector (`AT_PAGESZ`), `None` if unreadable
+    pub(crate) fn page_size() -> Option<usize> {
+        // The kernel puts AT_PAGESZ within the first few entries
+        let mut buf = [0u8; 512];
+        let len = read_file(c"/proc/self/auxv", &mut buf)?;
+        buf[..len].chunks_exact(16).find_map(|entry| {
+            let key = u64::from_ne_bytes(entry[..8].try_into().ok()?);
+            let value = u64::from_ne_bytes(entry[8..].try_into().ok()?);
+            (key == AT_PAGESZ && value > 0).then_some(value as usize)
+        })
+    }
+
+    /// `mlockall(flags)`, the negated errno on failure
+    pub(crate) fn mlockall(flags: usize) -> Result<(), i32> {
+        let ret = unsafe { syscall3(nr::MLOCKALL, flags, 0, 0) };
+        if ret == 0 {
+            Ok(())
+        } else {
+            Err(-ret as i32)
+        }
+    }
+
+    /// Minor plus major page faults of the calling thread
+    #[cfg(all(feature = "realtime", debug_assertions))]
+    pub(crate) fn thread_faults() -> Option<u64> {
+        let mut usage = core::mem::MaybeUninit::<RUsage>::zeroed();
+        let ret = unsafe { syscall3(nr::GETRUSAGE, RUSAGE_THREAD, usage.as_mut_ptr() as usize, 0) };
+        if ret != 0 {
+            return None;
+        }
+        let usage = unsafe { usage.assume_init() };
+        Some((usage.minflt + usage.majflt) as u64)
+    }
+
+    extern "C" {
+        // The process environment block; std's set_var edits it, and no syscall reads it
+        static environ: *const *const core::ffi::c_char;
+    }
+
+    /// Value of environment variable `name`, read in place from `environ`
+    pub(crate) fn getenv(name: &core::ffi::CStr) -> Option<&'static [u8]> {
+        let name = name.to_bytes();
+        let mut entry = unsafe { environ };
+        if entry.is_null() {
+            return None;
+        }
+        unsafe {
+            while !(*entry).is_null() {
+                let pair = core::ffi::CStr::from_ptr(*entry).to_bytes();
+                if let Some(value) = pair.strip_prefix(name).and_then(|rest| rest.strip_prefix(b"=")) {
+                    return Some(value);
+                }
+                entry = entry.add(1);
+            }
+        }
+        None
+    }
+}
+
+/// Get CPU core count without allocating memory (to avoid infinite recursion)
+fn get_cpu_cores_safe() -> usize {
+    #[cfg(unix)]
+    {
+        #[cfg(auto_allocator_no_libc)]
+        let cores = raw_syscall::cpu_cores().unwrap_or(1);
+
+        // Use direct libc calls to avoid std allocation
+        #[cfg(not(auto_allocator_no_libc))]
+        let cores = unsafe {
+            let cores = libc::sysconf(libc::_SC_NPROCESSORS_ONLN);
+            if cores > 0 {
+                cores as usize
+            } else {
+                1
+            }
+        };
+        // Same clamp as collect_system_info, so selection and reporting agree
+        cgroup_cpu_quota_cores().map_or(cores, |quota| quota.min(cores))
+    }
+    
+    #[cfg(windows)]
+    {
+        // Windows: Use direct WinAPI to avoid std allocation
+        use winapi::um::sysinfoapi::{GetSystemInfo, SYSTEM_INFO};
+        unsafe {
+            let mut sysinfo: SYSTEM_INFO = std::mem::zeroed();
+            GetSystemInfo(&mut sysinfo);
+            sysinfo.dwNumberOfProcessors as usize
+        }
+    }
+    
+    #[cfg(not(any(unix, windows)))]
+    {
+        // Fallback: assume multi-core for unknown platforms
+        4
+    }
+}
+
+// ========== Custom Allocator Backend ==========
+
+// Written once by register_custom_allocator before RUNTIME_ALLOCATOR_ID becomes 6,
+// so every reader that observes ID 6 (Acquire) also sees the allocator
+struct CustomAllocatorSlot(core::cell::UnsafeCell<Option<&'static dyn GlobalAlloc>>);
+
+unsafe impl Sync for CustomAllocatorSlot {}
+
+static CUSTOM_ALLOCATOR: CustomAllocatorSlot = CustomAllocatorSlot(core::cell::UnsafeCell::new(None));
+static CUSTOM_ALLOCATOR_CLAIMED: AtomicBool = AtomicBool::new(false);
+
+/// Registers a user-supplied allocator as the backend for all allocations
+///
+/// Keeps auto-allocator's API surface (info, stats, bump mode, aligned helpers) while
+/// delegating the actual allocation to `allocator`. Must happen before the first
+/// allocation, because memory can't move between backends. The Rust runtime allocates
+/// before `main`, so in std binaries register from a pre-main constructor (e.g. an
+/// `.init_array` entry on Linux); on no_std targets call it first thing in the entry point.
+///
+/// Returns `false` if an allocator is already registered or selection already happened,
+/// in which case the automatically selected allocator stays in use.
+///
+/// # Example
+///
+/// ```rust
+/// use std::alloc::{GlobalAlloc, Layout, System};
+///
+/// struct MyArena;
+///
+/// unsafe impl GlobalAlloc for MyArena {
+///     unsafe fn alloc(&self, layout: Layout) -> *mut u8 { System.alloc(layout) }
+///     unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) { System.dealloc(ptr, layout) }
+/// }
+///
+/// static MY_ARENA: MyArena = MyArena;
+///
+/// // Too late here: the runtime has already allocated through the selected allocator
+/// assert!(!auto_allocator::register_custom_allocator(&MY_ARENA));
+/// ```
+pub fn register_custom_allocator(allocator: &'static dyn GlobalAlloc) -> bool {
+    if CUSTOM_ALLOCATOR_CLAIMED
+        .compare_exchange(false, true, Ordering::AcqRel, Ordering::Acquire)
+        .is_err()
+    {
+        return false;
+    }
+
+    // Only this thread writes the slot, and nobody reads it until ID 6 is published
+    unsafe { *CUSTOM_ALLOCATOR.0.get() = Some(allocator) };
+
+    if RUNTIME_ALLOCATOR_ID
+        .compare_exchange(0, 6, Ordering::AcqRel, Ordering::Acquire)
+        .is_err()
+    {
+        // Selection got there first: drop the unused allocator and release the claim.
+        // The ID never returns to 0, so no later registration can publish the slot either
+        unsafe { *CUSTOM_ALLOCATOR.0.get() = None };
+        CUSTOM_ALLOCATOR_CLAIMED.store(false, Ordering::Release);
+        return false;
+    }
+
+    // The custom allocator settles selection: later selection setters report `false`
+    seal_selection_config();
+    RuntimeAllocator::log_allocator_selection(6);
+    true
+}
+
+/// Returns the registered custom allocator; only called once ID 6 has been observed
+#[inline(always)]
+unsafe fn custom_allocator() -> &'static dyn GlobalAlloc {
+    // ID 6 may have been read with a Relaxed load; this fence pairs with the
+    // AcqRel publish in register_custom_allocator() so the slot write is visible
+    core::sync::atomic::fence(Ordering::Acquire);
+    match *CUSTOM_ALLOCATOR.0.get() {
+        Some(allocator) => allocator,
+        None => unreachable_custom_allocator(),
+    }
+}
+
+#[cold]
+fn unreachable_custom_allocator() -> ! {
+    // ID 6 is only published after the slot is written
+    unreachable!("custom allocator ID set without a registered allocator")
+}
+
+// ========== Embedded Heap Configuration ==========
+
+/// Parses a decimal build-time setting (`AUTO_ALLOCATOR_HEAP_SIZE`, `AUTO_ALLOCATOR_CPU_CORES`, ...)
+///
+/// build.rs has already rejected anything that isn't a positive number and
+/// re-emitted hex values (`0x...`) in decimal.
+#[cfg(target_os = "none")]
+const fn parse_build_setting(value: &str) -> usize {
+    let bytes = value.as_bytes();
+    let mut number = 0usize;
+    let mut i = 0;
+    while i < bytes.len() {
+        assert!(bytes[i].is_ascii_digit(), "auto-allocator build setting must be a number");
+        number = number * 10 + (bytes[i] - b'0') as usize;
+        i += 1;
+    }
+    number
+}
+
+// Embedded heap configuration for all no_std targets
+#[cfg(target_os = "none")]
+mod embedded_heap_config {
+    // With `manual-embedded-init` the firmware supplies the heap region, so the
+    // built-in sizes and pool go unused
+    #![cfg_attr(feature = "manual-embedded-init", allow(dead_code))]
+
+    use core::alloc::GlobalAlloc;
+    use embedded_alloc::Heap;
+    #[cfg(not(target_os = "none"))]
+    use std::sync::LazyLock;
+
+    // Architecture-specific heap sizes based on typical available memory
+    // These are conservative defaults that work well for most embedded applications
+    // Override at build time with AUTO_ALLOCATOR_HEAP_SIZE=<bytes> (validated by build.rs)
+
+    #[cfg(target_arch = "avr")]
+    const DEFAULT_HEAP_SIZE: usize = 512; // AVR (Arduino Uno): 2KB total, use 512B heap (25%)
+
+    #[cfg(target_arch = "msp430")]
+    const DEFAULT_HEAP_SIZE: usize = 256; // MSP430: 1KB total, use 256B heap (25%)
+
+    #[cfg(target_arch = "riscv32")]
+    const DEFAULT_HEAP_SIZE: usize = 2048; // RISC-V 32-bit: typically 32KB+, use 2KB heap (6%)
+
+    #[cfg(target_arch = "riscv64")]
+    const DEFAULT_HEAP_SIZE: usize = 4096; // RISC-V 64-bit: typically 128KB+, use 4KB heap (3%)
+
+    #[cfg(target_arch = "xtensa")]
+    const DEFAULT_HEAP_SIZE: usize = 4096; // Xtensa (ESP32): 256KB+, use 4KB heap (1.5%)
+
+    #[cfg(target_arch = "arm")]
+    const DEFAULT_HEAP_SIZE: usize = 1024; // ARM Cortex-M: typically 16KB+, use 1KB heap (6%)
+
+    // x86_64/aarch64 bare metal is OS development (kernels, unikernels), not an MCU
+    #[cfg(any(target_arch = "x86_64", target_arch = "aarch64"))]
+    const DEFAULT_HEAP_SIZE: usize = 1 << 20; // Kernel/unikernel: RAM in the GBs, use 1MB heap
+
+    // Default heap size for other embedded architectures (LoongArch, Hexagon, BPF, SPARC, etc.)
+    #[cfg(not(any(
+        target_arch = "avr",
+        target_arch = "msp430", 
+        target_arch = "riscv32",
+        target_arch = "riscv64",
+        target_arch = "xtensa",
+        target_arch = "arm",
+        target_arch = "x86_64",
+        target_arch = "aarch64"
+    )))]
+    const DEFAULT_HEAP_SIZE: usize = 2048; // Conservative default for unknown architectures
+
+    pub const HEAP_SIZE: usize = match option_env!("AUTO_ALLOCATOR_HEAP_SIZE") {
+        Some(bytes) => crate::parse_build_setting(bytes),
+        None => DEFAULT_HEAP_SIZE,
+    };
+
+    // ATmega328P (Arduino Uno) has 2KB of SRAM shared by .data/.bss, heap and stack;
+    // the default 512B heap plus this reserve leaves 512B for statics
+    #[cfg(target_arch = "avr")]
+    const AVR_RAM_BYTES: usize = 2048;
+    #[cfg(target_arch = "avr")]
+    const AVR_MIN_STACK_RESERVE: usize = 1024;
+    #[cfg(target_arch = "avr")]
+    const _: () = assert!(
+        HEAP_SIZE + AVR_MIN_STACK_RESERVE <= AVR_RAM_BYTES,
+        "auto-allocator: AVR heap leaves less than 1KB of the ATmega328P's 2KB SRAM for the stack; lower AUTO_ALLOCATOR_HEAP_SIZE"
+    );
+
+    // Backing array for the heap, aligned so DMA buffers can be carved from it:
+    // page-aligned for kernels, cache-line (32 bytes) everywhere else
+    #[cfg_attr(any(target_arch = "x86_64", target_arch = "aarch64"), repr(C, align(4096)))]
+    #[cfg_attr(not(any(target_arch = "x86_64", target_arch = "aarch64")), repr(C, align(32)))]
+    pub struct HeapMemory(pub [u8; HEAP_SIZE]);
+
+    // Static memory pool for embedded heap
+    // This is a conservative allocation that should work on most embedded systems
+    pub static mut HEAP_MEMORY: HeapMemory = HeapMemory([0; HEAP_SIZE]);
+
+    /// Largest `Layout::align()` the embedded heap serves (4096 on x86_64/aarch64
+    /// bare metal, 32 elsewhere)
+    ///
+    /// The alignment of the static heap array. Requests aligned more strictly get
+    /// null instead of a block, so a DMA buffer is never silently misaligned; the
+    /// same bound applies to a region handed to `init_embedded_heap()`.
+    pub const EMBEDDED_HEAP_MAX_ALIGN: usize = core::mem::align_of::<HeapMemory>();
+
+    // Singleton heap instance - different implementations for std vs no_std
+    #[cfg(not(target_os = "none"))]
+    pub static EMBEDDED_HEAP: LazyLock<Heap> = LazyLock::new(|| unsafe { Heap::new(&mut HEAP_MEMORY.0[..]) });
+    
+    #[cfg(all(target_os = "none", not(feature = "manual-embedded-init")))]
+    static mut EMBEDDED_HEAP_INSTANCE: Option<Heap> = None;
+    
+    /// Gets the embedded heap instance for no_std environments
+    /// 
+    /// This function provides access to the global embedded heap used in no_std 
+    /// environments. The heap is lazily initialized on first access with 
+    /// architecture-appropriate size defaults.
+    /// 
+    /// # Returns
+    /// 
+    /// A reference to the static embedded heap instance
+    /// 
+    /// # Safety
+    /// 
+    /// This function is only available in no_std environments (`target_os = "none"`).
+    /// The heap initialization is done safely using static guarantees.
+    #[cfg(all(target_os = "none", not(feature = "manual-embedded-init")))]
+    pub fn get_embedded_heap() -> &'static Heap {
+        unsafe {
+            if EMBEDDED_HEAP_INSTANCE.is_none() {
+                let heap = Heap::empty();
+                heap.init(core::ptr::addr_of_mut!(HEAP_MEMORY) as usize, HEAP_SIZE);
+                EMBEDDED_HEAP_INSTANCE = Some(heap);
+            }
+            EMBEDDED_HEAP_INSTANCE.as_ref().unwrap()
+        }
+    }
+
+    // `manual-embedded-init`: stays empty (every allocation returns null) until
+    // init_embedded_heap() hands it a region
+    #[cfg(all(target_os = "none", feature = "manual-embedded-init"))]
+    static mut MANUAL_HEAP: Heap = Heap::empty();
+    #[cfg(all(target_os = "none", feature = "manual-embedded-init"))]
+    static MANUAL_HEAP_INITIALIZED: core::sync::atomic::AtomicBool =
+        core::sync::atomic::AtomicBool::new(false);
+    // Region handed to init_embedded_heap(), kept for reset_embedded_heap()
+    #[cfg(all(target_os = "none", feature = "manual-embedded-init"))]
+    static MANUAL_HEAP_START: core::sync::atomic::AtomicUsize = core::sync::atomic::AtomicUsize::new(0);
+    #[cfg(all(target_os = "none", feature = "manual-embedded-init"))]
+    static MANUAL_HEAP_SIZE: core::sync::atomic::AtomicUsize = core::sync::atomic::AtomicUsize::new(0);
+
+    /// Gets the embedded heap, which is empty until [`init_embedded_heap()`] runs
+    #[cfg(all(target_os = "none", feature = "manual-embedded-init"))]
+    pub fn get_embedded_heap() -> &'static Heap {
+        unsafe { &*core::ptr::addr_of!(MANUAL_HEAP) }
+    }
+
+    /// Hands the embedded heap its memory region (`manual-embedded-init` feature)
+    ///
+    /// With this feature the heap is never initialized implicitly, so firmware decides
+    /// exactly when it comes up - e.g. after the MMU/MPU maps the region. Until then
+    /// the allocator returns null for every request, which `alloc` turns into the
+    /// allocation error handler (a panic or abort, depending on the firmware's
+    /// `#[alloc_error_handler]` / panic setup) for `Box`, `Vec` and friends.
+    ///
+    /// Requests aligned beyond [`EMBEDDED_HEAP_MAX_ALIGN`] still get null, however
+    /// the region itself is aligned.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called more than once.
+    ///
+    /// # Safety
+    ///
+    /// `start..start + size` must be valid, writable memory reserved for the heap for
+    /// the rest of the program, and `size` must be nonzero.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// static mut HEAP: [u8; 4096] = [0; 4096];
+    ///
+    /// #[entry]
+    /// fn main() -> ! {
+    ///     configure_mpu();
+    ///     unsafe { auto_allocator::init_embedded_heap(core::ptr::addr_of_mut!(HEAP) as usize, 4096) };
+    ///     // allocations work from here on
+    /// }
+    /// ```
+    #[cfg(all(target_os = "none", feature = "manual-embedded-init"))]
+    pub unsafe fn init_embedded_heap(start: usize, size: usize) {
+        use core::sync::atomic::Ordering;
+
+        if MANUAL_HEAP_INITIALIZED.swap(true, Ordering::AcqRel) {
+            panic!("auto-allocator: init_embedded_heap() called more than once");
+        }
+        (*core::ptr::addr_of!(MANUAL_HEAP)).init(start, size);
+        MANUAL_HEAP_START.store(start, Ordering::Relaxed);
+        MANUAL_HEAP_SIZE.store(size, Ordering::Release);
+    }
+
+    // 0 = no handler, otherwise a `fn(Layout)`
+    static OOM_HANDLER: core::sync::atomic::AtomicUsize = core::sync::atomic::AtomicUsize::new(0);
+    // Set while the handler runs, so allocations it makes fail instead of recursing into it;
+    // global, so exhaustion on other cores / in ISRs fails fast meanwhile too
+    static IN_OOM_HANDLER: core::sync::atomic::AtomicBool = core::sync::atomic::AtomicBool::new(false);
+
+    /// Sets (or with `None`, clears) the handler called when the embedded heap is exhausted
+    ///
+    /// Runs inside the allocator whenever a request can't be served, with the layout
+    /// that failed. The handler can free memory (drop caches, release pooled buffers)
+    /// and the allocation is then retried once; if it still fails, the caller gets
+    /// null as before - which `Box`, `Vec` and friends turn into the allocation error
+    /// handler. It can also just record the failure, or reset the device.
+    ///
+    /// The handler must not rely on allocating: requests it makes fail immediately
+    /// (without calling it again) so exhaustion can't recurse.
+    ///
+    /// That guard is global, not per core or per interrupt context - no_std has no
+    /// portable ID to scope it by. While the handler runs, a request that exhausts the
+    /// heap on another core or in an interrupt handler also gets null right away,
+    /// without the handler or a retry; requests that fit are served as usual. Firmware
+    /// that allocates from several cores or ISRs should treat that null as retryable.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// fn on_oom(layout: core::alloc::Layout) {
+    ///     defmt::warn!("heap exhausted: {} bytes", layout.size());
+    ///     CACHE.lock(|cache| cache.clear());
+    /// }
+    ///
+    /// auto_allocator::set_embedded_oom_handler(Some(on_oom));
+    /// ```
+    pub fn set_embedded_oom_handler(handler: Option<fn(core::alloc::Layout)>) {
+        use core::sync::atomic::Ordering;
+
+        OOM_HANDLER.store(handler.map_or(0, |handler| handler as usize), Ordering::Release);
+    }
+
+    // A request failed: report it to the handler, then give `retry` one more chance
+    #[cold]
+    unsafe fn handle_exhaustion(layout: core::alloc::Layout, retry: impl FnOnce() -> *mut u8) -> *mut u8 {
+        use core::sync::atomic::Ordering;
+
+        let handler = OOM_HANDLER.load(Ordering::Acquire);
+        if handler == 0 || IN_OOM_HANDLER.swap(true, Ordering::Acquire) {
+            return core::ptr::null_mut();
+        }
+        let handler: fn(core::alloc::Layout) = core::mem::transmute(handler);
+        handler(layout);
+        IN_OOM_HANDLER.store(false, Ordering::Release);
+        retry()
+    }
+
+    /// Reclaims the whole embedded heap at once, as if nothing had been allocated
+    ///
+    /// For firmware with a clear "everything is dead now" point - e.g. between
+    /// sessions or jobs, or as a last resort from the exhaustion handler (see
+    /// [`set_embedded_oom_handler()`]) before starting over. Resets the on-chip heap
+    /// and, with `esp-psram` / `embedded-pool`, the PSRAM heap and the small-block
+    /// pool. Does nothing for a heap that hasn't been set up yet.
+    ///
+    /// # Safety
+    ///
+    /// Every allocation made from the embedded heap must be dead: no `Box`, `Vec`,
+    /// `String` or other owner may be used or dropped afterwards, on any core or in
+    /// any interrupt handler. Dropping one later frees memory the heap has handed
+    /// out again.
+    pub unsafe fn reset_embedded_heap() {
+        #[cfg(not(feature = "manual-embedded-init"))]
+        critical_section::with(|_| {
+            let slot = &mut *core::ptr::addr_of_mut!(EMBEDDED_HEAP_INSTANCE);
+            if slot.is_some() {
+                *slot = Some(fresh_heap(core::ptr::addr_of_mut!(HEAP_MEMORY) as usize, HEAP_SIZE));
+            }
+        });
+
+        #[cfg(feature = "manual-embedded-init")]
+        {
+            use core::sync::atomic::Ordering;
+
+            let size = MANUAL_HEAP_SIZE.load(Ordering::Acquire);
+            if size != 0 {
+                let start = MANUAL_HEAP_START.load(Ordering::Relaxed);
+                critical_section::with(|_| {
+                    *core::ptr::addr_of_mut!(MANUAL_HEAP) = fresh_heap(start, size);
+                });
+            }
+        }
+
+        #[cfg(all(target_arch = "xtensa", feature = "esp-psram"))]
+        psram::reset();
+
+        #[cfg(feature = "embedded-pool")]
+        pool::reset();
+    }
+
+    /// A new heap over `start..start + size`
+    ///
+    /// `Heap::init` may only be called once per heap, so a reset swaps in a fresh one
+    /// instead of re-initializing. Callers replace the old heap inside a critical
+    /// section, which every `Heap` operation also takes, so no allocation sees it half-written.
+    unsafe fn fresh_heap(start: usize, size: usize) -> Heap {
+        let heap = Heap::empty();
+        heap.init(start, size);
+        heap
+    }
+
+    /// Allocates from the embedded heap, or from PSRAM per [`psram`] routing
+    #[inline]
+    pub unsafe fn heap_alloc(layout: core::alloc::Layout) -> *mut u8 {
+        // Not an exhaustion, so the OOM handler isn't involved
+        if layout.align() > EMBEDDED_HEAP_MAX_ALIGN {
+            return core::ptr::null_mut();
+        }
+        let mut ptr = heap_alloc_once(layout);
+        if ptr.is_null() {
+            ptr = handle_exhaustion(layout, || heap_alloc_once(layout));
+        }
+        debug_assert_aligned(ptr, layout.align());
+        ptr
+    }
+
+    // DMA engines fault (or corrupt neighbours) on a misaligned buffer: catch it in debug builds
+    #[inline]
+    fn debug_assert_aligned(ptr: *mut u8, align: usize) {
+        debug_assert!(
+            ptr as usize % align == 0,
+            "auto-allocator: embedded heap returned {:p}, not aligned to {}",
+            ptr,
+            align
+        );
+    }
+
+    #[inline]
+    unsafe fn heap_alloc_once(layout: core::alloc::Layout) -> *mut u8 {
+        // Small requests try the pool first; a miss (too big, or class full) uses the heap
+        #[cfg(feature = "embedded-pool")]
+        if pool_ready() {
+            let ptr = pool::alloc(layout);
+            if !ptr.is_null() {
+                return ptr;
+            }
+        }
+
+        #[cfg(all(target_arch = "xtensa", feature = "esp-psram"))]
+        {
+            psram::alloc(layout, get_embedded_heap())
+        }
+        #[cfg(not(all(target_arch = "xtensa", feature = "esp-psram")))]
+        {
+            get_embedded_heap().alloc(layout)
+        }
+    }
+
+    // With `manual-embedded-init`, the pool waits for the heap so that nothing is
+    // allocated before init_embedded_heap()
+    #[cfg(feature = "embedded-pool")]
+    #[inline]
+    fn pool_ready() -> bool {
+        #[cfg(feature = "manual-embedded-init")]
+        return MANUAL_HEAP_INITIALIZED.load(core::sync::atomic::Ordering::Acquire);
+        #[cfg(not(feature = "manual-embedded-init"))]
+        true
+    }
+
+    #[inline]
+    pub unsafe fn heap_dealloc(ptr: *mut u8, layout: core::alloc::Layout) {
+        #[cfg(feature = "embedded-pool")]
+        if pool::owns(ptr) {
+            return pool::dealloc(ptr);
+        }
+        #[cfg(all(target_arch = "xtensa", feature = "esp-psram"))]
+        if psram::owns(ptr) {
+            return psram::dealloc(ptr, layout);
+        }
+        get_embedded_heap().dealloc(ptr, layout)
+    }
+
+    #[inline]
+    pub unsafe fn heap_realloc(ptr: *mut u8, layout: core::alloc::Layout, new_size: usize) -> *mut u8 {
+        let mut new_ptr = heap_realloc_once(ptr, layout, new_size);
+        if new_ptr.is_null() {
+            let new_layout = core::alloc::Layout::from_size_align_unchecked(new_size, layout.align());
+            new_ptr = handle_exhaustion(new_layout, || heap_realloc_once(ptr, layout, new_size));
+        }
+        debug_assert_aligned(new_ptr, layout.align());
+        new_ptr
+    }
+
+    #[inline]
+    unsafe fn heap_realloc_once(ptr: *mut u8, layout: core::alloc::Layout, new_size: usize) -> *mut u8 {
+        // A pool block can't grow past its class: move it once it no longer fits
+        #[cfg(feature = "embedded-pool")]
+        if pool::owns(ptr) {
+            if new_size <= pool::block_size(ptr) {
+                return ptr;
+            }
+            let new_ptr = heap_alloc_once(core::alloc::Layout::from_size_align_unchecked(new_size, layout.align()));
+            if !new_ptr.is_null() {
+                core::ptr::copy_nonoverlapping(ptr, new_ptr, layout.size());
+                pool::dealloc(ptr);
+            }
+            return new_ptr;
+        }
+
+        #[cfg(all(target_arch = "xtensa", feature = "esp-psram"))]
+        {
+            // The block may move between SRAM and PSRAM, so always go through the router
+            let new_layout = core::alloc::Layout::from_size_align_unchecked(new_size, layout.align());
+            let new_ptr = heap_alloc_once(new_layout);
+            if !new_ptr.is_null() {
+                core::ptr::copy_nonoverlapping(ptr, new_ptr, layout.size().min(new_size));
+                heap_dealloc(ptr, layout);
+            }
+            new_ptr
+        }
+        #[cfg(not(all(target_arch = "xtensa", feature = "esp-psram")))]
+        {
+            get_embedded_heap().realloc(ptr, layout, new_size)
+        }
+    }
+
+    // `esp-psram`: external SPI PSRAM as a second heap on ESP32-family (Xtensa) chips.
+    // Allocations of at least PSRAM_THRESHOLD bytes go to PSRAM; smaller ones stay in
+    // the fast on-chip heap and only spill over to PSRAM when it is full
+    #[cfg(all(target_arch = "xtensa", feature = "esp-psram"))]
+    pub mod psram {
+        use core::alloc::{GlobalAlloc, Layout};
+        use core::sync::atomic::{AtomicU8, AtomicUsize, Ordering};
+        use embedded_alloc::Heap;
+
+        /// Requests of at least this many bytes are served from PSRAM (`AUTO_ALLOCATOR_PSRAM_THRESHOLD`)
+        pub const PSRAM_THRESHOLD: usize = match option_env!("AUTO_ALLOCATOR_PSRAM_THRESHOLD") {
+            Some(bytes) => crate::parse_build_setting(bytes),
+            None => 1024,
+        };
+
+        // Build-time region (AUTO_ALLOCATOR_PSRAM_BASE / _SIZE; build.rs requires both or neither)
+        const BUILD_REGION: Option<(usize, usize)> = match (
+            option_env!("AUTO_ALLOCATOR_PSRAM_BASE"),
+            option_env!("AUTO_ALLOCATOR_PSRAM_SIZE"),
+        ) {
+            (Some(base), Some(size)) => {
+                Some((crate::parse_build_setting(base), crate::parse_build_setting(size)))
+            }
+            _ => None,
+        };
+
+        /// PSRAM bytes configured at build time, counted in `SystemInfo::total_memory_bytes`
+        pub const BUILD_SIZE: usize = match BUILD_REGION {
+            Some((_, size)) => size,
+            None => 0,
+        };
+
+        const UNSET: u8 = 0;
+        const INITIALIZING: u8 = 1;
+        const READY: u8 = 2;
+
+        static mut PSRAM_HEAP: Heap = Heap::empty();
+        static PSRAM_STATE: AtomicU8 = AtomicU8::new(UNSET);
+        static PSRAM_START: AtomicUsize = AtomicUsize::new(0);
+        static PSRAM_END: AtomicUsize = AtomicUsize::new(0);
+
+        // Claims and initializes the PSRAM heap; false if another caller already did
+        unsafe fn setup(start: usize, size: usize) -> bool {
+            if PSRAM_STATE
+                .compare_exchange(UNSET, INITIALIZING, Ordering::AcqRel, Ordering::Acquire)
+                .is_err()
+            {
+                return false;
+            }
+            (*core::ptr::addr_of!(PSRAM_HEAP)).init(start, size);
+            PSRAM_START.store(start, Ordering::Relaxed);
+            PSRAM_END.store(start + size, Ordering::Relaxed);
+            PSRAM_STATE.store(READY, Ordering::Release);
+            true
+        }
+
+        // The PSRAM heap once it has a region; the build-time region is set up on first use
+        fn heap() -> Option<&'static Heap> {
+            loop {
+                match PSRAM_STATE.load(Ordering::Acquire) {
+                    READY => return Some(unsafe { &*core::ptr::addr_of!(PSRAM_HEAP) }),
+                    INITIALIZING => core::hint::spin_loop(), // the other core is setting it up
+                    _ => {
+                        let (start, size) = BUILD_REGION?;
+                        unsafe { setup(start, size) };
+                    }
+                }
+            }
+        }
+
+        /// Hands the PSRAM heap its region at runtime (`esp-psram` feature)
+        ///
+        /// For firmware that learns the mapping at startup, e.g. from the HAL's PSRAM
+        /// initialization, instead of fixing it with `AUTO_ALLOCATOR_PSRAM_BASE` /
+        /// `AUTO_ALLOCATOR_PSRAM_SIZE`. Until then (and without a build-time region),
+        /// every allocation stays in the on-chip heap.
+        ///
+        /// # Panics
+        ///
+        /// Panics if the PSRAM heap already has a region - from an earlier call, or
+        /// from the build-time settings after a large allocation used it.
+        ///
+        /// # Safety
+        ///
+        /// `start..start + size` must be mapped, writable PSRAM reserved for the heap
+        /// for the rest of the program (not also used by the HAL or a DMA buffer).
+        ///
+        /// # Example
+        ///
+        /// ```rust,ignore
+        /// let (start, size) = esp_hal::psram::psram_raw_parts(&peripherals.PSRAM);
+        /// unsafe { auto_allocator::init_psram_heap(start as usize, size) };
+        /// ```
+        pub unsafe fn init_psram_heap(start: usize, size: usize) {
+            if !setup(start, size) {
+                panic!("auto-allocator: PSRAM heap already initialized");
+            }
+        }
+
+        /// Whether `ptr` lies in the PSRAM heap
+        #[inline]
+        pub fn owns(ptr: *mut u8) -> bool {
+            let address = ptr as usize;
+            PSRAM_STATE.load(Ordering::Acquire) == READY
+                && address >= PSRAM_START.load(Ordering::Relaxed)
+                && address < PSRAM_END.load(Ordering::Relaxed)
+        }
+
+        pub unsafe fn alloc(layout: Layout, sram: &Heap) -> *mut u8 {
+            if layout.size() >= PSRAM_THRESHOLD {
+                if let Some(psram) = heap() {
+                    let ptr = psram.alloc(layout);
+                    if !ptr.is_null() {
+                        return ptr;
+                    }
+                }
+                return sram.alloc(layout);
+            }
+
+            let ptr = sram.alloc(layout);
+            if ptr.is_null() {
+                return heap().map_or(core::ptr::null_mut(), |psram| psram.alloc(layout));
+            }
+            ptr
+        }
+
+        pub unsafe fn dealloc(ptr: *mut u8, layout: Layout) {
+            (*core::ptr::addr_of!(PSRAM_HEAP)).dealloc(ptr, layout)
+        }
+
+        /// Replaces the PSRAM heap with an empty one, see [`super::reset_embedded_heap()`]
+        pub unsafe fn reset() {
+            if PSRAM_STATE.load(Ordering::Acquire) == READY {
+                let start = PSRAM_START.load(Ordering::Relaxed);
+                let size = PSRAM_END.load(Ordering::Relaxed) - start;
+                critical_section::with(|_| {
+                    *core::ptr::addr_of_mut!(PSRAM_HEAP) = super::fresh_heap(start, size);
+                });
+            }
+        }
+    }
+
+    // `embedded-pool`: fixed-size blocks for the smallest requests, in front of the heap.
+    // Each class is a static array of blocks plus a bitmap (bit set = block in use)
+    // claimed with compare-and-swap, so the pool is lock-free and interrupt-safe and a
+    // hit never walks the heap's free list
+    #[cfg(feature = "embedded-pool")]
+    pub mod pool {
+        use core::alloc::Layout;
+        use core::sync::atomic::{AtomicUsize, Ordering};
+
+        /// Block sizes of the three classes, smallest first
+        /// (`AUTO_ALLOCATOR_POOL_SMALL` / `_MEDIUM` / `_LARGE`, default 16 / 32 / 64 bytes)
+        pub const CLASS_SIZES: [usize; 3] = [
+            match option_env!("AUTO_ALLOCATOR_POOL_SMALL") {
+                Some(bytes) => crate::parse_build_setting(bytes),
+                None => 16,
+            },
+            match option_env!("AUTO_ALLOCATOR_POOL_MEDIUM") {
+                Some(bytes) => crate::parse_build_setting(bytes),
+                None => 32,
+            },
+            match option_env!("AUTO_ALLOCATOR_POOL_LARGE") {
+                Some(bytes) => crate::parse_build_setting(bytes),
+                None => 64,
+            },
+        ];
+
+        /// Blocks in each class (`AUTO_ALLOCATOR_POOL_BLOCKS`, default 8, at most `usize::BITS`)
+        pub const BLOCKS_PER_CLASS: usize = match option_env!("AUTO_ALLOCATOR_POOL_BLOCKS") {
+            Some(blocks) => crate::parse_build_setting(blocks),
+            None => 8,
+        };
+
+        // Blocks start on 8-byte boundaries; more strictly aligned requests use the heap
+        const BLOCK_ALIGN: usize = 8;
+
+        const _: () = assert!(
+            BLOCKS_PER_CLASS <= usize::BITS as usize,
+            "auto-allocator: AUTO_ALLOCATOR_POOL_BLOCKS can't exceed the bits in a usize (32 on 32-bit MCUs)"
+        );
+        const _: () = assert!(
+            CLASS_SIZES[0] < CLASS_SIZES[1] && CLASS_SIZES[1] < CLASS_SIZES[2],
+            "auto-allocator: pool class sizes must be ascending (SMALL < MEDIUM < LARGE)"
+        );
+        const _: () = assert!(
+            CLASS_SIZES[0] % BLOCK_ALIGN == 0
+                && CLASS_SIZES[1] % BLOCK_ALIGN == 0
+                && CLASS_SIZES[2] % BLOCK_ALIGN == 0,
+            "auto-allocator: pool class sizes must be multiples of 8 bytes"
+        );
+
+        // Classes are laid out back to back in one array
+        const CLASS_OFFSETS: [usize; 3] = [
+            0,
+            CLASS_SIZES[0] * BLOCKS_PER_CLASS,
+            (CLASS_SIZES[0] + CLASS_SIZES[1]) * BLOCKS_PER_CLASS,
+        ];
+        const POOL_BYTES: usize = (CLASS_SIZES[0] + CLASS_SIZES[1] + CLASS_SIZES[2]) * BLOCKS_PER_CLASS;
+        const ALL_BLOCKS: usize = if BLOCKS_PER_CLASS == usize::BITS as usize {
+            usize::MAX
+        } else {
+            (1 << BLOCKS_PER_CLASS) - 1
+        };
+
+        #[repr(C, align(8))]
+        struct PoolMemory([u8; POOL_BYTES]);
+
+        static mut POOL_MEMORY: PoolMemory = PoolMemory([0; POOL_BYTES]);
+
+        #[allow(clippy::declare_interior_mutable_const)]
+        const NONE_IN_USE: AtomicUsize = AtomicUsize::new(0);
+        static IN_USE: [AtomicUsize; 3] = [NONE_IN_USE; 3];
+
+        #[inline]
+        fn base() -> usize {
+            core::ptr::addr_of!(POOL_MEMORY) as usize
+        }
+
+        /// Whether `ptr` is a pool block
+        #[inline]
+        pub fn owns(ptr: *mut u8) -> bool {
+            (ptr as usize).wrapping_sub(base()) < POOL_BYTES
+        }
+
+        // (class, block index) of a pool pointer
+        #[inline]
+        fn locate(ptr: *mut u8) -> (usize, usize) {
+            let offset = ptr as usize - base();
+            let class = if offset < CLASS_OFFSETS[1] {
+                0
+            } else if offset < CLASS_OFFSETS[2] {
+                1
+            } else {
+                2
+            };
+            (class, (offset - CLASS_OFFSETS[class]) / CLASS_SIZES[class])
+        }
+
+        /// Block size of the class `ptr` (a pool block) belongs to
+        #[inline]
+        pub fn block_size(ptr: *mut u8) -> usize {
+            CLASS_SIZES[locate(ptr).0]
+        }
+
+        /// Claims a block from the smallest class that fits and has one free, or null
+        #[inline]
+        pub fn alloc(layout: Layout) -> *mut u8 {
+            if layout.align() > BLOCK_ALIGN {
+                return core::ptr::null_mut();
+            }
+            for class in 0..CLASS_SIZES.len() {
+                if layout.size() > CLASS_SIZES[class] {
+                    continue;
+                }
+                let claimed = IN_USE[class].fetch_update(Ordering::Acquire, Ordering::Relaxed, |used| {
+                    let free = !used & ALL_BLOCKS;
+                    // Lowest free bit
+                    (free != 0).then(|| used | (free & free.wrapping_neg()))
+                });
+                if let Ok(used) = claimed {
+                    let block = (!used & ALL_BLOCKS).trailing_zeros() as usize;
+                    return (base() + CLASS_OFFSETS[class] + block * CLASS_SIZES[class]) as *mut u8;
+                }
+            }
+            core::ptr::null_mut()
+        }
+
+        /// Returns a pool block (`owns(ptr)` must hold)
+        #[inline]
+        pub fn dealloc(ptr: *mut u8) {
+            let (class, block) = locate(ptr);
+            IN_USE[class].fetch_and(!(1 << block), Ordering::Release);
+        }
+
+        /// Marks every block free, see [`super::reset_embedded_heap()`]
+        pub fn reset() {
+            for in_use in &IN_USE {
+                in_use.store(0, Ordering::Release);
+            }
+        }
+    }
+}
+
+#[cfg(all(target_os = "none", feature = "_embedded", feature = "manual-embedded-init"))]
+pub use embedded_heap_config::init_embedded_heap;
+
+#[cfg(all(target_os = "none", feature = "_embedded"))]
+pub use embedded_heap_config::{reset_embedded_heap, set_embedded_oom_handler, EMBEDDED_HEAP_MAX_ALIGN};
+
+#[cfg(all(target_os = "none", target_arch = "xtensa", feature = "_embedded", feature = "esp-psram"))]
+pub use embedded_heap_config::psram::init_psram_heap;
+
+// User-declared embedded heaps: size and placement chosen by the firmware instead of
+// AUTO_ALLOCATOR_HEAP_SIZE, plugged in through the custom allocator slot
+#[cfg(all(target_os = "none", feature = "_embedded"))]
+mod static_heap {
+    use core::alloc::{GlobalAlloc, Layout};
+    use core::cell::UnsafeCell;
+    use core::sync::atomic::{AtomicBool, Ordering};
+    use embedded_alloc::Heap;
+
+    /// An embedded heap with its own `N`-byte backing array
+    ///
+    /// Declare it as a `static` - with `#[link_section]` to put it in a particular RAM
+    /// bank (CCM, DTCM, external SRAM) - and call [`as_global()`](StaticHeap::as_global)
+    /// first thing in the entry point. Every allocation then comes from this heap
+    /// instead of the built-in one, whose size is fixed by `AUTO_ALLOCATOR_HEAP_SIZE`.
+    /// Firmware that doesn't declare one keeps the built-in heap.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use auto_allocator::StaticHeap;
+    ///
+    /// #[link_section = ".ccmram"]
+    /// static HEAP: StaticHeap<{ 32 * 1024 }> = StaticHeap::new();
+    ///
+    /// #[entry]
+    /// fn main() -> ! {
+    ///     assert!(HEAP.as_global());
+    ///     let buffer = vec![0u8; 1024]; // from the 32KB CCM heap
+    ///     // ...
+    /// }
+    /// ```
+    pub struct StaticHeap<const N: usize> {
+        heap: Heap,
+        memory: UnsafeCell<[u8; N]>,
+        claimed: AtomicBool,
+    }
+
+    // The array is only handed to `heap` (once, in as_global); Heap does its own locking
+    unsafe impl<const N: usize> Sync for StaticHeap<N> {}
+
+    impl<const N: usize> StaticHeap<N> {
+        /// Creates the heap; nothing is allocated from it until [`as_global()`](StaticHeap::as_global)
+        pub const fn new() -> StaticHeap<N> {
+            assert!(N > 0, "auto-allocator: StaticHeap needs a nonzero size");
+            StaticHeap {
+                heap: Heap::empty(),
+                memory: UnsafeCell::new([0; N]),
+                claimed: AtomicBool::new(false),
+            }
+        }
+
+        /// Initializes the heap and makes it the backend for all allocations
+        ///
+        /// Like [`register_custom_allocator()`](crate::register_custom_allocator), this must
+        /// run before the first allocation, since memory can't move between heaps. Returns
+        /// `false` when it was too late, when another allocator was registered first, or
+        /// when this heap was already claimed - the current heap then stays in use.
+        pub fn as_global(&'static self) -> bool {
+            if self.claimed.swap(true, Ordering::AcqRel) {
+                return false;
+            }
+            unsafe { self.heap.init(self.memory.get() as usize, N) };
+            crate::register_custom_allocator(self)
+        }
+
+        /// Bytes currently allocated from this heap
+        pub fn used(&self) -> usize {
+            self.heap.used()
+        }
+
+        /// Bytes still free in this heap
+        pub fn free(&self) -> usize {
+            self.heap.free()
+        }
+    }
+
+    impl<const N: usize> Default for StaticHeap<N> {
+        fn default() -> StaticHeap<N> {
+            StaticHeap::new()
+        }
+    }
+
+    unsafe impl<const N: usize> GlobalAlloc for StaticHeap<N> {
+        #[inline]
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            self.heap.alloc(layout)
+        }
+
+        #[inline]
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            self.heap.dealloc(ptr, layout)
+        }
+    }
+}
+
+#[cfg(all(target_os = "none", feature = "_embedded"))]
+pub use static_heap::StaticHeap;
+
+// ========== Safe Runtime Allocator Implementation ==========
+
+pub struct RuntimeAllocator;
+
+impl RuntimeAllocator {
+    #[inline]
+    fn get_allocator_id() -> u8 {
+        // Relaxed is enough on the fast path: the ID is a self-contained u8, and once
+        // published it never changes. The only dependent data is the custom allocator
+        // slot, which custom_allocator() orders with its own acquire fence.
+        let current_id = RUNTIME_ALLOCATOR_ID.load(Ordering::Relaxed);
+
+        if unlikely(current_id == 0) {
+            #[cfg(not(any(target_os = "none", all(target_arch = "wasm32", target_os = "unknown"))))]
+            let started = std::time::Instant::now();
+
+            // From here on, setters report that they came too late for this selection
+            seal_selection_config();
+
+            // First call, perform hardware detection and selection
+            let selected_id = detect_allocator_id();
+            #[cfg(all(feature = "testing", not(target_os = "none")))]
+            testing::run_selection_barrier();
+            debug_assert!(
+                has_dispatch_arm(selected_id),
+                "auto-allocator: selected allocator {} has no dispatch arm in this build",
+                selected_id
+            );
+
+            // Lost the race to another thread's selection or register_custom_allocator()
+            if let Err(existing_id) = RUNTIME_ALLOCATOR_ID.compare_exchange(
+                0,
+                selected_id,
+                Ordering::AcqRel,
+                Ordering::Acquire,
+            ) {
+                return existing_id;
+            }
+
+            #[cfg(all(feature = "huge-pages", not(target_os = "none")))]
+            mimalloc_options::enable_huge_pages_if_available(selected_id);
+
+            if selected_id == 2 || selected_id == 5 {
+                match get_allocator_profile() {
+                    AllocatorProfile::Footprint => tune_mimalloc_for_footprint(),
+                    AllocatorProfile::LowLatency => tune_mimalloc_for_latency(),
+                    _ => {}
+                }
+            }
+
+            // Record selection information (ensure only logged once)
+            Self::log_allocator_selection(selected_id);
+
+            #[cfg(not(any(target_os = "none", all(target_arch = "wasm32", target_os = "unknown"))))]
+            let _ = SELECTION_DURATION.set(started.elapsed());
+
+            selected_id
+        } else {
+            current_id
+        }
+    }
+
+    #[cold]
+    #[cfg(all(not(feature = "no-log"), not(target_os = "none")))]
+    fn log_allocator_selection(allocator_id: u8) {
+        if ALLOCATOR_LOGGED
+            .compare_exchange(false, true, Ordering::AcqRel, Ordering::Acquire)
+            .is_ok()
+        {
+            let system_info = collect_system_info();
+            let reason = selection_reason_for_id(allocator_id, &system_info);
+            // Falling back because something failed is what `AUTO_ALLOCATOR_LOG=errors` keeps
+            let failed = reason == SelectionReason::ProbeFailed;
+            record_allocator_selection(
+                allocator_name_for_id(allocator_id),
+                &reason.to_string(),
+                &system_info,
+                failed,
+            );
+        }
+    }
+
+    #[cold]
+    #[cfg(any(feature = "no-log", target_os = "none"))]
+    fn log_allocator_selection(_allocator_id: u8) {
+        // No logging in no_std environments or with the `no-log` feature
+    }
+}
+
+/// Canonical short name for a stable allocator ID (see [`allocator_id()`])
+const fn allocator_name_for_id(allocator_id: u8) -> &'static str {
+    match allocator_id {
+        5 => "mimalloc-secure",
+        2 => "mimalloc",
+        4 => "embedded-alloc",
+        6 => "custom",
+        _ => "system",
+    }
+}
+
+impl RuntimeAllocator {
+    /// Allocator ID for the backend dispatch `match`
+    ///
+    /// With a build-time choice, the fast path is one relaxed load compared against the
+    /// constant; on that path the `match` folds to the constant's arm, but the function
+    /// does not return a constant outright. The load stays because the first allocation still has to publish and
+    /// log the ID, and a pre-main [`register_custom_allocator()`] can replace the
+    /// build-time choice with ID 6 - both take the cold [`Self::get_allocator_id()`] path.
+    #[inline(always)]
+    fn backend_id() -> u8 {
+        match COMPILE_TIME_ALLOCATOR_ID {
+            Some(allocator_id) => {
+                if unlikely(RUNTIME_ALLOCATOR_ID.load(Ordering::Relaxed) != allocator_id) {
+                    return Self::get_allocator_id();
+                }
+                allocator_id
+            }
+            None => Self::get_allocator_id(),
+        }
+    }
+}
+
+/// Makes mimalloc hand freed memory back to the OS right away (`Footprint` profile)
+///
+/// Equivalent to `MIMALLOC_RESET_DELAY=0 MIMALLOC_PAGE_RESET=1 MIMALLOC_SEGMENT_RESET=1
+/// MIMALLOC_RESET_DECOMMITS=1 MIMALLOC_EAGER_COMMIT=0`.
+#[cfg(auto_allocator_mimalloc)]
+#[cold]
+fn tune_mimalloc_for_footprint() {
+    use mimalloc_rust::raw::runtime_options::{
+        mi_option_eager_commit, mi_option_page_reset, mi_option_reset_decommits,
+        mi_option_reset_delay, mi_option_segment_reset,
+    };
+    use mimalloc_rust::GlobalMiMalloc;
+
+    GlobalMiMalloc::option_set(mi_option_reset_delay, 0);
+    GlobalMiMalloc::option_enable(mi_option_page_reset);
+    GlobalMiMalloc::option_enable(mi_option_segment_reset);
+    GlobalMiMalloc::option_enable(mi_option_reset_decommits);
+    GlobalMiMalloc::option_disable(mi_option_eager_commit);
+}
+
+// mimalloc is never selected here
+#[cfg(not(auto_allocator_mimalloc))]
+fn tune_mimalloc_for_footprint() {}
+
+/// Keeps mimalloc from faulting pages in or giving them back on the allocation path (`LowLatency` profile)
+///
+/// Equivalent to `MIMALLOC_EAGER_COMMIT=1 MIMALLOC_EAGER_REGION_COMMIT=1
+/// MIMALLOC_EAGER_COMMIT_DELAY=0 MIMALLOC_PAGE_RESET=0 MIMALLOC_SEGMENT_RESET=0
+/// MIMALLOC_RESET_DECOMMITS=0`: segments are committed up front, from a thread's first
+/// one on, and freed pages stay committed for reuse - more RSS for fewer page faults.
+#[cfg(auto_allocator_mimalloc)]
+#[cold]
+fn tune_mimalloc_for_latency() {
+    use mimalloc_rust::raw::runtime_options::{
+        mi_option_eager_commit, mi_option_eager_commit_delay, mi_option_eager_region_commit,
+        mi_option_page_reset, mi_option_reset_decommits, mi_option_segment_reset,
+    };
+    use mimalloc_rust::GlobalMiMalloc;
+
+    GlobalMiMalloc::option_enable(mi_option_eager_commit);
+    GlobalMiMalloc::option_enable(mi_option_eager_region_commit);
+    GlobalMiMalloc::option_set(mi_option_eager_commit_delay, 0);
+    GlobalMiMalloc::option_disable(mi_option_page_reset);
+    GlobalMiMalloc::option_disable(mi_option_segment_reset);
+    GlobalMiMalloc::option_disable(mi_option_reset_decommits);
+}
+
+// mimalloc is never selected here
+#[cfg(not(auto_allocator_mimalloc))]
+fn tune_mimalloc_for_latency() {}
+
+// Branch prediction optimization
+#[inline(always)]
+fn unlikely(b: bool) -> bool {
+    #[cold]
+    fn cold() {}
+    if b {
+        cold();
+    }
+    b
+}
+
+// ========== Global Allocator Implementation - Platform-specific VTable handling ==========
+
+impl RuntimeAllocator {
+    /// Allocates from the selected backend, without any instrumentation layers
+    #[inline(always)]
+    unsafe fn backend_alloc(layout: Layout) -> *mut u8 {
+        match Self::backend_id() {
+
+            // mimalloc-secure - security-hardened allocator with 10% performance overhead
+            #[cfg(all(auto_allocator_mimalloc, feature = "_mimalloc_secure"))]
+            5 => {
+                mimalloc_rust::GlobalMiMalloc.alloc(layout)
+            }
+
+            // mimalloc - high-performance allocator with compiler compatibility detection
+            #[cfg(all(auto_allocator_mimalloc, feature = "_mimalloc"))]
+            2 => {
+                mimalloc_rust::GlobalMiMalloc.alloc(layout)
+
+            }
+
+            // embedded-alloc - for all no_std embedded platforms
+            #[cfg(all(
+                feature = "_embedded",
+                target_os = "none"
+            ))]
+            4 => {
+                // Use embedded-alloc for all no_std targets
+                #[cfg(not(target_os = "none"))]
+                {
+                    embedded_heap_config::EMBEDDED_HEAP.alloc(layout)
+                }
+                #[cfg(target_os = "none")]
+                {
+                    embedded_heap_config::heap_alloc(layout)
+                }
+            }
+
+            // User-supplied allocator from register_custom_allocator()
+            6 => custom_allocator().alloc(layout),
+
+            // System allocator - default fallback
+            #[cfg(not(target_os = "none"))]
+            _ => alloc::System.alloc(layout),
+            
+            #[cfg(target_os = "none")]
+            _ => core::ptr::null_mut(),
+        }
+    }
+
+    /// Deallocates through the selected backend, without any instrumentation layers
+    #[inline(always)]
+    unsafe fn backend_dealloc(ptr: *mut u8, layout: Layout) {
+        match Self::backend_id() {
+
+            // mimalloc-secure - security-hardened allocator
+            #[cfg(all(auto_allocator_mimalloc, feature = "_mimalloc_secure"))]
+            5 => {
+                mimalloc_rust::GlobalMiMalloc.dealloc(ptr, layout)
+            }
+
+            // mimalloc - high-performance allocator with compiler compatibility detection
+            #[cfg(all(auto_allocator_mimalloc, feature = "_mimalloc"))]
+            2 => {
+                mimalloc_rust::GlobalMiMalloc.dealloc(ptr, layout)
+            }
 
-            // mimalloc-secure - security-hardened allocator with 10% performance overhead
             #[cfg(all(
-                feature = "_mimalloc_secure",
-                not(target_arch = "wasm32"),
-                not(debug_assertions),
-                not(target_os = "none")
+                feature = "_embedded",
+                target_os = "none"
             ))]
+            4 => {
+                // Use embedded-alloc for all no_std targets
+                #[cfg(not(target_os = "none"))]
+                {
+                    embedded_heap_config::EMBEDDED_HEAP.dealloc(ptr, layout)
+                }
+                #[cfg(target_os = "none")]
+                {
+                    embedded_heap_config::heap_dealloc(ptr, layout)
+                }
+            }
+
+            6 => custom_allocator().dealloc(ptr, layout),
+
+            #[cfg(not(target_os = "none"))]
+            _ => alloc::System.dealloc(ptr, layout),
+            
+            #[cfg(target_os = "none")]
+            _ => {},
+        }
+    }
+
+    /// Resizes through the selected backend, without any instrumentation layers
+    ///
+    /// mimalloc grows in place within the block's size class via `mi_expand`, and its
+    /// realloc keeps shrinks in place while at least half the block stays used.
+    /// The system allocator uses its own realloc; embedded-alloc copies.
+    #[inline(always)]
+    unsafe fn backend_realloc(ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        match Self::backend_id() {
+
+            // mimalloc-secure / mimalloc - try in-place growth before a moving realloc
+            #[cfg(all(auto_allocator_mimalloc, feature = "_mimalloc_secure"))]
             5 => {
-                mimalloc_rust::GlobalMiMalloc.alloc(layout)
+                Self::mimalloc_realloc(ptr, layout, new_size)
+            }
+
+            #[cfg(all(auto_allocator_mimalloc, feature = "_mimalloc"))]
+            2 => {
+                Self::mimalloc_realloc(ptr, layout, new_size)
+            }
+
+            #[cfg(all(
+                feature = "_embedded",
+                target_os = "none"
+            ))]
+            4 => {
+                embedded_heap_config::heap_realloc(ptr, layout, new_size)
+            }
+
+            6 => custom_allocator().realloc(ptr, layout, new_size),
+
+            #[cfg(not(target_os = "none"))]
+            _ => alloc::System.realloc(ptr, layout, new_size),
+
+            #[cfg(target_os = "none")]
+            _ => core::ptr::null_mut(),
+        }
+    }
+
+    #[cfg(auto_allocator_mimalloc)]
+    #[inline(always)]
+    unsafe fn mimalloc_realloc(ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        use mimalloc_rust::raw::basic_allocation::mi_expand;
+
+        if new_size > layout.size() {
+            // Grow: mi_expand never moves, so alignment is preserved; null = doesn't fit
+            let expanded = mi_expand(ptr as *mut core::ffi::c_void, new_size);
+            if !expanded.is_null() {
+                return expanded as *mut u8;
+            }
+        }
+        mimalloc_rust::GlobalMiMalloc.realloc(ptr, layout, new_size)
+    }
+}
+
+unsafe impl GlobalAlloc for RuntimeAllocator {
+    #[inline]
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        #[cfg(all(feature = "realtime", debug_assertions, not(target_os = "none")))]
+        let _watch = realtime::hot_path::Watch::start();
+
+        #[cfg(all(feature = "bump", not(target_os = "none")))]
+        let ptr = bump::try_alloc(layout).unwrap_or_else(|| Self::backend_alloc(layout));
+        #[cfg(not(all(feature = "bump", not(target_os = "none"))))]
+        let ptr = Self::backend_alloc(layout);
+
+        #[cfg(all(feature = "stats", not(target_os = "none")))]
+        if !ptr.is_null() {
+            stats::record_alloc(layout.size(), layout.align());
+            stats::tags::record_alloc(ptr, layout.size());
+        }
+
+        #[cfg(all(feature = "profiling", not(target_os = "none")))]
+        if !ptr.is_null() {
+            profiling::record_alloc(ptr, layout.size());
+        }
+
+        #[cfg(all(feature = "trace-all", not(target_os = "none")))]
+        if !ptr.is_null() {
+            trace::record(trace::AllocTraceKind::Alloc, ptr, layout.size());
+        }
+
+        #[cfg(all(feature = "poison-alloc", debug_assertions))]
+        if !ptr.is_null() {
+            poison::fill_fresh(ptr, layout.size());
+        }
+
+        ptr
+    }
+
+    #[inline]
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        #[cfg(all(feature = "poison-alloc", debug_assertions))]
+        poison::fill_freed(ptr, layout.size());
+
+        #[cfg(all(feature = "stats", not(target_os = "none")))]
+        {
+            stats::record_dealloc(layout.size());
+            stats::tags::record_dealloc(ptr, layout.size());
+        }
+
+        #[cfg(all(feature = "profiling", not(target_os = "none")))]
+        profiling::record_dealloc(ptr, layout.size());
+
+        #[cfg(all(feature = "trace-all", not(target_os = "none")))]
+        trace::record(trace::AllocTraceKind::Dealloc, ptr, layout.size());
+
+        // Bump arena memory is only reclaimed by reset_bump()
+        #[cfg(all(feature = "bump", not(target_os = "none")))]
+        if bump::owns(ptr) {
+            return;
+        }
+
+        Self::backend_dealloc(ptr, layout)
+    }
+
+    #[inline]
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        // Bump arena blocks can't be resized by the backend - move them out by copying
+        #[cfg(all(feature = "bump", not(target_os = "none")))]
+        if bump::owns(ptr) {
+            let new_layout = Layout::from_size_align_unchecked(new_size, layout.align());
+            let new_ptr = self.alloc(new_layout);
+            if !new_ptr.is_null() {
+                core::ptr::copy_nonoverlapping(ptr, new_ptr, layout.size().min(new_size));
+                self.dealloc(ptr, layout);
+            }
+            return new_ptr;
+        }
+
+        #[cfg(all(feature = "realtime", debug_assertions, not(target_os = "none")))]
+        let _watch = realtime::hot_path::Watch::start();
+
+        // Shrinking gives up the tail, but the block stays the caller's until the backend
+        // succeeds - so move explicitly and poison the old block only once the copy exists
+        #[cfg(all(feature = "poison-alloc", debug_assertions))]
+        let new_ptr = if new_size < layout.size() {
+            let new_ptr = Self::backend_alloc(Layout::from_size_align_unchecked(new_size, layout.align()));
+            if !new_ptr.is_null() {
+                core::ptr::copy_nonoverlapping(ptr, new_ptr, new_size);
+                poison::fill_freed(ptr, layout.size());
+                Self::backend_dealloc(ptr, layout);
+            }
+            new_ptr
+        } else {
+            Self::backend_realloc(ptr, layout, new_size)
+        };
+        #[cfg(not(all(feature = "poison-alloc", debug_assertions)))]
+        let new_ptr = Self::backend_realloc(ptr, layout, new_size);
+
+        #[cfg(all(feature = "poison-alloc", debug_assertions))]
+        if !new_ptr.is_null() && new_size > layout.size() {
+            poison::fill_fresh(new_ptr.add(layout.size()), new_size - layout.size());
+        }
+
+        // Counted like the default realloc: one dealloc of the old size, one alloc of the new
+        #[cfg(all(feature = "stats", not(target_os = "none")))]
+        if !new_ptr.is_null() {
+            stats::record_dealloc(layout.size());
+            stats::record_alloc(new_size, layout.align());
+            stats::record_realloc(new_ptr == ptr);
+            stats::tags::record_realloc(ptr, layout.size(), new_ptr, new_size);
+        }
+
+        #[cfg(all(feature = "profiling", not(target_os = "none")))]
+        if !new_ptr.is_null() {
+            profiling::record_dealloc(ptr, layout.size());
+            profiling::record_alloc(new_ptr, new_size);
+        }
+
+        #[cfg(all(feature = "trace-all", not(target_os = "none")))]
+        if !new_ptr.is_null() {
+            trace::record(trace::AllocTraceKind::Dealloc, ptr, layout.size());
+            trace::record(trace::AllocTraceKind::Alloc, new_ptr, new_size);
+        }
+
+        new_ptr
+    }
+}
+
+// Debug-build memory poisoning (`poison-alloc` feature): fresh memory reads as 0xAA and
+// freed memory as 0xDD, so use of uninitialized or freed memory shows a recognizable
+// pattern instead of plausible stale data. alloc_zeroed still zeroes, on top of the fill.
+#[cfg(all(feature = "poison-alloc", debug_assertions))]
+mod poison {
+    /// Byte pattern written over memory returned by `alloc` (and the grown part of `realloc`)
+    pub const FRESH: u8 = 0xAA;
+
+    /// Byte pattern written over memory passed to `dealloc` (and the dropped tail of `realloc`)
+    pub const FREED: u8 = 0xDD;
+
+    #[inline]
+    pub unsafe fn fill_fresh(ptr: *mut u8, size: usize) {
+        core::ptr::write_bytes(ptr, FRESH, size);
+    }
+
+    #[inline]
+    pub unsafe fn fill_freed(ptr: *mut u8, size: usize) {
+        core::ptr::write_bytes(ptr, FREED, size);
+    }
+}
+
+// `no-global` leaves the process's global allocator to the downstream crate
+#[cfg_attr(not(feature = "no-global"), global_allocator)]
+static GLOBAL: RuntimeAllocator = RuntimeAllocator;
+
+// ========== Allocation Statistics ==========
+
+// Process-wide and per-thread allocation counters (`stats` feature, std only)
+// Updated on every alloc/dealloc, so everything here must stay allocation-free
+#[cfg(all(feature = "stats", not(target_os = "none")))]
+mod stats {
+    use core::cell::Cell;
+    use core::sync::atomic::{AtomicU64, Ordering};
+
+    /// Allocation counters snapshot
+    ///
+    /// Returned by [`allocation_stats()`] (process-wide) and
+    /// [`thread_allocation_stats()`] (calling thread only).
+    /// Byte counts are requested sizes (`Layout::size()`), not allocator overhead.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let stats = auto_allocator::allocation_stats();
+    /// println!("{} allocations, {} bytes live", stats.alloc_count, stats.net_bytes());
+    /// ```
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+    pub struct AllocationStats {
+        /// Number of successful allocations
+        pub alloc_count: u64,
+
+        /// Number of deallocations
+        pub dealloc_count: u64,
+
+        /// Total bytes ever allocated
+        pub bytes_allocated: u64,
+
+        /// Total bytes ever deallocated
+        pub bytes_deallocated: u64,
+
+        /// Successful reallocs that kept the block where it was (grown or shrunk in place)
+        ///
+        /// Process-wide only: always 0 in [`thread_allocation_stats()`].
+        pub realloc_in_place: u64,
+
+        /// Successful reallocs that returned a new address (copied to a new block)
+        ///
+        /// Process-wide only: always 0 in [`thread_allocation_stats()`].
+        pub realloc_moved: u64,
+    }
+
+    impl AllocationStats {
+        /// Bytes allocated minus bytes deallocated
+        ///
+        /// Can be negative for per-thread stats when a thread frees memory
+        /// that another thread allocated.
+        pub fn net_bytes(&self) -> i64 {
+            self.bytes_allocated as i64 - self.bytes_deallocated as i64
+        }
+    }
+
+    static ALLOC_COUNT: AtomicU64 = AtomicU64::new(0);
+    static DEALLOC_COUNT: AtomicU64 = AtomicU64::new(0);
+    static BYTES_ALLOCATED: AtomicU64 = AtomicU64::new(0);
+    static BYTES_DEALLOCATED: AtomicU64 = AtomicU64::new(0);
+    static REALLOC_IN_PLACE: AtomicU64 = AtomicU64::new(0);
+    static REALLOC_MOVED: AtomicU64 = AtomicU64::new(0);
+
+    // Per-thread counters: const-initialized Cells without Drop, so first access
+    // neither allocates nor registers a TLS destructor
+    struct ThreadCounters {
+        alloc_count: Cell<u64>,
+        dealloc_count: Cell<u64>,
+        bytes_allocated: Cell<u64>,
+        bytes_deallocated: Cell<u64>,
+    }
+
+    thread_local! {
+        static THREAD_COUNTERS: ThreadCounters = const {
+            ThreadCounters {
+                alloc_count: Cell::new(0),
+                dealloc_count: Cell::new(0),
+                bytes_allocated: Cell::new(0),
+                bytes_deallocated: Cell::new(0),
+            }
+        };
+    }
+
+    /// Number of size classes reported by [`size_class_stats()`]
+    pub const SIZE_CLASSES: usize = 24;
+
+    // Power-of-two size classes: 0..=8, 9..=16, 17..=32, ..., the last one open-ended
+    #[inline]
+    fn size_class(size: usize) -> usize {
+        let bits = (usize::BITS - size.saturating_sub(1).leading_zeros()) as usize;
+        bits.saturating_sub(3).min(SIZE_CLASSES - 1)
+    }
+
+    /// Number of alignment classes reported by [`alignment_stats()`]
+    pub const ALIGNMENT_CLASSES: usize = 13;
+
+    #[allow(clippy::declare_interior_mutable_const)]
+    const ZERO: AtomicU64 = AtomicU64::new(0);
+    static CLASS_LIVE: [AtomicU64; SIZE_CLASSES] = [ZERO; SIZE_CLASSES];
+    static CLASS_BYTES: [AtomicU64; SIZE_CLASSES] = [ZERO; SIZE_CLASSES];
+    // Alignments 1, 2, 4, ..., 2048, then one class for 4096 (page) and above
+    static ALIGNMENT_COUNTS: [AtomicU64; ALIGNMENT_CLASSES] = [ZERO; ALIGNMENT_CLASSES];
+
+    #[inline]
+    pub(crate) fn record_alloc(size: usize, align: usize) {
+        ALLOC_COUNT.fetch_add(1, Ordering::Relaxed);
+        BYTES_ALLOCATED.fetch_add(size as u64, Ordering::Relaxed);
+
+        let class = size_class(size);
+        CLASS_LIVE[class].fetch_add(1, Ordering::Relaxed);
+        CLASS_BYTES[class].fetch_add(size as u64, Ordering::Relaxed);
+
+        // Layout alignments are powers of two, so the exponent is the class
+        let align_class = (align.trailing_zeros() as usize).min(ALIGNMENT_CLASSES - 1);
+        ALIGNMENT_COUNTS[align_class].fetch_add(1, Ordering::Relaxed);
+
+        // try_with: TLS may already be torn down during thread exit
+        let _ = THREAD_COUNTERS.try_with(|c| {
+            c.alloc_count.set(c.alloc_count.get() + 1);
+            c.bytes_allocated.set(c.bytes_allocated.get() + size as u64);
+        });
+    }
+
+    #[inline]
+    pub(crate) fn record_dealloc(size: usize) {
+        DEALLOC_COUNT.fetch_add(1, Ordering::Relaxed);
+        BYTES_DEALLOCATED.fetch_add(size as u64, Ordering::Relaxed);
+        CLASS_LIVE[size_class(size)].fetch_sub(1, Ordering::Relaxed);
+
+        let _ = THREAD_COUNTERS.try_with(|c| {
+            c.dealloc_count.set(c.dealloc_count.get() + 1);
+            c.bytes_deallocated.set(c.bytes_deallocated.get() + size as u64);
+        });
+    }
+
+    #[inline]
+    pub(crate) fn record_realloc(in_place: bool) {
+        if in_place {
+            REALLOC_IN_PLACE.fetch_add(1, Ordering::Relaxed);
+        } else {
+            REALLOC_MOVED.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Returns process-wide allocation counters
+    ///
+    /// Counters are updated with relaxed atomics, so a snapshot taken while
+    /// other threads allocate may be slightly inconsistent across fields.
+    pub fn allocation_stats() -> AllocationStats {
+        AllocationStats {
+            alloc_count: ALLOC_COUNT.load(Ordering::Relaxed),
+            dealloc_count: DEALLOC_COUNT.load(Ordering::Relaxed),
+            bytes_allocated: BYTES_ALLOCATED.load(Ordering::Relaxed),
+            bytes_deallocated: BYTES_DEALLOCATED.load(Ordering::Relaxed),
+            realloc_in_place: REALLOC_IN_PLACE.load(Ordering::Relaxed),
+            realloc_moved: REALLOC_MOVED.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Allocation activity in one size class, yielded by [`size_class_stats()`]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct SizeClassStat {
+        /// Smallest requested size in this class, in bytes
+        pub min_size: usize,
+
+        /// Largest requested size in this class, in bytes (`usize::MAX` for the last class)
+        pub max_size: usize,
+
+        /// Allocations in this class that are currently live
+        pub live_count: u64,
+
+        /// Total bytes ever allocated in this class
+        pub bytes_allocated: u64,
+    }
+
+    /// Returns process-wide counters per power-of-two size class, smallest first
+    ///
+    /// Classes are `0..=8`, `9..=16`, `17..=32` and so on up to 32MB, then one class
+    /// for everything larger - always [`SIZE_CLASSES`] items, including empty classes.
+    /// Shows where a workload's allocations fall: mostly small, short-lived blocks are
+    /// where mimalloc's per-size free lists pay off. A realloc counts as a free in
+    /// the old size's class and an allocation in the new one.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// for class in auto_allocator::size_class_stats().filter(|c| c.bytes_allocated > 0) {
+    ///     println!(
+    ///         "{}..={} bytes: {} live, {} bytes allocated",
+    ///         class.min_size, class.max_size, class.live_count, class.bytes_allocated
+    ///     );
+    /// }
+    /// ```
+    pub fn size_class_stats() -> impl Iterator<Item = SizeClassStat> {
+        (0..SIZE_CLASSES).map(|class| SizeClassStat {
+            min_size: if class == 0 { 0 } else { (4usize << class) + 1 },
+            max_size: if class == SIZE_CLASSES - 1 {
+                usize::MAX
+            } else {
+                8usize << class
+            },
+            live_count: CLASS_LIVE[class].load(Ordering::Relaxed),
+            bytes_allocated: CLASS_BYTES[class].load(Ordering::Relaxed),
+        })
+    }
+
+    /// Allocations requested with one alignment, yielded by [`alignment_stats()`]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct AlignmentStat {
+        /// Requested alignment in bytes (the last class also counts every larger alignment)
+        pub align: usize,
+
+        /// Allocations ever requested with this alignment
+        pub alloc_count: u64,
+    }
+
+    /// Returns process-wide allocation counts per requested alignment, smallest first
+    ///
+    /// Alignments `1`, `2`, `4`, ... `2048`, then `4096` for page alignment and above -
+    /// always [`ALIGNMENT_CLASSES`] items. Most allocations use their type's natural
+    /// alignment (8 or 16 bytes); a sizeable count at 32-64 (SIMD) or 4096 (DMA, page
+    /// buffers) means over-aligned requests, which allocators generally serve off their
+    /// fast paths - worth knowing when comparing allocators for the workload.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let over_aligned: u64 = auto_allocator::alignment_stats()
+    ///     .filter(|class| class.align > 16)
+    ///     .map(|class| class.alloc_count)
+    ///     .sum();
+    /// println!("{} over-aligned allocations", over_aligned);
+    /// ```
+    pub fn alignment_stats() -> impl Iterator<Item = AlignmentStat> {
+        (0..ALIGNMENT_CLASSES).map(|class| AlignmentStat {
+            align: 1 << class,
+            alloc_count: ALIGNMENT_COUNTS[class].load(Ordering::Relaxed),
+        })
+    }
+
+    /// Returns allocation counters for the calling thread only
+    ///
+    /// Useful for finding which worker thread churns memory. Counts start at
+    /// zero for each new thread and are a plain TLS increment on the hot path.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let handle = std::thread::spawn(|| {
+    ///     let _buffer = vec![0u8; 4096];
+    ///     auto_allocator::thread_allocation_stats()
+    /// });
+    /// let worker = handle.join().unwrap();
+    /// # #[cfg(not(feature = "no-global"))]
+    /// assert!(worker.bytes_allocated >= 4096);
+    /// ```
+    pub fn thread_allocation_stats() -> AllocationStats {
+        THREAD_COUNTERS
+            .try_with(|c| AllocationStats {
+                alloc_count: c.alloc_count.get(),
+                dealloc_count: c.dealloc_count.get(),
+                bytes_allocated: c.bytes_allocated.get(),
+                bytes_deallocated: c.bytes_deallocated.get(),
+                ..AllocationStats::default()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Allocation activity between two counter snapshots, returned by [`measure()`]
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+    pub struct AllocationDelta {
+        /// Allocations performed in the measured region
+        pub allocs: u64,
+
+        /// Deallocations performed in the measured region
+        pub frees: u64,
+
+        /// Bytes allocated minus bytes freed in the measured region
+        ///
+        /// Positive when the region retained memory, negative when it released
+        /// memory allocated before it started.
+        pub net_bytes: i64,
+    }
+
+    impl AllocationDelta {
+        fn between(before: AllocationStats, after: AllocationStats) -> AllocationDelta {
+            AllocationDelta {
+                allocs: after.alloc_count - before.alloc_count,
+                frees: after.dealloc_count - before.dealloc_count,
+                net_bytes: after.net_bytes() - before.net_bytes(),
+            }
+        }
+    }
+
+    /// Runs `f` and returns its result with the allocation activity it caused
+    ///
+    /// Uses the process-wide counters, so allocations made by other threads while
+    /// `f` runs are included - measure on a quiet process, or use
+    /// [`measure_thread()`] to count only the calling thread.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let (words, delta) = auto_allocator::measure(|| {
+    ///     "parse this phase".split(' ').map(String::from).collect::<Vec<_>>()
+    /// });
+    /// println!("{} words cost {} allocations, {} bytes retained", words.len(), delta.allocs, delta.net_bytes);
+    /// ```
+    pub fn measure<R>(f: impl FnOnce() -> R) -> (R, AllocationDelta) {
+        let before = allocation_stats();
+        let result = f();
+        let after = allocation_stats();
+        (result, AllocationDelta::between(before, after))
+    }
+
+    /// Like [`measure()`], but counts only allocations made by the calling thread
+    ///
+    /// Work `f` hands off to other threads is not included.
+    pub fn measure_thread<R>(f: impl FnOnce() -> R) -> (R, AllocationDelta) {
+        let before = thread_allocation_stats();
+        let result = f();
+        let after = thread_allocation_stats();
+        (result, AllocationDelta::between(before, after))
+    }
+
+    // (timestamp, alloc_count) of the previous allocation_rate() call
+    static RATE_BASELINE: std::sync::Mutex<Option<(std::time::Instant, u64)>> =
+        std::sync::Mutex::new(None);
+
+    /// Returns process-wide allocations per second since the previous call
+    ///
+    /// The window is the time between two calls, so polling it at a fixed interval
+    /// gives a cheap churn signal (e.g. for autoscaling) without a profiler. The first
+    /// call only establishes the baseline and returns `0.0`. Counts all threads, and
+    /// callers share one baseline - use a single poller.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// auto_allocator::allocation_rate(); // baseline
+    /// std::thread::sleep(std::time::Duration::from_millis(10));
+    /// println!("{:.0} allocations/s", auto_allocator::allocation_rate());
+    /// ```
+    pub fn allocation_rate() -> f64 {
+        let now = std::time::Instant::now();
+        let count = ALLOC_COUNT.load(Ordering::Relaxed);
+
+        let mut baseline = RATE_BASELINE.lock().unwrap_or_else(|e| e.into_inner());
+        let rate = match *baseline {
+            Some((since, previous)) => {
+                let elapsed = now.duration_since(since).as_secs_f64();
+                if elapsed > 0.0 {
+                    count.saturating_sub(previous) as f64 / elapsed
+                } else {
+                    0.0
+                }
+            }
+            None => 0.0,
+        };
+        *baseline = Some((now, count));
+        rate
+    }
+
+    // Per-tag live bytes for tag_scope(): allocations made inside a scope are remembered
+    // in a fixed pointer -> tag table so their free debits the same tag, on any thread
+    pub(crate) mod tags {
+        use core::cell::Cell;
+        use core::sync::atomic::{AtomicI64, AtomicU8, AtomicUsize, Ordering};
+
+        /// Most distinct tag names [`tag_scope()`] accepts; later names run untagged
+        pub const MAX_TAGS: usize = 255;
+
+        /// Size of the tagged-allocation table; a tagged allocation that finds no free
+        /// entry near its address hash goes unattributed, so expect that well before it fills
+        pub const TAG_TABLE_CAPACITY: usize = 1 << 16;
+
+        // Table keys: EMPTY was never used (ends a lookup), FREED was and can be reused
+        const EMPTY: usize = 0;
+        const FREED: usize = 1;
+        // Slots tried per block; keeps frees cheap once FREED keys pile up
+        const MAX_PROBES: usize = 64;
+
+        #[allow(clippy::declare_interior_mutable_const)]
+        const NO_KEY: AtomicUsize = AtomicUsize::new(EMPTY);
+        #[allow(clippy::declare_interior_mutable_const)]
+        const NO_TAG: AtomicU8 = AtomicU8::new(0);
+        #[allow(clippy::declare_interior_mutable_const)]
+        const ZERO_BYTES: AtomicI64 = AtomicI64::new(0);
+
+        static KEYS: [AtomicUsize; TAG_TABLE_CAPACITY] = [NO_KEY; TAG_TABLE_CAPACITY];
+        static KEY_TAGS: [AtomicU8; TAG_TABLE_CAPACITY] = [NO_TAG; TAG_TABLE_CAPACITY];
+        // Tagged allocations in the table; while 0, frees skip the lookup entirely
+        static TRACKED: AtomicUsize = AtomicUsize::new(0);
+        // Indexed by tag id; id 0 means "untagged" and stays unused
+        static TAG_BYTES: [AtomicI64; MAX_TAGS + 1] = [ZERO_BYTES; MAX_TAGS + 1];
+        // Tag names, id = index + 1; only touched by tag_scope() and tag_stats(), never in the allocator
+        static TAG_NAMES: std::sync::Mutex<Vec<String>> = std::sync::Mutex::new(Vec::new());
+
+        thread_local! {
+            static CURRENT_TAG: Cell<u8> = const { Cell::new(0) };
+        }
+
+        // Restores the enclosing tag, also when the scope unwinds
+        struct ScopeGuard(u8);
+
+        impl Drop for ScopeGuard {
+            fn drop(&mut self) {
+                let _ = CURRENT_TAG.try_with(|current| current.set(self.0));
+            }
+        }
+
+        /// Runs `f` with its allocations attributed to `tag`, returning what `f` returns
+        ///
+        /// Every allocation the calling thread makes inside `f` counts towards `tag` in
+        /// [`tag_stats()`] until it is freed - even when it is freed later, outside the
+        /// scope or on another thread, and even when it is reallocated. Scopes nest; the
+        /// innermost tag wins. Work `f` hands to other threads is not attributed.
+        ///
+        /// # Overhead
+        ///
+        /// Tagged allocations are recorded in a fixed table of [`TAG_TABLE_CAPACITY`]
+        /// entries, and while any are live every free (tagged or not) probes it. Entering a
+        /// scope takes a lock to look the name up. Untagged allocations pay only a TLS read.
+        /// Beyond [`MAX_TAGS`] names, or with the table full, allocations go untagged.
+        ///
+        /// # Example
+        ///
+        /// ```rust
+        /// let cache = auto_allocator::tag_scope("tenant-42", || vec![0u8; 64 * 1024]);
+        /// # #[cfg(not(feature = "no-global"))]
+        /// assert!(auto_allocator::tag_stats()["tenant-42"] >= 64 * 1024);
+        /// drop(cache);
+        /// ```
+        pub fn tag_scope<R>(tag: &str, f: impl FnOnce() -> R) -> R {
+            let id = tag_id(tag);
+            let Ok(previous) = CURRENT_TAG.try_with(|current| current.replace(id)) else {
+                return f();
+            };
+            let _guard = ScopeGuard(previous);
+            f()
+        }
+
+        /// Returns live bytes per tag used with [`tag_scope()`]
+        ///
+        /// Bytes allocated inside a scope and not freed yet, by requested size. A tag
+        /// whose memory has all been freed reports 0.
+        pub fn tag_stats() -> std::collections::HashMap<String, i64> {
+            let names = TAG_NAMES.lock().unwrap_or_else(|e| e.into_inner());
+            names
+                .iter()
+                .enumerate()
+                .map(|(index, name)| (name.clone(), TAG_BYTES[index + 1].load(Ordering::Relaxed)))
+                .collect()
+        }
+
+        // Registers the name on first use; 0 (untagged) once MAX_TAGS names exist
+        fn tag_id(tag: &str) -> u8 {
+            // Registration allocates - keep that out of the enclosing scope's tag
+            let previous = CURRENT_TAG.try_with(|current| current.replace(0)).unwrap_or(0);
+            let id = {
+                let mut names = TAG_NAMES.lock().unwrap_or_else(|e| e.into_inner());
+                match names.iter().position(|name| name == tag) {
+                    Some(index) => index as u8 + 1,
+                    None if names.len() < MAX_TAGS => {
+                        names.push(tag.to_string());
+                        names.len() as u8
+                    }
+                    None => 0,
+                }
+            };
+            let _ = CURRENT_TAG.try_with(|current| current.set(previous));
+            id
+        }
+
+        #[inline]
+        fn current_tag() -> u8 {
+            // try_with: TLS may already be torn down during thread exit
+            CURRENT_TAG.try_with(Cell::get).unwrap_or(0)
+        }
+
+        #[inline]
+        fn slot(ptr: *mut u8) -> usize {
+            // Fibonacci hashing; the low bits of heap addresses are mostly alignment
+            ((ptr as usize >> 4).wrapping_mul(0x9E37_79B9_7F4A_7C15u64 as usize)) % TAG_TABLE_CAPACITY
+        }
+
+        #[inline]
+        pub(crate) fn record_alloc(ptr: *mut u8, size: usize) {
+            let tag = current_tag();
+            if tag != 0 {
+                insert(ptr, tag, size);
+            }
+        }
+
+        #[inline]
+        pub(crate) fn record_dealloc(ptr: *mut u8, size: usize) {
+            if TRACKED.load(Ordering::Relaxed) != 0 {
+                remove(ptr, size);
+            }
+        }
+
+        // A reallocated block keeps the tag it was allocated under
+        #[inline]
+        pub(crate) fn record_realloc(ptr: *mut u8, size: usize, new_ptr: *mut u8, new_size: usize) {
+            let tag = if TRACKED.load(Ordering::Relaxed) != 0 { remove(ptr, size) } else { 0 };
+            let tag = if tag != 0 { tag } else { current_tag() };
+            if tag != 0 {
+                insert(new_ptr, tag, new_size);
+            }
+        }
+
+        #[cold]
+        fn insert(ptr: *mut u8, tag: u8, size: usize) {
+            let start = slot(ptr);
+            for probe in 0..MAX_PROBES {
+                let index = (start + probe) % TAG_TABLE_CAPACITY;
+                let key = KEYS[index].load(Ordering::Relaxed);
+                if key != EMPTY && key != FREED {
+                    continue;
+                }
+                if KEYS[index]
+                    .compare_exchange(key, ptr as usize, Ordering::Relaxed, Ordering::Relaxed)
+                    .is_ok()
+                {
+                    // Only a free of this block reads the tag, and that free happens after
+                    // the allocation returns - so after this store
+                    KEY_TAGS[index].store(tag, Ordering::Relaxed);
+                    TRACKED.fetch_add(1, Ordering::Relaxed);
+                    TAG_BYTES[tag as usize].fetch_add(size as i64, Ordering::Relaxed);
+                    return;
+                }
+            }
+            // No free slot within reach: the allocation stays unattributed
+        }
+
+        // Debits and forgets a tagged block, returning its tag (0 if it wasn't tagged)
+        fn remove(ptr: *mut u8, size: usize) -> u8 {
+            let start = slot(ptr);
+            for probe in 0..MAX_PROBES {
+                let index = (start + probe) % TAG_TABLE_CAPACITY;
+                match KEYS[index].load(Ordering::Relaxed) {
+                    // Keys never go back to EMPTY, so the block was never inserted
+                    EMPTY => return 0,
+                    key if key == ptr as usize => {
+                        let tag = KEY_TAGS[index].load(Ordering::Relaxed);
+                        KEYS[index].store(FREED, Ordering::Relaxed);
+                        TRACKED.fetch_sub(1, Ordering::Relaxed);
+                        TAG_BYTES[tag as usize].fetch_sub(size as i64, Ordering::Relaxed);
+                        return tag;
+                    }
+                    _ => {}
+                }
+            }
+            0
+        }
+    }
+
+    /// Returns resident memory divided by live requested bytes, a whole-process overhead figure
+    ///
+    /// The denominator is [`allocation_stats()`]`.net_bytes()` (what the program asked for
+    /// and still holds), the numerator the OS's resident set size from
+    /// [`process_memory_usage()`](crate::process_memory_usage) - so `1.3` means 30% more
+    /// memory is committed than requested. Run the same workload under different
+    /// allocators (e.g. with `AUTO_ALLOCATOR_DENY=mimalloc`) and compare the ratios.
+    ///
+    /// `None` when RSS isn't available on this platform or nothing is live.
+    ///
+    /// # Caveats
+    ///
+    /// RSS is more than the heap: code, stacks, and shared library pages count too, so
+    /// small heaps give inflated ratios - measure with a realistic working set. Shared
+    /// pages (libraries, shared memory) are counted in full although other processes map
+    /// them as well, and memory the allocator has committed but not yet touched (or has
+    /// swapped out) is missing, so the ratio can also dip below `1.0`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let data = vec![1u8; 64 << 20];
+    /// if let Some(ratio) = auto_allocator::overhead_ratio() {
+    ///     println!("{:.2}x resident per requested byte", ratio);
+    /// }
+    /// # drop(data);
+    /// ```
+    pub fn overhead_ratio() -> Option<f64> {
+        let requested = allocation_stats().net_bytes();
+        if requested <= 0 {
+            return None;
+        }
+        let resident = crate::process_memory_usage()?;
+        Some(resident as f64 / requested as f64)
+    }
+}
+
+#[cfg(all(feature = "stats", not(target_os = "none")))]
+pub use stats::{
+    alignment_stats, allocation_rate, allocation_stats, measure, measure_thread, overhead_ratio,
+    size_class_stats, thread_allocation_stats, AlignmentStat, AllocationDelta, AllocationStats,
+    SizeClassStat, ALIGNMENT_CLASSES, SIZE_CLASSES,
+};
+
+#[cfg(all(feature = "stats", not(target_os = "none")))]
+pub use stats::tags::{tag_scope, tag_stats, MAX_TAGS, TAG_TABLE_CAPACITY};
+
+// ========== Allocation Profiling ==========
+
+// dhat-style allocation event stream (`profiling` feature, std only)
+// Runs inside the global allocator: capture and site storage must not allocate,
+// and a reentrancy guard keeps the hook's own allocations out of the stream
+#[cfg(all(feature = "profiling", not(target_os = "none")))]
+mod profiling {
+    use core::cell::Cell;
+    use core::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+
+    /// Whether an [`AllocationEvent`] reports an allocation or a free
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum AllocationEventKind {
+        /// A block was allocated (including the new block of a realloc)
+        Alloc,
+
+        /// A block was freed (including the old block of a realloc)
+        Dealloc,
+    }
+
+    /// One allocator event, delivered to the hook set with [`set_profiling_hook()`]
+    ///
+    /// Carries what dhat's heap profiler records per block: the address and requested
+    /// size, plus the allocation site as a backtrace id. Pair `Dealloc` events with
+    /// their `Alloc` by `address` to get block lifetimes and peak usage per site.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct AllocationEvent {
+        /// Allocation or free
+        pub kind: AllocationEventKind,
+
+        /// Block address
+        pub address: usize,
+
+        /// Requested size in bytes (`Layout::size()`)
+        pub size: usize,
+
+        /// Allocation site, resolvable with [`backtrace_frames()`] / [`resolve_backtrace()`]
+        ///
+        /// `0` for `Dealloc` events (the site belongs to the matching `Alloc`) and on
+        /// platforms without stack capture (only glibc Linux without `no-libc`, and macOS, have one).
+        pub backtrace_id: u64,
+    }
+
+    /// Maximum number of return addresses kept per allocation site
+    pub const MAX_BACKTRACE_FRAMES: usize = 24;
+    const SITE_SLOTS: usize = 4096;
+
+    // 0 = no hook, otherwise a `fn(&AllocationEvent)`
+    static HOOK: AtomicUsize = AtomicUsize::new(0);
+
+    thread_local! {
+        static IN_HOOK: Cell<bool> = const { Cell::new(false) };
+    }
+
+    // Open-addressed id -> frames table, written once per slot and never removed
+    struct Site {
+        id: AtomicU64,
+        len: AtomicUsize,
+        frames: [AtomicUsize; MAX_BACKTRACE_FRAMES],
+    }
+
+    #[allow(clippy::declare_interior_mutable_const)]
+    const EMPTY_FRAME: AtomicUsize = AtomicUsize::new(0);
+    #[allow(clippy::declare_interior_mutable_const)]
+    const EMPTY_SITE: Site = Site {
+        id: AtomicU64::new(0),
+        len: AtomicUsize::new(0),
+        frames: [EMPTY_FRAME; MAX_BACKTRACE_FRAMES],
+    };
+    static SITES: [Site; SITE_SLOTS] = [EMPTY_SITE; SITE_SLOTS];
+
+    /// Sets (or with `None`, clears) the hook receiving every allocation event
+    ///
+    /// Layers profiling over whichever backend was selected, so the measured program
+    /// keeps its real allocator - unlike `dhat::Alloc`, which replaces it. The hook
+    /// runs synchronously inside the allocator on the allocating thread; it may
+    /// allocate (e.g. to buffer events or write them out), and those allocations are
+    /// not reported back to it.
+    ///
+    /// # Overhead
+    ///
+    /// Every allocation captures a stack backtrace and calls the hook, which makes
+    /// allocation-heavy code an order of magnitude slower or worse. Use this in
+    /// profiling builds only; with no hook set the cost is one atomic load per call.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use auto_allocator::{AllocationEvent, AllocationEventKind};
+    /// use std::sync::atomic::{AtomicUsize, Ordering};
+    ///
+    /// static BYTES: AtomicUsize = AtomicUsize::new(0);
+    ///
+    /// fn on_event(event: &AllocationEvent) {
+    ///     if event.kind == AllocationEventKind::Alloc {
+    ///         BYTES.fetch_add(event.size, Ordering::Relaxed);
+    ///     }
+    /// }
+    ///
+    /// auto_allocator::set_profiling_hook(Some(on_event));
+    /// let data = vec![0u8; 4096];
+    /// auto_allocator::set_profiling_hook(None);
+    /// # #[cfg(not(feature = "no-global"))]
+    /// assert!(BYTES.load(Ordering::Relaxed) >= data.len());
+    /// ```
+    pub fn set_profiling_hook(hook: Option<fn(&AllocationEvent)>) {
+        HOOK.store(hook.map_or(0, |hook| hook as usize), Ordering::Release);
+    }
+
+    /// Returns the return addresses recorded for a backtrace id
+    ///
+    /// Innermost frame first. The top frames are auto-allocator's own and the
+    /// `__rust_alloc` shims (how many depends on inlining, so they are kept rather
+    /// than guessed away); the allocating code follows. `None` if the id is
+    /// unknown or the site table (4096 sites) was full when it was first seen.
+    pub fn backtrace_frames(backtrace_id: u64) -> Option<Vec<usize>> {
+        let site = find_site(backtrace_id)?;
+        let len = site.len.load(Ordering::Acquire);
+        if len == 0 {
+            return None;
+        }
+        Some(site.frames[..len].iter().map(|frame| frame.load(Ordering::Relaxed)).collect())
+    }
+
+    /// Symbolizes a backtrace id, one line per frame
+    ///
+    /// Uses the platform's `backtrace_symbols()`, which gives `binary(symbol+offset)`
+    /// style lines; feed [`backtrace_frames()`] to `addr2line` for file and line
+    /// numbers. Empty when the id is unknown or the platform can't capture stacks.
+    /// Call it outside the hook - it allocates and is slow.
+    pub fn resolve_backtrace(backtrace_id: u64) -> Vec<String> {
+        match backtrace_frames(backtrace_id) {
+            Some(frames) => symbolize(&frames),
+            None => Vec::new(),
+        }
+    }
+
+    #[inline]
+    pub(crate) fn record_alloc(address: *mut u8, size: usize) {
+        if HOOK.load(Ordering::Relaxed) != 0 {
+            dispatch(AllocationEventKind::Alloc, address as usize, size);
+        }
+    }
+
+    #[inline]
+    pub(crate) fn record_dealloc(address: *mut u8, size: usize) {
+        if HOOK.load(Ordering::Relaxed) != 0 {
+            dispatch(AllocationEventKind::Dealloc, address as usize, size);
+        }
+    }
+
+    #[cold]
+    #[inline(never)]
+    fn dispatch(kind: AllocationEventKind, address: usize, size: usize) {
+        // try_with: TLS may already be torn down during thread exit
+        let _ = IN_HOOK.try_with(|in_hook| {
+            if in_hook.replace(true) {
+                return;
+            }
+
+            let hook = HOOK.load(Ordering::Acquire);
+            if hook != 0 {
+                let backtrace_id = match kind {
+                    AllocationEventKind::Alloc => capture_site(),
+                    AllocationEventKind::Dealloc => 0,
+                };
+                // SAFETY: only set_profiling_hook() stores non-zero values, all `fn(&AllocationEvent)`
+                let hook: fn(&AllocationEvent) = unsafe { core::mem::transmute(hook) };
+                hook(&AllocationEvent { kind, address, size, backtrace_id });
+            }
+
+            in_hook.set(false);
+        });
+    }
+
+    fn find_site(backtrace_id: u64) -> Option<&'static Site> {
+        if backtrace_id == 0 {
+            return None;
+        }
+        let start = backtrace_id as usize % SITE_SLOTS;
+        for probe in 0..SITE_SLOTS {
+            let site = &SITES[(start + probe) % SITE_SLOTS];
+            match site.id.load(Ordering::Acquire) {
+                id if id == backtrace_id => return Some(site),
+                0 => return None,
+                _ => {}
+            }
+        }
+        None
+    }
+
+    // Captures the caller's stack, stores it on first sight and returns its id
+    fn capture_site() -> u64 {
+        let mut raw: [RawFrame; MAX_BACKTRACE_FRAMES] = [core::ptr::null_mut(); MAX_BACKTRACE_FRAMES];
+        let captured = capture(&mut raw);
+        let frames = &raw[..captured];
+        if frames.is_empty() {
+            return 0;
+        }
+
+        // FNV-1a over the return addresses; never 0, which means "no backtrace"
+        let mut id: u64 = 0xcbf2_9ce4_8422_2325;
+        for &frame in frames {
+            id ^= frame as usize as u64;
+            id = id.wrapping_mul(0x0100_0000_01b3);
+        }
+        let id = id.max(1);
+
+        let start = id as usize % SITE_SLOTS;
+        for probe in 0..SITE_SLOTS {
+            let site = &SITES[(start + probe) % SITE_SLOTS];
+            match site.id.compare_exchange(0, id, Ordering::AcqRel, Ordering::Acquire) {
+                Ok(_) => {
+                    for (slot, &frame) in site.frames.iter().zip(frames) {
+                        slot.store(frame as usize, Ordering::Relaxed);
+                    }
+                    site.len.store(frames.len(), Ordering::Release);
+                    break;
+                }
+                Err(existing) if existing == id => break,
+                Err(_) => {}
+            }
+        }
+        id
+    }
+
+    #[cfg(any(all(target_os = "linux", target_env = "gnu", not(auto_allocator_no_libc)), target_os = "macos"))]
+    type RawFrame = *mut libc::c_void;
+    #[cfg(not(any(all(target_os = "linux", target_env = "gnu", not(auto_allocator_no_libc)), target_os = "macos")))]
+    type RawFrame = *mut u8;
+
+    #[cfg(any(all(target_os = "linux", target_env = "gnu", not(auto_allocator_no_libc)), target_os = "macos"))]
+    #[inline(never)]
+    fn capture(frames: &mut [RawFrame]) -> usize {
+        // backtrace() may allocate on its first call (loading the unwinder); the
+        // reentrancy guard sends those allocations straight to the backend
+        let captured = unsafe { libc::backtrace(frames.as_mut_ptr(), frames.len() as libc::c_int) };
+        captured.max(0) as usize
+    }
+
+    #[cfg(not(any(all(target_os = "linux", target_env = "gnu", not(auto_allocator_no_libc)), target_os = "macos")))]
+    fn capture(_frames: &mut [RawFrame]) -> usize {
+        0
+    }
+
+    #[cfg(any(all(target_os = "linux", target_env = "gnu", not(auto_allocator_no_libc)), target_os = "macos"))]
+    fn symbolize(frames: &[usize]) -> Vec<String> {
+        let addresses: Vec<*mut libc::c_void> = frames.iter().map(|&frame| frame as *mut libc::c_void).collect();
+        unsafe {
+            let symbols = libc::backtrace_symbols(addresses.as_ptr(), addresses.len() as libc::c_int);
+            if symbols.is_null() {
+                return Vec::new();
+            }
+            let lines = (0..addresses.len())
+                .map(|i| std::ffi::CStr::from_ptr(*symbols.add(i)).to_string_lossy().into_owned())
+                .collect();
+            libc::free(symbols as *mut libc::c_void);
+            lines
+        }
+    }
+
+    #[cfg(not(any(all(target_os = "linux", target_env = "gnu", not(auto_allocator_no_libc)), target_os = "macos")))]
+    fn symbolize(_frames: &[usize]) -> Vec<String> {
+        Vec::new()
+    }
+}
+
+#[cfg(all(feature = "profiling", not(target_os = "none")))]
+pub use profiling::{
+    backtrace_frames, resolve_backtrace, set_profiling_hook, AllocationEvent, AllocationEventKind,
+    MAX_BACKTRACE_FRAMES,
+};
+
+// ========== Allocation Trace ==========
+
+// Ring buffer of every allocator operation (`trace-all` feature, std only)
+// Written from inside the global allocator, so recording is a few atomic stores into a
+// static array: no allocation, no locks, no I/O that could re-enter the allocator
+#[cfg(all(feature = "trace-all", not(target_os = "none")))]
+mod trace {
+    use core::sync::atomic::{fence, AtomicU64, AtomicU8, AtomicUsize, Ordering};
+
+    /// Number of operations kept by the trace ring buffer; older ones are overwritten
+    pub const ALLOC_TRACE_CAPACITY: usize = 4096;
+
+    /// Whether an [`AllocTraceEntry`] records an allocation or a free
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum AllocTraceKind {
+        /// A block was allocated (including the new block of a realloc)
+        Alloc,
+
+        /// A block was freed (including the old block of a realloc)
+        Dealloc,
+    }
+
+    /// One allocator operation recorded by the `trace-all` feature
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct AllocTraceEntry {
+        /// Process-wide sequence number, starting at 0 and counting every operation
+        ///
+        /// Gaps in a dump are operations that were overwritten or still being written.
+        pub seq: u64,
+
+        /// Allocation or free
+        pub kind: AllocTraceKind,
+
+        /// Block address
+        pub address: usize,
+
+        /// Requested size in bytes (`Layout::size()`)
+        pub size: usize,
+    }
+
+    impl core::fmt::Display for AllocTraceEntry {
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+            let kind = match self.kind {
+                AllocTraceKind::Alloc => "alloc",
+                AllocTraceKind::Dealloc => "free",
+            };
+            write!(f, "#{} {} {} bytes at {:#x}", self.seq, kind, self.size, self.address)
+        }
+    }
+
+    // Slot stamp: 0 = never written, WRITING = being overwritten, otherwise seq + 1
+    const WRITING: u64 = u64::MAX;
+
+    struct Slot {
+        stamp: AtomicU64,
+        kind: AtomicU8,
+        address: AtomicUsize,
+        size: AtomicUsize,
+    }
+
+    #[allow(clippy::declare_interior_mutable_const)]
+    const EMPTY_SLOT: Slot = Slot {
+        stamp: AtomicU64::new(0),
+        kind: AtomicU8::new(0),
+        address: AtomicUsize::new(0),
+        size: AtomicUsize::new(0),
+    };
+    static SLOTS: [Slot; ALLOC_TRACE_CAPACITY] = [EMPTY_SLOT; ALLOC_TRACE_CAPACITY];
+    static NEXT_SEQ: AtomicU64 = AtomicU64::new(0);
+
+    #[inline]
+    pub(crate) fn record(kind: AllocTraceKind, address: *mut u8, size: usize) {
+        // The only shared write: claiming a sequence number picks the slot
+        let seq = NEXT_SEQ.fetch_add(1, Ordering::Relaxed);
+        let slot = &SLOTS[seq as usize % ALLOC_TRACE_CAPACITY];
+
+        // Seqlock-style publish, so a concurrent dump skips a half-written slot
+        slot.stamp.store(WRITING, Ordering::Relaxed);
+        fence(Ordering::Release);
+        slot.kind.store(kind as u8, Ordering::Relaxed);
+        slot.address.store(address as usize, Ordering::Relaxed);
+        slot.size.store(size, Ordering::Relaxed);
+        slot.stamp.store(seq + 1, Ordering::Release);
+    }
+
+    /// Returns the most recent allocator operations, oldest first
+    ///
+    /// With the `trace-all` feature every allocation and free made through
+    /// [`RuntimeAllocator`](crate::RuntimeAllocator) is recorded with its size, address
+    /// and a sequence number - the last [`ALLOC_TRACE_CAPACITY`] of them are kept in a
+    /// fixed ring buffer, not printed, so recording never re-enters the allocator. A
+    /// realloc shows up as a free of the old block and an allocation of the new one.
+    /// Entries being overwritten while the dump runs are left out.
+    ///
+    /// # Overhead
+    ///
+    /// A debugging aid for chasing flaky tests, not for production: every allocator
+    /// call takes a contended atomic increment and several stores into a shared
+    /// buffer, which serializes allocation-heavy threads. Off by default.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let data = vec![0u8; 4096];
+    /// drop(data);
+    /// for entry in auto_allocator::dump_alloc_trace().iter().rev().take(10) {
+    ///     eprintln!("{}", entry);
+    /// }
+    /// ```
+    pub fn dump_alloc_trace() -> Vec<AllocTraceEntry> {
+        // Allocate first, so the buffer's own allocation is part of the dump
+        let mut entries = Vec::with_capacity(ALLOC_TRACE_CAPACITY);
+        let end = NEXT_SEQ.load(Ordering::Acquire);
+        let start = end.saturating_sub(ALLOC_TRACE_CAPACITY as u64);
+
+        for seq in start..end {
+            let slot = &SLOTS[seq as usize % ALLOC_TRACE_CAPACITY];
+            if slot.stamp.load(Ordering::Acquire) != seq + 1 {
+                continue;
+            }
+            let kind = slot.kind.load(Ordering::Relaxed);
+            let address = slot.address.load(Ordering::Relaxed);
+            let size = slot.size.load(Ordering::Relaxed);
+            fence(Ordering::Acquire);
+            if slot.stamp.load(Ordering::Relaxed) != seq + 1 {
+                continue;
+            }
+
+            let kind = if kind == AllocTraceKind::Alloc as u8 {
+                AllocTraceKind::Alloc
+            } else {
+                AllocTraceKind::Dealloc
+            };
+            entries.push(AllocTraceEntry { seq, kind, address, size });
+        }
+        entries
+    }
+}
+
+#[cfg(all(feature = "trace-all", not(target_os = "none")))]
+pub use trace::{dump_alloc_trace, AllocTraceEntry, AllocTraceKind, ALLOC_TRACE_CAPACITY};
+
+// ========== Logging System ==========
+
+#[cfg(all(feature = "log", not(feature = "no-log"), not(target_os = "none")))]
+static PENDING_LOG_MESSAGE: std::sync::Mutex<Option<String>> = std::sync::Mutex::new(None);
+
+// Descriptor the selection line is written to on Unix; stderr unless set_stderr_log_fd() changed it
+#[cfg(all(unix, not(target_os = "none")))]
+static STDERR_LOG_FD: core::sync::atomic::AtomicI32 = core::sync::atomic::AtomicI32::new(2);
+
+/// Writes the startup selection line to `fd` instead of stderr (fd 2)
+///
+/// For hosts that hand the process a dedicated logging pipe, or repurpose fd 2.
+/// Simpler than forwarding through the `log` feature when the program only needs the
+/// line to land somewhere else. The descriptor is used as-is: it is not opened,
+/// duplicated or closed here, and it must stay open until selection has run.
+///
+/// The line is written on the first allocation, so - like the selection settings -
+/// this only takes effect from a pre-main constructor (see
+/// [Configuration Timing](crate#configuration-timing)). Returns `true` if the
+/// selection line will go to `fd`, `false` if `fd` is negative or selection had
+/// already started; nothing changes in either case.
+///
+/// # Example
+///
+/// ```rust
+/// # #[cfg(unix)]
+/// # {
+/// // Meant for a pre-main constructor; in `main` the line has already been written
+/// if !auto_allocator::set_stderr_log_fd(3) {
+///     eprintln!("selection line already went to stderr");
+/// }
+/// # }
+/// ```
+#[cfg(all(unix, not(target_os = "none")))]
+pub fn set_stderr_log_fd(fd: i32) -> bool {
+    fd >= 0 && write_selection_config(false, || STDERR_LOG_FD.store(fd, Ordering::Release))
+}
+
+/// Records allocator selection using a dual logging strategy
+///
+/// Immediately outputs to stderr (safe during global allocator init) and 
+/// saves for later output through the logging framework when available
+/// (`log` feature, on by default).
+///
+/// `AUTO_ALLOCATOR_LOG_FORMAT=json` replaces the human-readable line with a
+/// single-line JSON object for log pipelines:
+/// `{"allocator":"mimalloc","reason":"...","cpu_cores":16,"total_ram_bytes":137438953472}`.
+/// `AUTO_ALLOCATOR_LOG=off` records nothing (see [`will_log_selection()`]), and
+/// `AUTO_ALLOCATOR_LOG=errors` only records a selection that `failed` (fell back
+/// because of an error, such as the mimalloc probe).
+#[cfg(all(not(feature = "no-log"), not(target_os = "none")))]
+fn record_allocator_selection(allocator_name: &str, reason: &str, system_info: &SystemInfo, failed: bool) {
+    match selection_log_mode() {
+        SelectionLogMode::Off => return,
+        SelectionLogMode::ErrorsOnly if !failed => return,
+        _ => {}
+    }
+
+    let mut buf = [0u8; 8];
+    let json = read_env_no_alloc(c"AUTO_ALLOCATOR_LOG_FORMAT", &mut buf)
+        .is_some_and(|format| format.eq_ignore_ascii_case(b"json"));
+
+    #[cfg_attr(not(any(unix, feature = "log")), allow(unused_variables))]
+    let message = if json {
+        let mut message = String::from("{\"allocator\":");
+        push_json_string(&mut message, allocator_name);
+        message.push_str(",\"reason\":");
+        push_json_string(&mut message, reason);
+        message.push_str(&format!(
+            ",\"cpu_cores\":{},\"total_ram_bytes\":{}}}",
+            system_info.cpu_cores, system_info.total_memory_bytes
+        ));
+        message
+    } else {
+        format!("Auto-allocator: {} selected - {}", allocator_name, reason)
+    };
+
+    // Immediate output to stderr (only safe method in global allocator)
+    #[cfg(unix)]
+    {
+        let stderr_message = if json {
+            format!("{}\n", message)
+        } else {
+            format!("[INFO] {}\n", message)
+        };
+        #[cfg(auto_allocator_no_libc)]
+        raw_syscall::write(STDERR_LOG_FD.load(Ordering::Acquire), stderr_message.as_bytes());
+        #[cfg(not(auto_allocator_no_libc))]
+        unsafe {
+            libc::write(
+                STDERR_LOG_FD.load(Ordering::Acquire),
+                stderr_message.as_ptr() as *const libc::c_void,
+                stderr_message.len(),
+            );
+        }
+    }
+
+    // Save message, output later through logging framework
+    #[cfg(feature = "log")]
+    if let Ok(mut pending) = PENDING_LOG_MESSAGE.lock() {
+        *pending = Some(message);
+    }
+}
+
+/// What `AUTO_ALLOCATOR_LOG` lets the selection log record
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum SelectionLogMode {
+    /// Every selection (the default)
+    All,
+    /// Only selections that fell back because of an error (`errors`)
+    ErrorsOnly,
+    /// Nothing (`off`, `0`, `false` or `none`)
+    Off,
+}
+
+/// Reads `AUTO_ALLOCATOR_LOG` without allocating; unknown values keep the default
+fn selection_log_mode() -> SelectionLogMode {
+    let mut buf = [0u8; 8];
+    match read_env_no_alloc(c"AUTO_ALLOCATOR_LOG", &mut buf) {
+        Some(value) if value.eq_ignore_ascii_case(b"errors") => SelectionLogMode::ErrorsOnly,
+        Some(value)
+            if [&b"off"[..], b"0", b"false", b"none"]
+                .iter()
+                .any(|disabled| value.eq_ignore_ascii_case(disabled)) =>
+        {
+            SelectionLogMode::Off
+        }
+        _ => SelectionLogMode::All,
+    }
+}
+
+/// Returns whether the allocator selection will be logged
+///
+/// Combines the build configuration - the `[INFO]` line goes to stderr on Unix, and
+/// the `log` feature forwards it to the `log` crate - with the `AUTO_ALLOCATOR_LOG`
+/// environment variable, which turns both off when set to `off`, `0`, `false` or `none`.
+/// `AUTO_ALLOCATOR_LOG=errors` also gives `false`: the routine line is suppressed and
+/// only a fallback caused by an error (the mimalloc probe failing) is still written.
+/// Always `false` on no_std targets, which never log, and with the `no-log` feature.
+///
+/// Only reads the environment, so it is allocation-free and does not trigger selection;
+/// it is meaningful before the first allocation (e.g. from a pre-main constructor).
+///
+/// # Example
+///
+/// ```rust
+/// if auto_allocator::will_log_selection() {
+///     println!("expect an auto-allocator line on stderr or in the log");
+/// }
+/// ```
+pub fn will_log_selection() -> bool {
+    cfg!(all(not(target_os = "none"), not(feature = "no-log"), any(unix, feature = "log")))
+        && selection_log_mode() == SelectionLogMode::All
+}
+
+/// Appends `value` as a quoted, escaped JSON string
+#[cfg(all(not(feature = "no-log"), not(target_os = "none")))]
+fn push_json_string(out: &mut String, value: &str) {
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+/// Attempts to flush pending log message to the logging framework
+#[cfg(all(feature = "log", not(feature = "no-log"), not(target_os = "none")))]
+fn try_flush_pending_log() {
+    if !LOG_FLUSHED.load(Ordering::Relaxed) {
+        if let Ok(mut pending) = PENDING_LOG_MESSAGE.lock() {
+            if let Some(message) = pending.take() {
+                let _ = std::panic::catch_unwind(|| {
+                    info!("{}", message);
+                });
+                LOG_FLUSHED.store(true, Ordering::Relaxed);
+            }
+        }
+    }
+}
+
+/// Intelligently flushes logs when the logging framework becomes available
+#[cfg(all(feature = "log", not(feature = "no-log"), not(target_os = "none")))]
+fn smart_try_flush_log() {
+    // If already output, no need to try again
+    if LOG_FLUSHED.load(Ordering::Relaxed) {
+        return;
+    }
+
+    // Try to output log
+    try_flush_pending_log();
+
+    // If still not successful, logging framework is not yet initialized
+    // Will continue trying on next call
+}
+
+// ========== Bump Allocation Mode ==========
+
+// Opt-in leak-by-design arena for short-lived processes (`bump` feature, std only)
+// Allocations bump a pointer inside one pre-reserved arena; frees are no-ops
+#[cfg(all(feature = "bump", not(target_os = "none")))]
+mod bump {
+    use core::alloc::Layout;
+    use core::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+    // Arena bounds are published once; BUMP_START == 0 means bump mode is off
+    static BUMP_START: AtomicUsize = AtomicUsize::new(0);
+    static BUMP_END: AtomicUsize = AtomicUsize::new(0);
+    static BUMP_NEXT: AtomicUsize = AtomicUsize::new(0);
+    static BUMP_ENABLING: AtomicBool = AtomicBool::new(false);
+
+    const ARENA_ALIGN: usize = 4096;
+
+    /// Switches the global allocator into bump mode with an arena of `arena_size` bytes
+    ///
+    /// The arena is reserved once from the selected backend. Afterwards every allocation
+    /// that fits is carved out of the arena by bumping a pointer, and `dealloc` of arena
+    /// memory is a no-op: **memory is leaked by design** until [`reset_bump()`].
+    /// Allocations that don't fit fall back to the normal selected allocator and are
+    /// freed normally. Intended for CLI tools that run briefly and exit.
+    ///
+    /// Returns `false` if bump mode is already enabled or the arena can't be reserved.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// // 64MB arena: nothing is freed until exit
+    /// auto_allocator::enable_bump_mode(64 << 20);
+    /// let words: Vec<String> = "short lived tool".split(' ').map(String::from).collect();
+    /// assert_eq!(words.len(), 3);
+    /// ```
+    pub fn enable_bump_mode(arena_size: usize) -> bool {
+        if arena_size == 0
+            || BUMP_ENABLING
+                .compare_exchange(false, true, Ordering::AcqRel, Ordering::Acquire)
+                .is_err()
+        {
+            return false;
+        }
+
+        let layout = match Layout::from_size_align(arena_size, ARENA_ALIGN) {
+            Ok(layout) => layout,
+            Err(_) => {
+                BUMP_ENABLING.store(false, Ordering::Release);
+                return false;
+            }
+        };
+
+        // Reserve from the backend directly: the arena itself is never freed
+        let start = unsafe { crate::RuntimeAllocator::backend_alloc(layout) } as usize;
+        if start == 0 {
+            BUMP_ENABLING.store(false, Ordering::Release);
+            return false;
+        }
+
+        BUMP_END.store(start + arena_size, Ordering::Relaxed);
+        BUMP_NEXT.store(start, Ordering::Relaxed);
+        BUMP_START.store(start, Ordering::Release);
+        true
+    }
+
+    /// Rewinds the bump arena so its memory can be handed out again
+    ///
+    /// # Safety
+    ///
+    /// Every allocation served from the arena since it was enabled (or last reset)
+    /// must be dead - no live references or owners may remain, on any thread.
+    /// Allocations that fell back to the normal allocator are unaffected.
+    pub unsafe fn reset_bump() {
+        let start = BUMP_START.load(Ordering::Acquire);
+        if start != 0 {
+            BUMP_NEXT.store(start, Ordering::Release);
+        }
+    }
+
+    /// Returns `(used_bytes, capacity_bytes)` of the bump arena, or `None` if bump mode is off
+    pub fn bump_arena_usage() -> Option<(usize, usize)> {
+        let start = BUMP_START.load(Ordering::Acquire);
+        if start == 0 {
+            return None;
+        }
+        let end = BUMP_END.load(Ordering::Relaxed);
+        let next = BUMP_NEXT.load(Ordering::Relaxed).min(end);
+        Some((next - start, end - start))
+    }
+
+    /// Carves `layout` out of the arena, or returns `None` to use the normal allocator
+    #[inline]
+    pub(crate) fn try_alloc(layout: Layout) -> Option<*mut u8> {
+        if BUMP_START.load(Ordering::Acquire) == 0 {
+            return None;
+        }
+        let end = BUMP_END.load(Ordering::Relaxed);
+
+        let mut current = BUMP_NEXT.load(Ordering::Relaxed);
+        loop {
+            let aligned = current.checked_add(layout.align() - 1)? & !(layout.align() - 1);
+            let next = aligned.checked_add(layout.size())?;
+            if next > end {
+                return None; // exhausted - fall back to the selected allocator
+            }
+            match BUMP_NEXT.compare_exchange_weak(current, next, Ordering::Relaxed, Ordering::Relaxed) {
+                Ok(_) => return Some(aligned as *mut u8),
+                Err(observed) => current = observed,
+            }
+        }
+    }
+
+    /// Checks whether `ptr` was served from the arena
+    #[inline]
+    pub(crate) fn owns(ptr: *mut u8) -> bool {
+        let start = BUMP_START.load(Ordering::Acquire);
+        let addr = ptr as usize;
+        start != 0 && addr >= start && addr < BUMP_END.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(all(feature = "bump", not(target_os = "none")))]
+pub use bump::{bump_arena_usage, enable_bump_mode, reset_bump};
+
+// ========== Region Allocator ==========
+
+// Request-scoped bump regions (`region` feature, std only): a user-facing helper,
+// not a global allocator - chunks come from the selected backend through RuntimeAllocator
+#[cfg(all(feature = "region", not(target_os = "none")))]
+mod region {
+    use core::alloc::{GlobalAlloc, Layout};
+    use core::cell::{Cell, UnsafeCell};
+    use core::ptr::NonNull;
+
+    const DEFAULT_CHUNK_SIZE: usize = 64 << 10;
+    const CHUNK_ALIGN: usize = 16;
+
+    /// Bump arena for request-scoped allocations, freed all at once
+    ///
+    /// Grabs chunks (64KB by default) from the selected backend and hands out
+    /// sub-allocations by bumping a pointer; an allocation that doesn't fit starts a
+    /// new chunk, sized up for oversized requests. Nothing is freed individually:
+    /// [`Region::reset()`] and drop return every chunk to the backend. Destructors of
+    /// values placed in the region do **not** run - store plain data, or drop values
+    /// yourself before resetting.
+    ///
+    /// Independent of the process-wide `bump` mode, and usable with `no-global`
+    /// (chunks still come from auto-allocator's selected backend).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let mut region = auto_allocator::Region::new();
+    /// for request in 0..3u64 {
+    ///     let id = region.alloc(request);
+    ///     let body = region.alloc_slice_copy(b"hello");
+    ///     assert_eq!((*id, &body[..]), (request, &b"hello"[..]));
+    ///     region.reset(); // everything above is gone
+    /// }
+    /// ```
+    pub struct Region {
+        chunk_size: usize,
+        // Free space in the current chunk
+        next: Cell<usize>,
+        end: Cell<usize>,
+        chunks: UnsafeCell<Vec<(NonNull<u8>, Layout)>>,
+        allocated: Cell<usize>,
+    }
+
+    // The region exclusively owns its chunks; Cell keeps it !Sync
+    unsafe impl Send for Region {}
+
+    impl Region {
+        /// Creates an empty region with 64KB chunks; nothing is reserved until the first allocation
+        pub fn new() -> Region {
+            Region::with_chunk_size(DEFAULT_CHUNK_SIZE)
+        }
+
+        /// Creates an empty region whose chunks are at least `chunk_size` bytes
+        pub fn with_chunk_size(chunk_size: usize) -> Region {
+            Region {
+                chunk_size: chunk_size.max(CHUNK_ALIGN),
+                next: Cell::new(0),
+                end: Cell::new(0),
+                chunks: UnsafeCell::new(Vec::new()),
+                allocated: Cell::new(0),
+            }
+        }
+
+        /// Moves `value` into the region and returns a reference valid until reset or drop
+        #[allow(clippy::mut_from_ref)] // each call returns fresh, non-overlapping memory
+        pub fn alloc<T>(&self, value: T) -> &mut T {
+            let ptr = self.alloc_layout(Layout::new::<T>()).cast::<T>();
+            unsafe {
+                ptr.as_ptr().write(value);
+                &mut *ptr.as_ptr()
+            }
+        }
+
+        /// Copies `values` into the region
+        #[allow(clippy::mut_from_ref)] // each call returns fresh, non-overlapping memory
+        pub fn alloc_slice_copy<T: Copy>(&self, values: &[T]) -> &mut [T] {
+            let layout = Layout::for_value(values);
+            let ptr = self.alloc_layout(layout).cast::<T>();
+            unsafe {
+                core::ptr::copy_nonoverlapping(values.as_ptr(), ptr.as_ptr(), values.len());
+                core::slice::from_raw_parts_mut(ptr.as_ptr(), values.len())
             }
+        }
 
-            // mimalloc - high-performance allocator with compiler compatibility detection
-            #[cfg(all(
-                feature = "_mimalloc",
-                not(target_arch = "wasm32"),
-                not(debug_assertions),
-                not(target_os = "none")
-            ))]
-            2 => {
-                mimalloc_rust::GlobalMiMalloc.alloc(layout)
+        /// Reserves uninitialized memory for `layout`
+        ///
+        /// Zero-sized layouts get a dangling, well-aligned pointer. Aborts through
+        /// [`std::alloc::handle_alloc_error()`] if the backend is out of memory.
+        pub fn alloc_layout(&self, layout: Layout) -> NonNull<u8> {
+            if layout.size() == 0 {
+                // Any non-null address that is a multiple of the alignment will do
+                return unsafe { NonNull::new_unchecked(layout.align() as *mut u8) };
+            }
+
+            let start = match self.bump(layout) {
+                Some(start) => start,
+                None => {
+                    self.grow(layout);
+                    self.bump(layout).expect("fresh region chunk fits the allocation")
+                }
+            };
+            self.allocated.set(self.allocated.get() + layout.size());
+            unsafe { NonNull::new_unchecked(start as *mut u8) }
+        }
 
+        /// Frees every chunk; the region can be reused afterwards
+        ///
+        /// Takes `&mut self`, so no reference handed out by the region survives it.
+        pub fn reset(&mut self) {
+            for (chunk, layout) in self.chunks.get_mut().drain(..) {
+                unsafe { GlobalAlloc::dealloc(&crate::RuntimeAllocator, chunk.as_ptr(), layout) };
             }
+            self.next.set(0);
+            self.end.set(0);
+            self.allocated.set(0);
+        }
 
-            // embedded-alloc - for all no_std embedded platforms
-            #[cfg(all(
-                feature = "_embedded",
-                target_os = "none"
-            ))]
-            4 => {
-                // Use embedded-alloc for all no_std targets
-                #[cfg(not(target_os = "none"))]
-                {
-                    embedded_heap_config::EMBEDDED_HEAP.alloc(layout)
+        /// Bytes handed out since creation or the last reset (requested sizes, without padding)
+        pub fn allocated_bytes(&self) -> usize {
+            self.allocated.get()
+        }
+
+        /// Number of chunks currently reserved from the backend
+        pub fn chunk_count(&self) -> usize {
+            unsafe { (*self.chunks.get()).len() }
+        }
+
+        // Carves `layout` out of the current chunk, if it fits
+        fn bump(&self, layout: Layout) -> Option<usize> {
+            let start = self.next.get().checked_add(layout.align() - 1)? & !(layout.align() - 1);
+            let end = start.checked_add(layout.size())?;
+            if self.next.get() == 0 || end > self.end.get() {
+                return None;
+            }
+            self.next.set(end);
+            Some(start)
+        }
+
+        // Starts a new chunk that can hold `layout`
+        fn grow(&self, layout: Layout) {
+            let needed = layout
+                .size()
+                .checked_add(layout.align().saturating_sub(CHUNK_ALIGN))
+                .expect("region allocation size overflow");
+            let chunk_layout = Layout::from_size_align(self.chunk_size.max(needed), CHUNK_ALIGN)
+                .expect("region chunk size overflow");
+
+            let chunk = unsafe { GlobalAlloc::alloc(&crate::RuntimeAllocator, chunk_layout) };
+            let Some(chunk) = NonNull::new(chunk) else {
+                std::alloc::handle_alloc_error(chunk_layout);
+            };
+            unsafe { (*self.chunks.get()).push((chunk, chunk_layout)) };
+            self.next.set(chunk.as_ptr() as usize);
+            self.end.set(chunk.as_ptr() as usize + chunk_layout.size());
+        }
+    }
+
+    impl Default for Region {
+        fn default() -> Region {
+            Region::new()
+        }
+    }
+
+    impl Drop for Region {
+        fn drop(&mut self) {
+            self.reset();
+        }
+    }
+
+    impl core::fmt::Debug for Region {
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+            f.debug_struct("Region")
+                .field("chunk_size", &self.chunk_size)
+                .field("chunk_count", &self.chunk_count())
+                .field("allocated_bytes", &self.allocated_bytes())
+                .finish()
+        }
+    }
+}
+
+#[cfg(all(feature = "region", not(target_os = "none")))]
+pub use region::Region;
+
+// ========== Memory Pressure Monitoring ==========
+
+// Opt-in background monitor for Linux memory pressure (`pressure` feature)
+// Reads PSI (/proc/pressure/memory), falling back to cgroup v2 memory.events
+#[cfg(all(feature = "pressure", target_os = "linux"))]
+mod pressure {
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Mutex;
+    use std::time::Duration;
+
+    /// Memory pressure level passed to the pressure callback
+    ///
+    /// Derived from PSI `some`/`full` stall percentages, or from cgroup v2
+    /// `memory.events` counters (`high` → Moderate, `max`/`oom` → Critical).
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+    pub enum PressureLevel {
+        /// No significant memory stalls
+        Normal,
+
+        /// Some tasks are stalling on memory (PSI `some avg10` >= 10%, or cgroup `high` reached)
+        Moderate,
+
+        /// All tasks are stalling on memory (PSI `full avg10` >= 5%, or cgroup `max`/OOM hit)
+        Critical,
+    }
+
+    impl PressureLevel {
+        /// Level recorded in the contents of a cgroup v2 `memory.events` file
+        ///
+        /// The counters only grow, so this tells whether the cgroup has *ever* been
+        /// under pressure: any `max` or `oom` event is `Critical`, any `high` event
+        /// `Moderate`. The monitor thread reacts to counter increases instead.
+        ///
+        /// # Example
+        ///
+        /// ```rust
+        /// use auto_allocator::PressureLevel;
+        ///
+        /// let events = "low 0\nhigh 12\nmax 0\noom 0\noom_kill 0\n";
+        /// assert_eq!(PressureLevel::from_cgroup_events(events), PressureLevel::Moderate);
+        /// ```
+        pub fn from_cgroup_events(content: &str) -> PressureLevel {
+            let (high, max) = cgroup_event_counters(content);
+            level_since(0, 0, high, max)
+        }
+    }
+
+    /// Level for counters that moved from `(high, max)` to `(new_high, new_max)`
+    fn level_since(high: u64, max: u64, new_high: u64, new_max: u64) -> PressureLevel {
+        if new_max > max {
+            PressureLevel::Critical
+        } else if new_high > high {
+            PressureLevel::Moderate
+        } else {
+            PressureLevel::Normal
+        }
+    }
+
+    const POLL_INTERVAL: Duration = Duration::from_secs(1);
+    const PSI_MODERATE_SOME_AVG10: f64 = 10.0;
+    const PSI_CRITICAL_FULL_AVG10: f64 = 5.0;
+
+    static CALLBACK: Mutex<Option<fn(PressureLevel)>> = Mutex::new(None);
+    static MONITOR_ENABLED: AtomicBool = AtomicBool::new(true);
+    static MONITOR_RUNNING: AtomicBool = AtomicBool::new(false);
+
+    /// Sets the callback invoked when the memory pressure level changes
+    ///
+    /// The callback runs on the dedicated monitor thread, which is started lazily
+    /// on the first [`get_allocator_info()`](crate::get_allocator_info) call.
+    /// It fires only when the level crosses a threshold (including back to `Normal`).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use auto_allocator::PressureLevel;
+    ///
+    /// fn on_pressure(level: PressureLevel) {
+    ///     if level == PressureLevel::Critical {
+    ///         // drop caches before the OOM killer fires
+    ///     }
+    /// }
+    ///
+    /// auto_allocator::set_memory_pressure_callback(on_pressure);
+    /// ```
+    pub fn set_memory_pressure_callback(callback: fn(PressureLevel)) {
+        if let Ok(mut slot) = CALLBACK.lock() {
+            *slot = Some(callback);
+        }
+    }
+
+    /// Enables or disables the background pressure monitor thread
+    ///
+    /// Disabling before the first [`get_allocator_info()`](crate::get_allocator_info)
+    /// call prevents the thread from ever starting; disabling later stops it
+    /// at its next poll. Enabled by default when the `pressure` feature is on.
+    pub fn set_memory_pressure_monitor_enabled(enabled: bool) {
+        MONITOR_ENABLED.store(enabled, Ordering::Release);
+    }
+
+    /// Reads the current memory pressure level once, without the monitor thread
+    ///
+    /// Returns `None` when neither PSI nor cgroup v2 memory events are available.
+    /// The cgroup fallback is counter-based, so a one-shot read reports
+    /// `Critical` if the cgroup has ever hit its `max` limit.
+    pub fn current_memory_pressure() -> Option<PressureLevel> {
+        match PressureSource::detect()? {
+            mut psi @ PressureSource::Psi => psi.poll(),
+            // No earlier reading to compare with: count every event since the cgroup started
+            PressureSource::CgroupEvents { high, max, .. } => Some(level_since(0, 0, high, max)),
+        }
+    }
+
+    /// Starts the monitor thread once, if enabled
+    pub(crate) fn ensure_monitor_started() {
+        if !MONITOR_ENABLED.load(Ordering::Acquire) {
+            return;
+        }
+        if MONITOR_RUNNING
+            .compare_exchange(false, true, Ordering::AcqRel, Ordering::Acquire)
+            .is_err()
+        {
+            return;
+        }
+
+        let spawned = std::thread::Builder::new()
+            .name("auto-allocator-pressure".to_string())
+            .spawn(monitor_loop);
+        if spawned.is_err() {
+            MONITOR_RUNNING.store(false, Ordering::Release);
+        }
+    }
+
+    fn monitor_loop() {
+        if let Some(mut source) = PressureSource::detect() {
+            let mut last_level = PressureLevel::Normal;
+
+            while MONITOR_ENABLED.load(Ordering::Acquire) {
+                if let Some(level) = source.poll() {
+                    if level != last_level {
+                        last_level = level;
+                        let callback = CALLBACK.lock().ok().and_then(|slot| *slot);
+                        if let Some(callback) = callback {
+                            callback(level);
+                        }
+                    }
                 }
-                #[cfg(target_os = "none")]
-                {
-                    embedded_heap_config::get_embedded_heap().alloc(layout)
+                std::thread::sleep(POLL_INTERVAL);
+            }
+        }
+
+        // Allow a later re-enable to restart the monitor
+        MONITOR_RUNNING.store(false, Ordering::Release);
+    }
+
+    enum PressureSource {
+        Psi,
+        CgroupEvents { path: String, high: u64, max: u64 },
+    }
+
+    impl PressureSource {
+        fn detect() -> Option<Self> {
+            if read_psi().is_some() {
+                return Some(PressureSource::Psi);
+            }
+
+            let path = cgroup_memory_events_path()?;
+            let (high, max) = read_cgroup_events(&path)?;
+            Some(PressureSource::CgroupEvents { path, high, max })
+        }
+
+        fn poll(&mut self) -> Option<PressureLevel> {
+            match self {
+                PressureSource::Psi => {
+                    let (some_avg10, full_avg10) = read_psi()?;
+                    Some(if full_avg10 >= PSI_CRITICAL_FULL_AVG10 {
+                        PressureLevel::Critical
+                    } else if some_avg10 >= PSI_MODERATE_SOME_AVG10 {
+                        PressureLevel::Moderate
+                    } else {
+                        PressureLevel::Normal
+                    })
+                }
+                PressureSource::CgroupEvents { path, high, max } => {
+                    let (new_high, new_max) = read_cgroup_events(path)?;
+                    let level = level_since(*high, *max, new_high, new_max);
+                    *high = new_high;
+                    *max = new_max;
+                    Some(level)
                 }
             }
+        }
+    }
 
-            // System allocator - default fallback
-            #[cfg(not(target_os = "none"))]
-            _ => alloc::System.alloc(layout),
-            
-            #[cfg(target_os = "none")]
-            _ => core::ptr::null_mut(),
+    /// Returns `(some avg10, full avg10)` from /proc/pressure/memory
+    fn read_psi() -> Option<(f64, f64)> {
+        let content = std::fs::read_to_string("/proc/pressure/memory").ok()?;
+        Some((psi_avg10(&content, "some")?, psi_avg10(&content, "full")?))
+    }
+
+    fn psi_avg10(content: &str, kind: &str) -> Option<f64> {
+        content
+            .lines()
+            .find(|line| line.starts_with(kind))?
+            .split_whitespace()
+            .find_map(|field| field.strip_prefix("avg10="))?
+            .parse()
+            .ok()
+    }
+
+    /// Locates memory.events for this process's cgroup v2
+    fn cgroup_memory_events_path() -> Option<String> {
+        let cgroup = std::fs::read_to_string("/proc/self/cgroup").ok()?;
+        let relative = cgroup.lines().find_map(|line| line.strip_prefix("0::"))?;
+        let own = format!("/sys/fs/cgroup{}/memory.events", relative.trim_end_matches('/'));
+
+        [own, "/sys/fs/cgroup/memory.events".to_string()]
+            .into_iter()
+            .find(|path| std::path::Path::new(path).exists())
+    }
+
+    /// Returns the `(high, max + oom)` counters from a memory.events file
+    fn read_cgroup_events(path: &str) -> Option<(u64, u64)> {
+        Some(cgroup_event_counters(&std::fs::read_to_string(path).ok()?))
+    }
+
+    /// Returns the `(high, max + oom)` counters from memory.events contents; missing ones are 0
+    fn cgroup_event_counters(content: &str) -> (u64, u64) {
+        let counter = |name: &str| -> u64 {
+            content
+                .lines()
+                .find_map(|line| line.strip_prefix(name)?.strip_prefix(' ')?.trim().parse().ok())
+                .unwrap_or(0)
+        };
+        (counter("high"), counter("max") + counter("oom"))
+    }
+}
+
+#[cfg(all(feature = "pressure", target_os = "linux"))]
+pub use pressure::{
+    current_memory_pressure, set_memory_pressure_callback, set_memory_pressure_monitor_enabled,
+    PressureLevel,
+};
+
+// ========== mimalloc Options ==========
+
+// Runtime tunables for the bundled mimalloc (`mimalloc-options` feature, std only)
+// Applied through mi_option_set, which only stores the value and never allocates
+#[cfg(all(feature = "mimalloc-options", not(target_os = "none")))]
+mod mimalloc_options {
+    /// mimalloc tunables applied by [`configure_mimalloc()`]
+    ///
+    /// `None` leaves mimalloc's built-in default (or its `MIMALLOC_*` environment variable) in place.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use auto_allocator::{configure_mimalloc, MimallocOptions};
+    ///
+    /// // Trade memory for latency: commit segments up front, keep freed pages longer
+    /// let applied = configure_mimalloc(MimallocOptions {
+    ///     eager_commit: Some(true),
+    ///     reset_delay_ms: Some(500),
+    ///     ..Default::default()
+    /// });
+    /// println!("mimalloc options applied: {}", applied);
+    /// ```
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+    pub struct MimallocOptions {
+        /// Commit whole segments eagerly instead of on demand (`mi_option_eager_commit`)
+        pub eager_commit: Option<bool>,
+        /// Delay before freed pages are reset/returned to the OS (`mi_option_reset_delay`)
+        pub reset_delay_ms: Option<u64>,
+        /// Newer mimalloc name for the reset delay; the bundled mimalloc 1.7 has a single
+        /// delay option, so this sets `mi_option_reset_delay` too and wins over `reset_delay_ms`
+        pub purge_delay_ms: Option<u64>,
+        /// Allow large (2MB) OS pages where available (`mi_option_large_os_pages`)
+        pub allow_large_os_pages: Option<bool>,
+    }
+
+    /// Applies mimalloc tunables when mimalloc is the active allocator
+    ///
+    /// Best called as early as possible - ideally from a pre-main constructor, since
+    /// the Rust runtime allocates before `main`. Options mimalloc reads per segment
+    /// (eager commit, reset delay) still take effect for memory reserved afterwards.
+    ///
+    /// Returns `true` if the options were applied, `false` (no-op) when the active
+    /// backend isn't mimalloc - e.g. debug builds, Android/BSD without the opt-in features.
+    pub fn configure_mimalloc(options: MimallocOptions) -> bool {
+        let active_id = crate::RuntimeAllocator::get_allocator_id();
+        if active_id != 2 && active_id != 5 {
+            return false;
         }
+        apply(&options);
+        true
     }
 
-    #[inline]
-    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
-        match Self::get_allocator_id() {
+    #[cfg(auto_allocator_mimalloc)]
+    fn apply(options: &MimallocOptions) {
+        use mimalloc_rust::raw::runtime_options::{
+            mi_option_eager_commit, mi_option_large_os_pages, mi_option_reset_delay, mi_option_t,
+        };
+        use mimalloc_rust::GlobalMiMalloc;
 
-            // mimalloc-secure - security-hardened allocator
-            #[cfg(all(
-                feature = "_mimalloc_secure",
-                not(target_arch = "wasm32"),
-                not(debug_assertions),
-                not(target_os = "none")
-            ))]
-            5 => {
-                mimalloc_rust::GlobalMiMalloc.dealloc(ptr, layout)
+        let set_flag = |option: mi_option_t, enabled: bool| {
+            if enabled {
+                GlobalMiMalloc::option_enable(option)
+            } else {
+                GlobalMiMalloc::option_disable(option)
             }
+        };
+        let clamp_ms = |ms: u64| ms.min(core::ffi::c_long::MAX as u64) as core::ffi::c_long;
 
-            // mimalloc - high-performance allocator with compiler compatibility detection
-            #[cfg(all(
-                feature = "_mimalloc",
-                not(target_arch = "wasm32"),
-                not(debug_assertions),
-                not(target_os = "none")
-            ))]
-            2 => {
-                mimalloc_rust::GlobalMiMalloc.dealloc(ptr, layout)
+        if let Some(enabled) = options.eager_commit {
+            set_flag(mi_option_eager_commit, enabled);
+        }
+        if let Some(enabled) = options.allow_large_os_pages {
+            set_flag(mi_option_large_os_pages, enabled);
+        }
+        if let Some(ms) = options.purge_delay_ms.or(options.reset_delay_ms) {
+            GlobalMiMalloc::option_set(mi_option_reset_delay, clamp_ms(ms));
+        }
+    }
+
+    /// Lets mimalloc back segments with huge pages when THP is enabled (`huge-pages` feature)
+    ///
+    /// Runs right after allocator selection; mimalloc checks the option per OS allocation,
+    /// so segments reserved from then on use `MADV_HUGEPAGE`.
+    /// With THP set to `never` the option would silently do nothing, so it stays off
+    /// and a warning goes to stderr instead.
+    #[cfg(feature = "huge-pages")]
+    pub(crate) fn enable_huge_pages_if_available(allocator_id: u8) {
+        if allocator_id != 2 && allocator_id != 5 {
+            return;
+        }
+        match crate::thp_mode_safe() {
+            Some(crate::ThpMode::Always | crate::ThpMode::Madvise) => apply(&MimallocOptions {
+                allow_large_os_pages: Some(true),
+                ..Default::default()
+            }),
+            #[cfg(unix)]
+            Some(crate::ThpMode::Never) => {
+                // Static message: this runs inside the first allocation
+                const WARNING: &[u8] = b"[WARN] Auto-allocator: huge-pages feature enabled, but transparent huge pages are set to 'never' - large OS pages stay off\n";
+                #[cfg(auto_allocator_no_libc)]
+                crate::raw_syscall::write(crate::STDERR_LOG_FD.load(core::sync::atomic::Ordering::Acquire), WARNING);
+                #[cfg(not(auto_allocator_no_libc))]
+                unsafe {
+                    libc::write(
+                        crate::STDERR_LOG_FD.load(core::sync::atomic::Ordering::Acquire),
+                        WARNING.as_ptr() as *const libc::c_void,
+                        WARNING.len(),
+                    );
+                }
+            }
+            // Windows has no THP; large pages need SeLockMemoryPrivilege enabled in the token
+            #[cfg(windows)]
+            None if windows_large_pages::privilege_state() == Some(true) => apply(&MimallocOptions {
+                allow_large_os_pages: Some(true),
+                ..Default::default()
+            }),
+            _ => {}
+        }
+    }
+
+    /// Whether the account holds `SeLockMemoryPrivilege`, needed for large pages on Windows
+    ///
+    /// `true` when the privilege is in the process token, enabled or not - i.e. the
+    /// account was granted "Lock pages in memory" in the security policy, and
+    /// [`enable_large_pages_privilege()`] can switch it on.
+    #[cfg(all(windows, feature = "huge-pages"))]
+    pub fn has_large_pages_privilege() -> bool {
+        windows_large_pages::privilege_state().is_some()
+    }
+
+    /// Enables `SeLockMemoryPrivilege` for the process so mimalloc can use large pages (Windows)
+    ///
+    /// Large (2MB) pages on Windows need the privilege both granted to the account and
+    /// enabled in the process token; this does the second step with
+    /// `AdjustTokenPrivileges`, then turns on mimalloc's large OS pages when mimalloc is
+    /// the active allocator. Call it early - only memory mimalloc reserves afterwards
+    /// uses large pages.
+    ///
+    /// # Errors
+    ///
+    /// - [`std::io::ErrorKind::PermissionDenied`] when the account doesn't hold the
+    ///   privilege: grant "Lock pages in memory" (Local Security Policy > User Rights
+    ///   Assignment) and sign in again. Running elevated doesn't add it.
+    /// - [`std::io::ErrorKind::Unsupported`] when the system has no large pages.
+    /// - The OS error when the token can't be opened or adjusted.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// match auto_allocator::enable_large_pages_privilege() {
+    ///     Ok(()) => println!("large pages on"),
+    ///     Err(error) => eprintln!("large pages unavailable: {}", error),
+    /// }
+    /// ```
+    #[cfg(all(windows, feature = "huge-pages"))]
+    pub fn enable_large_pages_privilege() -> std::io::Result<()> {
+        windows_large_pages::enable_privilege()?;
+        let active_id = crate::RuntimeAllocator::get_allocator_id();
+        if active_id == 2 || active_id == 5 {
+            apply(&MimallocOptions {
+                allow_large_os_pages: Some(true),
+                ..Default::default()
+            });
+        }
+        Ok(())
+    }
+
+    // Token plumbing for SeLockMemoryPrivilege; none of it allocates, so the state
+    // check can run during selection
+    #[cfg(all(windows, feature = "huge-pages"))]
+    mod windows_large_pages {
+        use std::io::{Error, ErrorKind};
+        use winapi::shared::minwindef::DWORD;
+        use winapi::shared::winerror::ERROR_NOT_ALL_ASSIGNED;
+        use winapi::um::errhandlingapi::GetLastError;
+        use winapi::um::handleapi::CloseHandle;
+        use winapi::um::memoryapi::GetLargePageMinimum;
+        use winapi::um::processthreadsapi::{GetCurrentProcess, OpenProcessToken};
+        use winapi::um::securitybaseapi::{AdjustTokenPrivileges, GetTokenInformation};
+        use winapi::um::winbase::LookupPrivilegeValueW;
+        use winapi::um::winnt::{
+            TokenPrivileges, HANDLE, LUID, LUID_AND_ATTRIBUTES, SE_PRIVILEGE_ENABLED,
+            TOKEN_ADJUST_PRIVILEGES, TOKEN_PRIVILEGES, TOKEN_QUERY,
+        };
+
+        // "SeLockMemoryPrivilege" as a NUL-terminated UTF-16 string
+        const SE_LOCK_MEMORY_NAME: [u16; 22] = {
+            let name = b"SeLockMemoryPrivilege";
+            let mut wide = [0u16; 22];
+            let mut i = 0;
+            while i < name.len() {
+                wide[i] = name[i] as u16;
+                i += 1;
+            }
+            wide
+        };
+
+        struct Token(HANDLE);
+
+        impl Token {
+            fn open(access: DWORD) -> Option<Token> {
+                let mut handle: HANDLE = core::ptr::null_mut();
+                let opened = unsafe { OpenProcessToken(GetCurrentProcess(), access, &mut handle) };
+                (opened != 0).then_some(Token(handle))
+            }
+        }
+
+        impl Drop for Token {
+            fn drop(&mut self) {
+                unsafe { CloseHandle(self.0) };
+            }
+        }
+
+        fn lock_memory_luid() -> Option<LUID> {
+            let mut luid: LUID = unsafe { core::mem::zeroed() };
+            let found = unsafe {
+                LookupPrivilegeValueW(core::ptr::null(), SE_LOCK_MEMORY_NAME.as_ptr(), &mut luid)
+            };
+            (found != 0).then_some(luid)
+        }
+
+        /// `None` if the token lacks the privilege, otherwise whether it is enabled
+        pub(super) fn privilege_state() -> Option<bool> {
+            let luid = lock_memory_luid()?;
+            let token = Token::open(TOKEN_QUERY)?;
+
+            // Stack buffer for TOKEN_PRIVILEGES: room for 64 privileges, far more than Windows defines
+            let mut buffer = [0u64; 128];
+            let mut length: DWORD = 0;
+            let ok = unsafe {
+                GetTokenInformation(
+                    token.0,
+                    TokenPrivileges,
+                    buffer.as_mut_ptr() as *mut _,
+                    core::mem::size_of_val(&buffer) as DWORD,
+                    &mut length,
+                )
+            };
+            if ok == 0 {
+                return None;
+            }
+            let privileges = unsafe {
+                let header = &*(buffer.as_ptr() as *const TOKEN_PRIVILEGES);
+                core::slice::from_raw_parts(
+                    header.Privileges.as_ptr(),
+                    header.PrivilegeCount as usize,
+                )
+            };
+            privileges
+                .iter()
+                .find(|entry| entry.Luid.LowPart == luid.LowPart && entry.Luid.HighPart == luid.HighPart)
+                .map(|entry| entry.Attributes & SE_PRIVILEGE_ENABLED != 0)
+        }
+
+        pub(super) fn enable_privilege() -> std::io::Result<()> {
+            if unsafe { GetLargePageMinimum() } == 0 {
+                return Err(Error::new(ErrorKind::Unsupported, "large pages are not supported on this system"));
+            }
+            let luid = lock_memory_luid().ok_or_else(Error::last_os_error)?;
+            let token = Token::open(TOKEN_ADJUST_PRIVILEGES | TOKEN_QUERY).ok_or_else(Error::last_os_error)?;
+
+            let mut privileges = TOKEN_PRIVILEGES {
+                PrivilegeCount: 1,
+                Privileges: [LUID_AND_ATTRIBUTES {
+                    Luid: luid,
+                    Attributes: SE_PRIVILEGE_ENABLED,
+                }],
+            };
+            let adjusted = unsafe {
+                AdjustTokenPrivileges(
+                    token.0,
+                    0,
+                    &mut privileges,
+                    0,
+                    core::ptr::null_mut(),
+                    core::ptr::null_mut(),
+                )
+            };
+            if adjusted == 0 {
+                return Err(Error::last_os_error());
+            }
+            // AdjustTokenPrivileges succeeds even when the account doesn't hold the privilege
+            if unsafe { GetLastError() } == ERROR_NOT_ALL_ASSIGNED {
+                return Err(Error::new(
+                    ErrorKind::PermissionDenied,
+                    "SeLockMemoryPrivilege is not granted to this account (\"Lock pages in memory\" user right)",
+                ));
+            }
+            Ok(())
+        }
+    }
+
+    // mimalloc is never the active backend here, so configure_mimalloc returns before this
+    #[cfg(not(auto_allocator_mimalloc))]
+    fn apply(_options: &MimallocOptions) {}
+}
+
+#[cfg(all(feature = "mimalloc-options", not(target_os = "none")))]
+pub use mimalloc_options::{configure_mimalloc, MimallocOptions};
+
+#[cfg(all(windows, feature = "huge-pages"))]
+pub use mimalloc_options::{enable_large_pages_privilege, has_large_pages_privilege};
+
+// ========== mimalloc Backend Stats ==========
+
+// mimalloc heap introspection (`mimalloc-stats` feature, std only)
+#[cfg(all(feature = "mimalloc-stats", not(target_os = "none")))]
+mod backend_stats {
+    /// Memory held by a mimalloc heap, returned by [`thread_backend_stats()`]
+    ///
+    /// Unlike the `stats` counters, these are the backend's own numbers: page
+    /// granularity, including free space mimalloc keeps cached for reuse.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// if let Some(heap) = auto_allocator::thread_backend_stats() {
+    ///     println!("{} of {} committed bytes in use", heap.used_bytes, heap.committed_bytes);
+    /// }
+    /// ```
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+    pub struct BackendStats {
+        /// Address space reserved for the heap's pages
+        pub reserved_bytes: u64,
+
+        /// Memory committed for the heap's pages (backed by RAM or swap once touched)
+        pub committed_bytes: u64,
+
+        /// Bytes in live blocks, counted at mimalloc's block size
+        pub used_bytes: u64,
+
+        /// Number of live blocks
+        pub block_count: u64,
+    }
+
+    /// Returns the calling thread's mimalloc heap usage, `None` if mimalloc isn't active
+    ///
+    /// mimalloc gives every thread its own heap, so process-wide numbers can hide one
+    /// worker holding most of the committed memory; call this from each worker (e.g.
+    /// when it reports metrics) to compare them. Only the calling thread's heap is
+    /// visited: blocks it allocated count here even if another thread holds them, and
+    /// blocks other threads allocated don't. Costs one walk over the heap's pages.
+    pub fn thread_backend_stats() -> Option<BackendStats> {
+        let active_id = crate::RuntimeAllocator::get_allocator_id();
+        if active_id != 2 && active_id != 5 {
+            return None;
+        }
+        visit_default_heap()
+    }
+
+    #[cfg(auto_allocator_mimalloc)]
+    fn visit_default_heap() -> Option<BackendStats> {
+        use core::ffi::c_void;
+        use mimalloc_rust::raw::heap::{mi_heap_area_t, mi_heap_get_default, mi_heap_visit_blocks};
+        use mimalloc_rust::raw::types::mi_heap_t;
+
+        // Called once per page area: without visit_all_blocks, `block` is always null
+        unsafe extern "C" fn add_area(
+            _heap: *const mi_heap_t,
+            area: *const mi_heap_area_t,
+            _block: *mut c_void,
+            _block_size: usize,
+            arg: *mut c_void,
+        ) -> bool {
+            let stats = &mut *(arg as *mut BackendStats);
+            let area = &*area;
+            stats.reserved_bytes += area.reserved as u64;
+            stats.committed_bytes += area.committed as u64;
+            stats.used_bytes += (area.used * area.block_size) as u64;
+            stats.block_count += area.used as u64;
+            true
+        }
+
+        let mut stats = BackendStats::default();
+        unsafe {
+            mi_heap_visit_blocks(
+                mi_heap_get_default(),
+                false,
+                Some(add_area),
+                &mut stats as *mut BackendStats as *mut c_void,
+            );
+        }
+        Some(stats)
+    }
+
+    // mimalloc is never the active backend here
+    #[cfg(not(auto_allocator_mimalloc))]
+    fn visit_default_heap() -> Option<BackendStats> {
+        None
+    }
+}
+
+#[cfg(all(feature = "mimalloc-stats", not(target_os = "none")))]
+pub use backend_stats::{thread_backend_stats, BackendStats};
+
+// ========== Realtime Mode ==========
+
+// Advisory hardening for soft-realtime threads (`realtime` feature, std only):
+// reserve and commit memory up front, optionally lock it, and in debug builds
+// check that allocations inside a realtime section don't page-fault
+#[cfg(all(feature = "realtime", not(target_os = "none")))]
+mod realtime {
+    use core::sync::atomic::{AtomicBool, Ordering};
+
+    /// Setup applied by [`enable_realtime_mode()`]
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use auto_allocator::RealtimeOptions;
+    ///
+    /// let options = RealtimeOptions {
+    ///     reserve_bytes: 256 << 20,
+    ///     ..Default::default()
+    /// };
+    /// assert!(!options.lock_memory);
+    /// ```
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct RealtimeOptions {
+        /// OS memory mimalloc reserves and commits up front (`mi_reserve_os_memory`), 64MB by default
+        pub reserve_bytes: usize,
+        /// Also pin the process's pages with [`lock_memory()`](crate::lock_memory) so they
+        /// are never swapped out (needs `CAP_IPC_LOCK` or a large enough `RLIMIT_MEMLOCK` on Linux)
+        pub lock_memory: bool,
+    }
+
+    impl Default for RealtimeOptions {
+        fn default() -> Self {
+            RealtimeOptions {
+                reserve_bytes: 64 << 20,
+                lock_memory: false,
+            }
+        }
+    }
+
+    static REALTIME_MODE: AtomicBool = AtomicBool::new(false);
+
+    /// Prepares the allocator for soft-realtime use
+    ///
+    /// With mimalloc active, commits every segment eagerly, stops returning freed pages
+    /// to the OS and reserves `reserve_bytes` of committed memory, so allocations after
+    /// warmup are served without `mmap`/`madvise` calls or commit faults. With
+    /// `lock_memory`, the process's pages are then pinned with [`lock_memory()`](crate::lock_memory).
+    /// This hardens the existing backend - it is not a new allocator,
+    /// and a large enough burst can still outgrow the reservation.
+    ///
+    /// Setup sequence:
+    /// 1. **Warmup** - call this, then run the workload once (or
+    ///    [`prefault_heap()`](crate::prefault_heap) its peak working set) so the memory is touched
+    /// 2. **Lock** - set `lock_memory` (or call this again with it) after warmup
+    /// 3. **Go realtime** - enter the hot loop inside a [`realtime_section()`]
+    ///
+    /// Returns `true` when every requested step succeeded; `false` when the backend isn't
+    /// mimalloc (debug builds, platforms using the native allocator) or locking failed.
+    /// Only a successful call turns on [`is_realtime_mode_enabled()`].
+    pub fn enable_realtime_mode(options: RealtimeOptions) -> bool {
+        let reserved = reserve(options.reserve_bytes);
+        let locked = !options.lock_memory || crate::lock_memory().is_ok();
+        let enabled = reserved && locked;
+        if enabled {
+            REALTIME_MODE.store(true, Ordering::Release);
+        }
+        enabled
+    }
+
+    /// Whether a call to [`enable_realtime_mode()`] has succeeded
+    ///
+    /// A later failing call (e.g. `lock_memory` refused after a successful warmup call)
+    /// leaves it on: the reservation from the earlier call is still in place.
+    pub fn is_realtime_mode_enabled() -> bool {
+        REALTIME_MODE.load(Ordering::Acquire)
+    }
+
+    #[cfg(auto_allocator_mimalloc)]
+    fn reserve(reserve_bytes: usize) -> bool {
+        use mimalloc_rust::raw::runtime_options::{
+            mi_option_eager_commit, mi_option_eager_commit_delay, mi_option_page_reset,
+            mi_option_reset_decommits, mi_option_segment_reset, mi_option_abandoned_page_reset,
+        };
+        use mimalloc_rust::GlobalMiMalloc;
+
+        extern "C" {
+            // Not bound by mimalloc-rust-sys; returns 0 or an errno value
+            fn mi_reserve_os_memory(size: usize, commit: bool, allow_large: bool) -> core::ffi::c_int;
+        }
+
+        let active_id = crate::RuntimeAllocator::get_allocator_id();
+        if active_id != 2 && active_id != 5 {
+            return false;
+        }
+
+        // Commit on reservation and keep freed pages committed
+        GlobalMiMalloc::option_enable(mi_option_eager_commit);
+        GlobalMiMalloc::option_set(mi_option_eager_commit_delay, 0);
+        GlobalMiMalloc::option_disable(mi_option_page_reset);
+        GlobalMiMalloc::option_disable(mi_option_segment_reset);
+        GlobalMiMalloc::option_disable(mi_option_abandoned_page_reset);
+        GlobalMiMalloc::option_disable(mi_option_reset_decommits);
+
+        reserve_bytes == 0 || unsafe { mi_reserve_os_memory(reserve_bytes, true, true) } == 0
+    }
+
+    // mimalloc is never the active backend here
+    #[cfg(not(auto_allocator_mimalloc))]
+    fn reserve(_reserve_bytes: usize) -> bool {
+        false
+    }
+
+
+    /// Marks the calling thread's hot path until the guard is dropped
+    ///
+    /// In debug builds on Linux, allocations made inside the section are checked for
+    /// page faults (via `getrusage(RUSAGE_THREAD)`), and dropping the guard panics if any
+    /// occurred - a sign the warmup didn't cover the hot path. Release builds and other
+    /// platforms skip the check, so the guard costs nothing there. Sections don't nest.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let section = auto_allocator::realtime_section();
+    /// // ... process one audio buffer ...
+    /// drop(section);
+    /// ```
+    pub fn realtime_section() -> RealtimeSection {
+        #[cfg(debug_assertions)]
+        hot_path::enter();
+        RealtimeSection { _private: () }
+    }
+
+    /// Guard returned by [`realtime_section()`]
+    #[derive(Debug)]
+    pub struct RealtimeSection {
+        _private: (),
+    }
+
+    impl Drop for RealtimeSection {
+        fn drop(&mut self) {
+            #[cfg(debug_assertions)]
+            {
+                let faults = hot_path::leave();
+                if !std::thread::panicking() {
+                    debug_assert_eq!(
+                        faults, 0,
+                        "auto-allocator: {} page fault(s) during allocation in a realtime section",
+                        faults
+                    );
+                }
+            }
+        }
+    }
+
+    // Per-thread fault accounting behind the debug assertion; runs inside the
+    // global allocator, so it must stay allocation-free
+    #[cfg(debug_assertions)]
+    pub(crate) mod hot_path {
+        use core::cell::Cell;
+
+        thread_local! {
+            // Faults seen inside the current section, None outside one
+            static SECTION_FAULTS: Cell<Option<u64>> = const { Cell::new(None) };
+        }
+
+        pub(super) fn enter() {
+            let _ = SECTION_FAULTS.try_with(|faults| faults.set(Some(0)));
+        }
+
+        pub(super) fn leave() -> u64 {
+            SECTION_FAULTS
+                .try_with(|faults| faults.take())
+                .ok()
+                .flatten()
+                .unwrap_or(0)
+        }
+
+        /// Watches one allocation; faults are recorded when the watch is dropped
+        pub(crate) struct Watch(Option<u64>);
+
+        impl Watch {
+            #[inline]
+            pub(crate) fn start() -> Watch {
+                let in_section = SECTION_FAULTS
+                    .try_with(|faults| faults.get().is_some())
+                    .unwrap_or(false);
+                Watch(if in_section { thread_faults() } else { None })
             }
+        }
 
-            #[cfg(all(
-                feature = "_embedded",
-                target_os = "none"
-            ))]
-            4 => {
-                // Use embedded-alloc for all no_std targets
-                #[cfg(not(target_os = "none"))]
-                {
-                    embedded_heap_config::EMBEDDED_HEAP.dealloc(ptr, layout)
-                }
-                #[cfg(target_os = "none")]
-                {
-                    embedded_heap_config::get_embedded_heap().dealloc(ptr, layout)
+        impl Drop for Watch {
+            #[inline]
+            fn drop(&mut self) {
+                if let (Some(before), Some(after)) = (self.0, thread_faults()) {
+                    let _ = SECTION_FAULTS.try_with(|faults| {
+                        if let Some(count) = faults.get() {
+                            faults.set(Some(count + after.saturating_sub(before)));
+                        }
+                    });
                 }
             }
+        }
 
-            #[cfg(not(target_os = "none"))]
-            _ => alloc::System.dealloc(ptr, layout),
-            
-            #[cfg(target_os = "none")]
-            _ => {},
+        /// Minor plus major page faults of the calling thread
+        #[cfg(auto_allocator_no_libc)]
+        fn thread_faults() -> Option<u64> {
+            crate::raw_syscall::thread_faults()
+        }
+
+        #[cfg(all(target_os = "linux", not(auto_allocator_no_libc)))]
+        fn thread_faults() -> Option<u64> {
+            unsafe {
+                let mut usage: libc::rusage = core::mem::zeroed();
+                (libc::getrusage(libc::RUSAGE_THREAD, &mut usage) == 0)
+                    .then(|| (usage.ru_minflt + usage.ru_majflt) as u64)
+            }
+        }
+
+        #[cfg(not(target_os = "linux"))]
+        fn thread_faults() -> Option<u64> {
+            None
         }
     }
 }
 
-#[global_allocator]
-static GLOBAL: RuntimeAllocator = RuntimeAllocator;
+#[cfg(all(feature = "realtime", not(target_os = "none")))]
+pub use realtime::{
+    enable_realtime_mode, is_realtime_mode_enabled, realtime_section, RealtimeOptions,
+    RealtimeSection,
+};
 
-// ========== Logging System ==========
+// ========== System Information Collection ==========
 
 #[cfg(not(target_os = "none"))]
-static PENDING_LOG_MESSAGE: Lazy<std::sync::Mutex<Option<String>>> =
-    Lazy::new(|| std::sync::Mutex::new(None));
+fn collect_system_info() -> SystemInfo {
+    let memory_status = get_memory_status();
+    let total_memory = memory_status.snapshot.total_bytes;
+    let effective_memory_bytes = effective_memory_bytes(total_memory);
+    let (total_swap_bytes, free_swap_bytes) = get_swap_safe();
+    let detected_cores = std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1);
+    let cpu_quota_cores = cgroup_cpu_quota_cores().filter(|&quota| quota < detected_cores);
+    let cpu_cores = cpu_quota_cores.unwrap_or(detected_cores);
+    let (performance_cores, efficiency_cores) = detect_core_topology(cpu_cores);
+    SystemInfo {
+        os_type: std::env::consts::OS.to_string(),
+        cpu_cores,
+        cpu_quota_cores,
+        total_memory_bytes: total_memory,
+        effective_memory_bytes,
+        available_memory_bytes: memory_status.snapshot.available_bytes,
+        total_swap_bytes,
+        free_swap_bytes,
+        commit_limit_bytes: memory_status.commit_limit_bytes,
+        available_commit_bytes: memory_status.available_commit_bytes,
+        is_debug: cfg!(debug_assertions),
+        is_wasm: cfg!(target_arch = "wasm32"),
+        target_arch: std::env::consts::ARCH.to_string(),
+        target_triple: TARGET,
+        pointer_width: usize::BITS as u8,
+        endian: Endianness::native(),
+        cpu_features: detect_cpu_features(),
+        huge_pages_available: huge_pages_available_safe(),
+        thp_mode: thp_mode_safe(),
+        page_size: get_page_size_safe(),
+        cache_sizes: detect_cache_sizes(),
+        performance_cores,
+        efficiency_cores,
+        hypervisor: detect_hypervisor(),
+        is_pid1: is_pid1(),
+    }
+}
 
-/// Records allocator selection using a dual logging strategy
-///
-/// Immediately outputs to stderr (safe during global allocator init) and 
-/// saves for later output through the logging framework when available.
+#[cfg(all(unix, not(target_os = "none")))]
+fn is_pid1() -> bool {
+    std::process::id() == 1
+}
+
+#[cfg(not(any(unix, target_os = "none")))]
+fn is_pid1() -> bool {
+    false
+}
+
+/// Detects CPU cache sizes, leaving unreported levels as `None`
 #[cfg(not(target_os = "none"))]
-fn record_allocator_selection(allocator_name: &str, reason: &str) {
-    let message = format!("Auto-allocator: {} selected - {}", allocator_name, reason);
+fn detect_cache_sizes() -> CacheSizes {
+    #[allow(unused_mut)]
+    let mut caches = CacheSizes::default();
 
-    // Immediate output to stderr (only safe method in global allocator)
-    #[cfg(unix)]
+    #[cfg(any(target_os = "linux", target_os = "android"))]
     {
-        let stderr_message = format!("[INFO] {}\n", message);
-        unsafe {
-            libc::write(
-                2,
-                stderr_message.as_ptr() as *const libc::c_void,
-                stderr_message.len(),
-            );
+        // Each indexN describes one cache: level, type (Data/Instruction/Unified), size ("48K")
+        let parse_size = |size: &str| -> Option<u64> {
+            let size = size.trim();
+            let (digits, multiplier) = match size.as_bytes().last()? {
+                b'K' => (&size[..size.len() - 1], 1u64 << 10),
+                b'M' => (&size[..size.len() - 1], 1u64 << 20),
+                b'G' => (&size[..size.len() - 1], 1u64 << 30),
+                _ => (size, 1),
+            };
+            digits.parse::<u64>().ok().map(|value| value * multiplier)
+        };
+        let entries = std::fs::read_dir("/sys/devices/system/cpu/cpu0/cache")
+            .into_iter()
+            .flatten()
+            .filter_map(|entry| entry.ok());
+        for entry in entries {
+            let read = |name: &str| std::fs::read_to_string(entry.path().join(name)).ok();
+            let (Some(level), Some(kind), Some(size)) = (read("level"), read("type"), read("size")) else {
+                continue;
+            };
+            let size = parse_size(&size);
+            match (level.trim(), kind.trim()) {
+                ("1", "Data") | ("1", "Unified") => caches.l1d = size,
+                ("2", "Data") | ("2", "Unified") => caches.l2 = size,
+                ("3", "Data") | ("3", "Unified") => caches.l3 = size,
+                _ => {}
+            }
         }
     }
 
-    // Save message, output later through logging framework
-    if let Ok(mut pending) = PENDING_LOG_MESSAGE.lock() {
-        *pending = Some(message);
+    #[cfg(target_os = "macos")]
+    {
+        let sysctl_size = |name: &core::ffi::CStr| -> Option<u64> {
+            let mut value: u64 = 0;
+            let mut len = core::mem::size_of::<u64>();
+            let result = unsafe {
+                libc::sysctlbyname(
+                    name.as_ptr(),
+                    &mut value as *mut _ as *mut libc::c_void,
+                    &mut len,
+                    core::ptr::null_mut(),
+                    0,
+                )
+            };
+            (result == 0 && value > 0).then_some(value)
+        };
+        caches.l1d = sysctl_size(c"hw.l1dcachesize");
+        caches.l2 = sysctl_size(c"hw.l2cachesize");
+        caches.l3 = sysctl_size(c"hw.l3cachesize");
     }
-}
 
-/// Attempts to flush pending log message to the logging framework
-#[cfg(not(target_os = "none"))]
-fn try_flush_pending_log() {
-    if !LOG_FLUSHED.load(Ordering::Relaxed) {
-        if let Ok(mut pending) = PENDING_LOG_MESSAGE.lock() {
-            if let Some(message) = pending.take() {
-                let _ = std::panic::catch_unwind(|| {
-                    info!("{}", message);
-                });
-                LOG_FLUSHED.store(true, Ordering::Relaxed);
+    #[cfg(target_os = "windows")]
+    {
+        use winapi::um::sysinfoapi::GetLogicalProcessorInformation;
+        use winapi::um::winnt::{
+            CacheData, CacheUnified, RelationCache, SYSTEM_LOGICAL_PROCESSOR_INFORMATION,
+        };
+        unsafe {
+            // First call reports the required buffer length
+            let mut length = 0u32;
+            GetLogicalProcessorInformation(core::ptr::null_mut(), &mut length);
+            let count = length as usize / core::mem::size_of::<SYSTEM_LOGICAL_PROCESSOR_INFORMATION>();
+            let mut buffer: Vec<SYSTEM_LOGICAL_PROCESSOR_INFORMATION> = Vec::with_capacity(count);
+            if count > 0 && GetLogicalProcessorInformation(buffer.as_mut_ptr(), &mut length) != 0 {
+                buffer.set_len(count);
+                for info in buffer.iter().filter(|info| info.Relationship == RelationCache) {
+                    let cache = info.u.Cache();
+                    if cache.Type != CacheData && cache.Type != CacheUnified {
+                        continue;
+                    }
+                    let size = Some(cache.Size as u64);
+                    match cache.Level {
+                        1 => caches.l1d = size,
+                        2 => caches.l2 = size,
+                        3 => caches.l3 = size,
+                        _ => {}
+                    }
+                }
             }
         }
     }
+
+    caches
 }
 
-/// Intelligently flushes logs when the logging framework becomes available
+/// Splits `cpu_cores` into (performance, efficiency) cores on heterogeneous CPUs
+///
+/// Falls back to `(cpu_cores, 0)` when the topology is homogeneous or unknown.
+/// Counts are clamped to `cpu_cores`, which may be limited by affinity or cgroups.
 #[cfg(not(target_os = "none"))]
-fn smart_try_flush_log() {
-    // If already output, no need to try again
-    if LOG_FLUSHED.load(Ordering::Relaxed) {
-        return;
-    }
-
-    // Try to output log
-    try_flush_pending_log();
+fn detect_core_topology(cpu_cores: usize) -> (usize, usize) {
+    #[allow(unused_mut)]
+    let mut topology: Option<(usize, usize)> = None;
 
-    // If still not successful, logging framework is not yet initialized
-    // Will continue trying on next call
-}
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    {
+        // Per-CPU relative capacity (max 1024); the highest-capacity class is "big"
+        let capacities: Vec<u64> = std::fs::read_dir("/sys/devices/system/cpu")
+            .into_iter()
+            .flatten()
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| {
+                let name = entry.file_name();
+                let name = name.to_string_lossy();
+                name.strip_prefix("cpu")
+                    .is_some_and(|id| !id.is_empty() && id.bytes().all(|b| b.is_ascii_digit()))
+            })
+            .filter_map(|entry| std::fs::read_to_string(entry.path().join("cpu_capacity")).ok())
+            .filter_map(|capacity| capacity.trim().parse().ok())
+            .collect();
+
+        if let Some(&max) = capacities.iter().max() {
+            let big = capacities.iter().filter(|&&capacity| capacity == max).count();
+            topology = Some((big, capacities.len() - big));
+        }
+    }
 
-// ========== System Information Collection ==========
+    #[cfg(target_os = "macos")]
+    {
+        // Apple silicon: perflevel0 = performance cluster, perflevel1 = efficiency cluster
+        let sysctl_count = |name: &core::ffi::CStr| -> Option<usize> {
+            let mut value: libc::c_int = 0;
+            let mut len = core::mem::size_of::<libc::c_int>();
+            let result = unsafe {
+                libc::sysctlbyname(
+                    name.as_ptr(),
+                    &mut value as *mut _ as *mut libc::c_void,
+                    &mut len,
+                    core::ptr::null_mut(),
+                    0,
+                )
+            };
+            (result == 0 && value > 0).then_some(value as usize)
+        };
+        if let Some(performance) = sysctl_count(c"hw.perflevel0.physicalcpu") {
+            topology = Some((performance, sysctl_count(c"hw.perflevel1.physicalcpu").unwrap_or(0)));
+        }
+    }
 
-#[cfg(not(target_os = "none"))]
-fn collect_system_info() -> SystemInfo {
-    let total_memory = get_total_memory_safe();
-    SystemInfo {
-        os_type: std::env::consts::OS.to_string(),
-        cpu_cores: std::thread::available_parallelism()
-            .map(|n| n.get())
-            .unwrap_or(1),
-        total_memory_bytes: total_memory,
-        is_debug: cfg!(debug_assertions),
-        is_wasm: cfg!(target_arch = "wasm32"),
-        target_arch: std::env::consts::ARCH.to_string(),
+    match topology {
+        Some((performance, efficiency)) if efficiency > 0 => {
+            let performance = performance.min(cpu_cores);
+            (performance, efficiency.min(cpu_cores - performance))
+        }
+        _ => (cpu_cores, 0),
     }
 }
 
@@ -931,12 +6172,25 @@ fn collect_system_info() -> SystemInfo {
 #[cfg(target_os = "none")]
 fn collect_system_info() -> SystemInfo {
     let total_memory = get_total_memory_safe();
+    let cpu_cores = bare_metal_cpu_cores();
     SystemInfo {
         os_type: "embedded",
-        cpu_cores: 1, // Assume single core for embedded
+        cpu_cores,
+        cpu_quota_cores: None,
+        performance_cores: cpu_cores,
+        efficiency_cores: 0,
         total_memory_bytes: total_memory,
+        effective_memory_bytes: total_memory,
+        available_memory_bytes: 0,
+        total_swap_bytes: 0,
+        free_swap_bytes: 0,
+        commit_limit_bytes: 0,
+        available_commit_bytes: 0,
         is_debug: cfg!(debug_assertions),
         is_wasm: false,
+        target_triple: TARGET,
+        pointer_width: usize::BITS as u8,
+        endian: Endianness::native(),
         target_arch: {
             #[cfg(target_arch = "riscv32")]
             { "riscv32" }
@@ -950,17 +6204,425 @@ fn collect_system_info() -> SystemInfo {
             { "msp430" }
             #[cfg(target_arch = "xtensa")]
             { "xtensa" }
+            #[cfg(target_arch = "x86_64")]
+            { "x86_64" }
+            #[cfg(target_arch = "aarch64")]
+            { "aarch64" }
             #[cfg(not(any(
                 target_arch = "riscv32",
                 target_arch = "riscv64", 
                 target_arch = "arm",
                 target_arch = "avr",
                 target_arch = "msp430",
-                target_arch = "xtensa"
+                target_arch = "xtensa",
+                target_arch = "x86_64",
+                target_arch = "aarch64"
             )))]
             { "unknown" }
         },
+        cpu_features: detect_cpu_features(),
+        huge_pages_available: huge_pages_available_safe(),
+        thp_mode: thp_mode_safe(),
+        page_size: get_page_size_safe(),
+        cache_sizes: CacheSizes::default(),
+        hypervisor: detect_hypervisor(),
+        is_pid1: false,
+    }
+}
+
+/// Detects CPU SIMD capabilities using runtime feature detection where available
+#[cfg(not(target_os = "none"))]
+fn detect_cpu_features() -> CpuFeatures {
+    #[allow(unused_mut)]
+    let mut features = CpuFeatures::default();
+
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    {
+        features.sse2 = std::arch::is_x86_feature_detected!("sse2");
+        features.avx = std::arch::is_x86_feature_detected!("avx");
+        features.avx2 = std::arch::is_x86_feature_detected!("avx2");
+        features.avx512f = std::arch::is_x86_feature_detected!("avx512f");
+    }
+
+    #[cfg(target_arch = "aarch64")]
+    {
+        features.neon = std::arch::is_aarch64_feature_detected!("neon");
+    }
+
+    features
+}
+
+/// Detects a hypervisor from CPUID leaf 1 (ECX bit 31) and the vendor leaf 0x40000000
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+fn detect_hypervisor() -> Option<&'static str> {
+    #[cfg(target_arch = "x86")]
+    use core::arch::x86::__cpuid;
+    #[cfg(target_arch = "x86_64")]
+    use core::arch::x86_64::__cpuid;
+
+    // __cpuid is safe on newer toolchains
+    #[allow(unused_unsafe)]
+    let features = unsafe { __cpuid(1) };
+    if features.ecx & (1 << 31) == 0 {
+        return None;
+    }
+
+    #[allow(unused_unsafe)]
+    let vendor_leaf = unsafe { __cpuid(0x4000_0000) };
+    let mut signature = [0u8; 12];
+    signature[..4].copy_from_slice(&vendor_leaf.ebx.to_le_bytes());
+    signature[4..8].copy_from_slice(&vendor_leaf.ecx.to_le_bytes());
+    signature[8..].copy_from_slice(&vendor_leaf.edx.to_le_bytes());
+
+    Some(match &signature {
+        b"KVMKVMKVM\0\0\0" => "KVM",
+        b"VMwareVMware" => "VMware",
+        b"Microsoft Hv" => "Hyper-V",
+        b"XenVMMXenVMM" => "Xen",
+        b"TCGTCGTCGTCG" => "QEMU",
+        b"VBoxVBoxVBox" => "VirtualBox",
+        b" lrpepyh  vr" | b"prl hyperv  " => "Parallels",
+        b"bhyve bhyve " => "bhyve",
+        _ => "unknown",
+    })
+}
+
+#[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+fn detect_hypervisor() -> Option<&'static str> {
+    None
+}
+
+/// Core count for no_std targets, without an OS to ask
+///
+/// `AUTO_ALLOCATOR_CPU_CORES` given at build time wins. Otherwise x86_64 bare metal
+/// (kernels, unikernels) reads the logical processors per package from CPUID leaf
+/// 0xB - present, not necessarily brought online yet. Everything else reports 1:
+/// MCUs are single-core and other architectures have no portable query.
+#[cfg(target_os = "none")]
+fn bare_metal_cpu_cores() -> usize {
+    if let Some(cores) = option_env!("AUTO_ALLOCATOR_CPU_CORES") {
+        return parse_build_setting(cores);
     }
+
+    #[cfg(target_arch = "x86_64")]
+    {
+        use core::arch::x86_64::{__cpuid, __cpuid_count};
+
+        // __cpuid is safe on newer toolchains
+        #[allow(unused_unsafe)]
+        let max_leaf = unsafe { __cpuid(0) }.eax;
+        if max_leaf >= 0xB {
+            // Subleaf 1 is the core level: EBX[15:0] counts logical processors below it
+            #[allow(unused_unsafe)]
+            let logical = unsafe { __cpuid_count(0xB, 1) }.ebx & 0xFFFF;
+            if logical > 0 {
+                return logical as usize;
+            }
+        }
+    }
+
+    1
+}
+
+/// Compile-time CPU feature flags for no_std environments (no runtime detection available)
+#[cfg(target_os = "none")]
+fn detect_cpu_features() -> CpuFeatures {
+    CpuFeatures {
+        sse2: cfg!(target_feature = "sse2"),
+        avx: cfg!(target_feature = "avx"),
+        avx2: cfg!(target_feature = "avx2"),
+        avx512f: cfg!(target_feature = "avx512f"),
+        neon: cfg!(target_feature = "neon"),
+    }
+}
+
+/// Detects (total, free) swap space in bytes without allocating, `(0, 0)` if unknown
+#[cfg(not(target_os = "none"))]
+fn get_swap_safe() -> (u64, u64) {
+    #[cfg(auto_allocator_no_libc)]
+    if let Some(swap) = raw_syscall::swap() {
+        return swap;
+    }
+
+    #[cfg(all(target_os = "linux", not(target_arch = "wasm32"), not(auto_allocator_no_libc)))]
+    unsafe {
+        let mut info: libc::sysinfo = core::mem::zeroed();
+        if libc::sysinfo(&mut info) == 0 {
+            let unit = info.mem_unit as u64;
+            return (info.totalswap as u64 * unit, info.freeswap as u64 * unit);
+        }
+    }
+
+    #[cfg(target_os = "macos")]
+    unsafe {
+        let mut usage: libc::xsw_usage = core::mem::zeroed();
+        let mut len = core::mem::size_of::<libc::xsw_usage>();
+        if libc::sysctlbyname(
+            c"vm.swapusage".as_ptr(),
+            &mut usage as *mut _ as *mut libc::c_void,
+            &mut len,
+            core::ptr::null_mut(),
+            0,
+        ) == 0
+        {
+            return (usage.xsu_total, usage.xsu_avail);
+        }
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        use winapi::um::sysinfoapi::{GlobalMemoryStatusEx, MEMORYSTATUSEX};
+        unsafe {
+            let mut mem_status: MEMORYSTATUSEX = core::mem::zeroed();
+            mem_status.dwLength = core::mem::size_of::<MEMORYSTATUSEX>() as u32;
+            if GlobalMemoryStatusEx(&mut mem_status) != 0 {
+                // The page-file limit includes physical memory; the excess is swap
+                return (
+                    mem_status.ullTotalPageFile.saturating_sub(mem_status.ullTotalPhys),
+                    mem_status.ullAvailPageFile.saturating_sub(mem_status.ullAvailPhys),
+                );
+            }
+        }
+    }
+
+    #[allow(unreachable_code)]
+    (0, 0)
+}
+
+/// Memory snapshot plus commit charge; fields are `0` when unknown
+#[cfg(not(target_os = "none"))]
+#[derive(Default)]
+struct MemoryStatus {
+    snapshot: MemorySnapshot,
+    commit_limit_bytes: u64,
+    available_commit_bytes: u64,
+}
+
+/// Reads current memory totals and availability for [`SystemInfo`] (may allocate, not for the selection path)
+#[cfg(not(target_os = "none"))]
+fn get_memory_status() -> MemoryStatus {
+    #[allow(unused_mut)]
+    let mut status = MemoryStatus::default();
+
+    #[cfg(all(target_os = "linux", not(target_arch = "wasm32")))]
+    {
+        // MemTotal and MemAvailable from a single read of the file
+        if let Ok(meminfo) = std::fs::read_to_string("/proc/meminfo") {
+            let field = |name: &str| {
+                let line = meminfo.lines().find(|line| line.starts_with(name))?;
+                let kb = line[name.len()..].trim().trim_end_matches("kB").trim();
+                kb.parse::<u64>().ok().map(|kb| kb * 1024)
+            };
+            status.snapshot.total_bytes = field("MemTotal:").unwrap_or(0);
+            status.snapshot.available_bytes = field("MemAvailable:").unwrap_or(0);
+        }
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        // Inactive pages are reclaimable without paging, like Linux's MemAvailable
+        #[allow(deprecated)] // libc points to the mach2 crate, but this one call doesn't justify a dependency
+        unsafe {
+            let mut vm_stat: libc::vm_statistics64 = core::mem::zeroed();
+            let mut count = libc::HOST_VM_INFO64_COUNT;
+            let result = libc::host_statistics64(
+                libc::mach_host_self(),
+                libc::HOST_VM_INFO64,
+                &mut vm_stat as *mut _ as libc::host_info64_t,
+                &mut count,
+            );
+            if result == libc::KERN_SUCCESS {
+                let pages = vm_stat.free_count as u64 + vm_stat.inactive_count as u64;
+                status.snapshot.available_bytes = pages * get_page_size_safe() as u64;
+            }
+        }
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        use winapi::um::sysinfoapi::{GlobalMemoryStatusEx, MEMORYSTATUSEX};
+        unsafe {
+            let mut mem_status: MEMORYSTATUSEX = core::mem::zeroed();
+            mem_status.dwLength = core::mem::size_of::<MEMORYSTATUSEX>() as u32;
+            if GlobalMemoryStatusEx(&mut mem_status) != 0 {
+                status.snapshot.total_bytes = mem_status.ullTotalPhys;
+                status.snapshot.available_bytes = mem_status.ullAvailPhys;
+                status.commit_limit_bytes = mem_status.ullTotalPageFile;
+                status.available_commit_bytes = mem_status.ullAvailPageFile;
+            }
+        }
+    }
+
+    if let Some(total) = total_memory_override() {
+        status.snapshot.total_bytes = total;
+    } else if status.snapshot.total_bytes == 0 {
+        // Platforms without a combined call (macOS: the fixed HW_MEMSIZE) or where it failed
+        status.snapshot.total_bytes = get_total_memory_safe();
+    }
+    status.snapshot.available_bytes = status.snapshot.available_bytes.min(status.snapshot.total_bytes);
+    status
+}
+
+/// Detects the OS page size without allocating (safe inside the global allocator)
+fn get_page_size_safe() -> usize {
+    // Fallback: 4KB is the smallest common MMU page size
+    const DEFAULT_PAGE_SIZE: usize = 4096;
+
+    #[cfg(target_arch = "wasm32")]
+    {
+        // WASM linear memory grows in fixed 64KB pages
+        return 65536;
+    }
+
+    #[cfg(auto_allocator_no_libc)]
+    if let Some(size) = raw_syscall::page_size() {
+        return size;
+    }
+
+    #[cfg(all(unix, not(target_arch = "wasm32"), not(auto_allocator_no_libc)))]
+    {
+        let size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) };
+        if size > 0 {
+            return size as usize;
+        }
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        use winapi::um::sysinfoapi::{GetSystemInfo, SYSTEM_INFO};
+        unsafe {
+            let mut info: SYSTEM_INFO = core::mem::zeroed();
+            GetSystemInfo(&mut info);
+            if info.dwPageSize > 0 {
+                return info.dwPageSize as usize;
+            }
+        }
+    }
+
+    #[allow(unreachable_code)]
+    DEFAULT_PAGE_SIZE
+}
+
+/// Reads a small file into `buf` without allocating (safe inside the global allocator)
+///
+/// Returns the bytes read, or `None` if the file can't be opened or is empty.
+#[cfg(all(target_os = "linux", not(target_arch = "wasm32")))]
+fn read_file_no_alloc<'a>(path: &core::ffi::CStr, buf: &'a mut [u8]) -> Option<&'a [u8]> {
+    #[cfg(auto_allocator_no_libc)]
+    let len = raw_syscall::read_file(path, buf)? as isize;
+    #[cfg(not(auto_allocator_no_libc))]
+    let len = unsafe {
+        let fd = libc::open(path.as_ptr(), libc::O_RDONLY | libc::O_CLOEXEC);
+        if fd < 0 {
+            return None;
+        }
+        let len = libc::read(fd, buf.as_mut_ptr() as *mut libc::c_void, buf.len());
+        libc::close(fd);
+        len
+    };
+    (len > 0).then(|| &buf[..len as usize])
+}
+
+/// Cores allowed by the cgroup CPU quota, as `ceil(quota / period)`, without allocating
+///
+/// Reads cgroup v2 `cpu.max`, then cgroup v1 `cpu.cfs_quota_us` / `cpu.cfs_period_us`,
+/// from the process's cgroup namespace root. `None` when unlimited or unavailable.
+#[cfg(all(target_os = "linux", not(target_arch = "wasm32")))]
+fn cgroup_cpu_quota_cores() -> Option<usize> {
+    fn parse_u64(bytes: &[u8]) -> Option<u64> {
+        core::str::from_utf8(bytes).ok()?.trim().parse().ok()
+    }
+    fn quota_cores(quota: u64, period: u64) -> Option<usize> {
+        (quota > 0 && period > 0).then(|| quota.div_ceil(period).max(1) as usize)
+    }
+
+    let mut buf = [0u8; 64];
+
+    // cgroup v2: "<quota> <period>" or "max <period>"
+    if let Some(cpu_max) = read_file_no_alloc(c"/sys/fs/cgroup/cpu.max", &mut buf) {
+        let mut fields = cpu_max.split(|b| b.is_ascii_whitespace());
+        let quota = fields.next().and_then(parse_u64)?;
+        let period = fields.next().and_then(parse_u64)?;
+        return quota_cores(quota, period);
+    }
+
+    // cgroup v1: quota is -1 when unlimited, which fails the u64 parse
+    let quota = read_file_no_alloc(c"/sys/fs/cgroup/cpu/cpu.cfs_quota_us", &mut buf).and_then(parse_u64)?;
+    let period = read_file_no_alloc(c"/sys/fs/cgroup/cpu/cpu.cfs_period_us", &mut buf).and_then(parse_u64)?;
+    quota_cores(quota, period)
+}
+
+#[cfg(not(any(all(target_os = "linux", not(target_arch = "wasm32")), target_os = "none")))]
+fn cgroup_cpu_quota_cores() -> Option<usize> {
+    None
+}
+
+/// Memory limit of the process's cgroup in bytes, without allocating
+///
+/// Reads cgroup v2 `memory.max`, then cgroup v1 `memory.limit_in_bytes`.
+/// `None` when unlimited ("max", or v1's near-`i64::MAX` sentinel) or unavailable.
+#[cfg(all(target_os = "linux", not(target_arch = "wasm32")))]
+fn cgroup_memory_limit_bytes() -> Option<u64> {
+    fn parse_u64(bytes: &[u8]) -> Option<u64> {
+        core::str::from_utf8(bytes).ok()?.trim().parse().ok()
+    }
+
+    let mut buf = [0u8; 64];
+
+    // cgroup v2: "<bytes>" or "max", which fails the parse
+    if let Some(memory_max) = read_file_no_alloc(c"/sys/fs/cgroup/memory.max", &mut buf) {
+        return parse_u64(memory_max);
+    }
+
+    // cgroup v1: unlimited is reported as a page-aligned i64::MAX, larger than any real RAM
+    read_file_no_alloc(c"/sys/fs/cgroup/memory/memory.limit_in_bytes", &mut buf)
+        .and_then(parse_u64)
+        .filter(|&limit| limit < 1u64 << 62)
+}
+
+#[cfg(not(all(target_os = "linux", not(target_arch = "wasm32"))))]
+fn cgroup_memory_limit_bytes() -> Option<u64> {
+    None
+}
+
+/// Physical memory capped by the cgroup memory limit, unless `AUTO_ALLOCATOR_TOTAL_MEMORY` set it
+fn effective_memory_bytes(total_memory_bytes: u64) -> u64 {
+    if total_memory_override().is_some() {
+        return total_memory_bytes;
+    }
+    cgroup_memory_limit_bytes().map_or(total_memory_bytes, |limit| limit.min(total_memory_bytes))
+}
+
+/// Memory available to the process, without allocating (safe inside the global allocator)
+fn get_effective_memory_safe() -> u64 {
+    effective_memory_bytes(get_total_memory_safe())
+}
+
+/// Checks transparent huge page support without allocating (safe inside the global allocator)
+#[cfg(all(target_os = "linux", not(target_arch = "wasm32")))]
+fn thp_mode_safe() -> Option<ThpMode> {
+    let mut buf = [0u8; 64];
+    // The active mode is bracketed: "always [madvise] never"
+    let modes = read_file_no_alloc(c"/sys/kernel/mm/transparent_hugepage/enabled", &mut buf)?;
+    let active = |mode: &[u8]| modes.windows(mode.len()).any(|window| window == mode);
+    if active(b"[always]") {
+        Some(ThpMode::Always)
+    } else if active(b"[madvise]") {
+        Some(ThpMode::Madvise)
+    } else if active(b"[never]") {
+        Some(ThpMode::Never)
+    } else {
+        None
+    }
+}
+
+#[cfg(not(all(target_os = "linux", not(target_arch = "wasm32"))))]
+fn thp_mode_safe() -> Option<ThpMode> {
+    None
+}
+
+fn huge_pages_available_safe() -> bool {
+    matches!(thp_mode_safe(), Some(ThpMode::Always | ThpMode::Madvise))
 }
 
 /// Detects total system memory without allocating during global allocator initialization
@@ -969,6 +6631,10 @@ fn collect_system_info() -> SystemInfo {
 /// Critical: This function must not allocate memory as it's called during global allocator setup.
 #[allow(unreachable_code)]
 fn get_total_memory_safe() -> u64 {
+    if let Some(total) = total_memory_override() {
+        return total;
+    }
+
     #[cfg(target_arch = "wasm32")]
     {
         // WASM can dynamically detect memory through core::arch::wasm32
@@ -1008,6 +6674,12 @@ fn get_total_memory_safe() -> u64 {
     #[cfg(all(target_os = "linux", not(target_arch = "wasm32")))]
     {
         // Linux: use sysinfo() system call
+        #[cfg(auto_allocator_no_libc)]
+        if let Some(total) = raw_syscall::total_memory() {
+            return total;
+        }
+
+        #[cfg(not(auto_allocator_no_libc))]
         unsafe {
             let mut info: libc::sysinfo = std::mem::zeroed();
             if libc::sysinfo(&mut info) == 0 {
@@ -1053,325 +6725,1254 @@ fn get_total_memory_safe() -> u64 {
 
     #[cfg(target_arch = "xtensa")]
     {
-        return 256u64 << 10; // 256KB for Xtensa (like ESP32 with up to 520KB)
+        // 256KB for Xtensa (like ESP32 with up to 520KB), plus PSRAM configured at build time
+        #[cfg(all(target_os = "none", feature = "_embedded", feature = "esp-psram"))]
+        return (256u64 << 10) + embedded_heap_config::psram::BUILD_SIZE as u64;
+        #[cfg(not(all(target_os = "none", feature = "_embedded", feature = "esp-psram")))]
+        return 256u64 << 10;
+    }
+
+    #[cfg(all(target_arch = "arm", target_os = "none"))]
+    {
+        return 16u64 << 10; // 16KB for ARM Cortex-M (conservative estimate, M0+ typically has this capacity)
+    }
+
+    // Default for unknown platforms
+    2u64 << 30
+}
+
+// No_std (and `log`-less) versions of log functions
+#[cfg(any(target_os = "none", not(feature = "log"), feature = "no-log"))]
+fn smart_try_flush_log() {
+    // No logging in no_std or with `no-log`, stderr only without the `log` feature
+}
+
+// ========== Runtime Allocator Information ==========
+
+#[cfg(not(target_os = "none"))]
+static ALLOCATOR_INFO: LazyLock<AllocatorInfo> = LazyLock::new(|| {
+    let system_info = collect_system_info();
+    let allocator_id = RUNTIME_ALLOCATOR_ID.load(Ordering::Acquire);
+
+    // If not yet initialized, trigger allocator selection once
+    let final_allocator_id = if allocator_id == 0 {
+        RuntimeAllocator::get_allocator_id()
+    } else {
+        allocator_id
+    };
+
+    // Determine type based on actually selected allocator ID (may differ due to feature disable)
+    let allocator_type = AllocatorType::from_id(final_allocator_id).unwrap_or(AllocatorType::System);
+
+    let reason_detail = selection_reason_for_id(final_allocator_id, &system_info);
+    // `no-log` skips the formatting: a fixed summary without the detected values
+    #[cfg(not(feature = "no-log"))]
+    let mut reason = reason_detail.to_string();
+    #[cfg(feature = "no-log")]
+    let mut reason = String::from(reason_detail.summary());
+    if !global_allocator_is_auto() {
+        reason.push_str(" [warning: auto-allocator is not the global allocator (`no-global` feature), this selection is not in effect]");
+    }
+
+    AllocatorInfo {
+        allocator_type,
+        reason,
+        reason_detail,
+        system_info,
+    }
+});
+
+// Simplified allocator info for no_std
+#[cfg(target_os = "none")]
+static mut EMBEDDED_ALLOCATOR_INFO: Option<AllocatorInfo> = None;
+
+// ========== Public API ==========
+
+/// Ensure allocator information is ready
+/// Internal function, ensures ALLOCATOR_INFO has been computed
+#[cfg(not(target_os = "none"))]
+fn ensure_allocator_info_ready() {
+    let _ = std::panic::catch_unwind(|| {
+        LazyLock::force(&ALLOCATOR_INFO);
+    });
+}
+
+// `eager-init`: select (and log) from a constructor, so the cost lands at startup rather
+// than on whichever thread happens to allocate first
+#[cfg(all(feature = "eager-init", not(target_os = "none")))]
+#[ctor::ctor(unsafe)]
+fn eager_init_allocator() {
+    ensure_allocator_info_ready();
+}
+
+#[cfg(target_os = "none")]
+fn ensure_allocator_info_ready() {
+    unsafe {
+        if EMBEDDED_ALLOCATOR_INFO.is_none() {
+            let system_info = collect_system_info();
+            if RuntimeAllocator::get_allocator_id() == 6 {
+                EMBEDDED_ALLOCATOR_INFO = Some(AllocatorInfo {
+                    allocator_type: AllocatorType::Custom,
+                    reason: "custom allocator - registered via register_custom_allocator()",
+                    reason_detail: SelectionReason::Custom,
+                    system_info,
+                });
+                return;
+            }
+            EMBEDDED_ALLOCATOR_INFO = Some(AllocatorInfo {
+                allocator_type: AllocatorType::EmbeddedHeap,
+                reason: if global_allocator_is_auto() {
+                    "embedded-alloc selected for no_std environment"
+                } else {
+                    "embedded-alloc selected for no_std environment [warning: not the global allocator (`no-global` feature)]"
+                },
+                reason_detail: SelectionReason::Embedded,
+                system_info,
+            });
+        }
+    }
+}
+
+/// Returns information about the automatically selected allocator
+///
+/// Provides allocator type, selection rationale, and system information.
+/// First call triggers hardware detection; subsequent calls return cached results.
+/// With the `log` feature, also forwards the pending selection message once a logger
+/// is installed - [`get_allocator_info_quiet()`] skips that.
+///
+/// # Example
+///
+/// ```rust
+/// use auto_allocator;
+///
+/// let info = auto_allocator::get_allocator_info();
+/// println!("Using: {:?}", info.allocator_type);
+/// println!("Reason: {}", info.reason);
+/// ```
+pub fn get_allocator_info() -> &'static AllocatorInfo {
+    smart_try_flush_log();
+    get_allocator_info_quiet()
+}
+
+/// Same as [`get_allocator_info()`], without the side effect of flushing the selection message
+///
+/// [`get_allocator_info()`] forwards the pending selection message to the `log` crate
+/// the first time it sees a logger. This variant never touches the logging framework,
+/// so it is safe inside a logger's own setup and cheap in tight loops; the message
+/// stays pending for the next [`get_allocator_info()`] / [`get_allocator_type()`] call.
+///
+/// # Example
+///
+/// ```rust
+/// // e.g. while configuring a logger, before it is installed
+/// let info = auto_allocator::get_allocator_info_quiet();
+/// let _prefix = format!("[{:?}]", info.allocator_type);
+/// ```
+#[cfg(not(target_os = "none"))]
+pub fn get_allocator_info_quiet() -> &'static AllocatorInfo {
+    ensure_allocator_info_ready();
+    #[cfg(all(feature = "pressure", target_os = "linux"))]
+    pressure::ensure_monitor_started();
+    fire_selection_callback(&ALLOCATOR_INFO);
+    &ALLOCATOR_INFO
+}
+
+#[cfg(target_os = "none")]
+pub fn get_allocator_info_quiet() -> &'static AllocatorInfo {
+    ensure_allocator_info_ready();
+    let info = unsafe { EMBEDDED_ALLOCATOR_INFO.as_ref().unwrap() };
+    fire_selection_callback(info);
+    info
+}
+
+/// Get current allocator type
+///
+/// Returns the currently used allocator type, this is a simplified version of [`get_allocator_info()`].
+/// If you only need to know the allocator type without other information, using this function is more concise.
+///
+/// # Return Value
+///
+/// Returns [`AllocatorType`] enum value, possible values:
+/// - [`AllocatorType::Mimalloc`] - Microsoft-developed high-performance allocator
+/// - [`AllocatorType::EmbeddedHeap`] - Embedded systems specific allocator
+/// - [`AllocatorType::System`] - System default allocator
+/// - [`AllocatorType::Custom`] - Allocator registered with [`register_custom_allocator()`]
+///
+/// # Example
+///
+/// ```rust
+/// use auto_allocator;
+///
+/// let allocator_type = auto_allocator::get_allocator_type();
+///
+/// // Simple allocator type check
+/// if allocator_type == auto_allocator::AllocatorType::Mimalloc {
+///     println!("Using high-performance mimalloc allocator");
+/// }
+///
+/// // Or use match statement
+/// match allocator_type {
+///     auto_allocator::AllocatorType::Mimalloc => {
+///         println!("mimalloc - optimal performance");
+///     }
+///     auto_allocator::AllocatorType::System => {
+///         println!("system - maximum compatibility");
+///     }
+///     _ => println!("other allocator"),
+/// }
+/// ```
+///
+/// # Performance Notes
+///
+/// This function is slightly faster than [`get_allocator_info()`] because it only returns type information.
+pub fn get_allocator_type() -> AllocatorType {
+    get_allocator_info().allocator_type
+}
+
+/// Same as [`get_allocator_type()`], without flushing the selection message to `log`
+///
+/// See [`get_allocator_info_quiet()`].
+pub fn get_allocator_type_quiet() -> AllocatorType {
+    get_allocator_info_quiet().allocator_type
+}
+
+/// Reads total and available physical memory as one coherent pair
+///
+/// Unlike [`SystemInfo`], which is captured once, this reads the current values on
+/// every call - for dashboards that poll memory, where reading the two from separate
+/// calls could momentarily show more available than total memory.
+/// On no_std targets `total_bytes` is the same estimate as `SystemInfo` and
+/// `available_bytes` is `0`.
+///
+/// # Example
+///
+/// ```rust
+/// let memory = auto_allocator::memory_snapshot();
+/// assert!(memory.available_bytes <= memory.total_bytes);
+/// println!("{} of {} bytes available", memory.available_bytes, memory.total_bytes);
+/// ```
+#[cfg(not(target_os = "none"))]
+pub fn memory_snapshot() -> MemorySnapshot {
+    get_memory_status().snapshot
+}
+
+#[cfg(target_os = "none")]
+pub fn memory_snapshot() -> MemorySnapshot {
+    MemorySnapshot {
+        total_bytes: get_total_memory_safe(),
+        available_bytes: 0,
+    }
+}
+
+// 0 = no callback, SELECTION_CALLBACK_FIRED once it has run, otherwise a
+// `fn(AllocatorType, &SystemInfo)` waiting to run
+static SELECTION_CALLBACK: AtomicUsize = AtomicUsize::new(0);
+const SELECTION_CALLBACK_FIRED: usize = 1;
+
+/// Registers a callback that runs exactly once with the selected allocator
+///
+/// For wiring metrics or logging to the selection result without polling
+/// [`get_allocator_info()`]. The callback never runs inside the global allocator:
+/// selection itself happens on the first allocation, and the callback is deferred
+/// to a normal call context, so it may allocate, lock and log freely.
+///
+/// It runs on the calling thread:
+/// - right here, if selection has already happened - the usual case in std
+///   binaries, whose runtime allocates before `main`
+/// - otherwise on the first [`get_allocator_info()`] family call (including
+///   [`get_allocator_type()`] and the `_quiet` variants) after selection
+///
+/// A later call replaces a callback that hasn't run yet. Returns `false`, without
+/// registering anything, once a callback has already run.
+///
+/// # Example
+///
+/// ```rust
+/// use auto_allocator::{AllocatorType, SystemInfo};
+///
+/// fn report(allocator: AllocatorType, system: &SystemInfo) {
+///     println!("allocator={:?} cores={}", allocator, system.cpu_cores);
+/// }
+///
+/// auto_allocator::on_allocator_selected(report);
+/// ```
+pub fn on_allocator_selected(callback: fn(AllocatorType, &SystemInfo)) -> bool {
+    let mut current = SELECTION_CALLBACK.load(Ordering::Acquire);
+    loop {
+        if current == SELECTION_CALLBACK_FIRED {
+            return false;
+        }
+        match SELECTION_CALLBACK.compare_exchange_weak(
+            current,
+            callback as usize,
+            Ordering::AcqRel,
+            Ordering::Acquire,
+        ) {
+            Ok(_) => break,
+            Err(actual) => current = actual,
+        }
+    }
+
+    if RUNTIME_ALLOCATOR_ID.load(Ordering::Acquire) != 0 {
+        get_allocator_info_quiet();
+    }
+    true
+}
+
+/// Runs the pending selection callback, if there is one; claims it first so it runs once
+fn fire_selection_callback(info: &AllocatorInfo) {
+    let mut current = SELECTION_CALLBACK.load(Ordering::Acquire);
+    while current != 0 && current != SELECTION_CALLBACK_FIRED {
+        match SELECTION_CALLBACK.compare_exchange_weak(
+            current,
+            SELECTION_CALLBACK_FIRED,
+            Ordering::AcqRel,
+            Ordering::Acquire,
+        ) {
+            Ok(_) => {
+                let callback: fn(AllocatorType, &SystemInfo) =
+                    unsafe { core::mem::transmute(current) };
+                callback(info.allocator_type, &info.system_info);
+                return;
+            }
+            Err(actual) => current = actual,
+        }
+    }
+}
+
+/// Target triple this crate was compiled for, as cargo passed it to `build.rs`
+///
+/// Exactly the `--target` name - "x86_64-unknown-linux-musl", "aarch64-apple-darwin",
+/// "thumbv7em-none-eabihf" - rather than a reconstruction from `cfg!` flags, which can't
+/// tell custom target JSON files or vendors apart. Also reported as
+/// [`SystemInfo::target_triple`].
+///
+/// # Example
+///
+/// ```rust
+/// println!("auto-allocator built for {}", auto_allocator::TARGET);
+/// ```
+pub const TARGET: &str = env!("AUTO_ALLOCATOR_TARGET");
+
+/// Returns whether auto-allocator is installed as the process's `#[global_allocator]`
+///
+/// Rust links exactly one global allocator: without the `no-global` feature, a second
+/// `#[global_allocator]` in the dependency graph fails to link ("the `#[global_allocator]`
+/// in ... conflicts with global allocator in: auto_allocator"), so a running program
+/// always allocates through auto-allocator. With `no-global`, the downstream crate's
+/// allocator is in effect, this returns `false`, and [`AllocatorInfo::reason`] carries a warning.
+///
+/// # Example
+///
+/// ```rust
+/// if !auto_allocator::global_allocator_is_auto() {
+///     eprintln!("auto-allocator is linked, but another global allocator is active");
+/// }
+/// ```
+pub const fn global_allocator_is_auto() -> bool {
+    !cfg!(feature = "no-global")
+}
+
+// Every feature in Cargo.toml order; the `_` backends are reported by the allocator they
+// pull in, since `default` / `secure` only show up through them
+const FEATURE_FLAGS: [(&str, bool); 28] = [
+    ("mimalloc", cfg!(feature = "_mimalloc")),
+    ("mimalloc-secure", cfg!(feature = "_mimalloc_secure")),
+    ("embedded-alloc", cfg!(feature = "_embedded")),
+    ("log", cfg!(feature = "log")),
+    ("secure", cfg!(feature = "secure")),
+    ("android-mimalloc", cfg!(feature = "android-mimalloc")),
+    ("bsd-mimalloc", cfg!(feature = "bsd-mimalloc")),
+    ("stats", cfg!(feature = "stats")),
+    ("profiling", cfg!(feature = "profiling")),
+    ("pressure", cfg!(feature = "pressure")),
+    ("bump", cfg!(feature = "bump")),
+    ("eager-init", cfg!(feature = "eager-init")),
+    ("poison-alloc", cfg!(feature = "poison-alloc")),
+    ("allow-single-core-mimalloc", cfg!(feature = "allow-single-core-mimalloc")),
+    ("esp-psram", cfg!(feature = "esp-psram")),
+    ("testing", cfg!(feature = "testing")),
+    ("no-libc", cfg!(feature = "no-libc")),
+    ("embedded-pool", cfg!(feature = "embedded-pool")),
+    ("region", cfg!(feature = "region")),
+    ("mimalloc-options", cfg!(feature = "mimalloc-options")),
+    ("mimalloc-stats", cfg!(feature = "mimalloc-stats")),
+    ("no-global", cfg!(feature = "no-global")),
+    ("huge-pages", cfg!(feature = "huge-pages")),
+    ("realtime", cfg!(feature = "realtime")),
+    ("no-mimalloc-destructor", cfg!(feature = "no-mimalloc-destructor")),
+    ("manual-embedded-init", cfg!(feature = "manual-embedded-init")),
+    ("trace-all", cfg!(feature = "trace-all")),
+    ("no-log", cfg!(feature = "no-log")),
+];
+
+const ENABLED_FEATURE_COUNT: usize = {
+    let mut count = 0;
+    let mut i = 0;
+    while i < FEATURE_FLAGS.len() {
+        if FEATURE_FLAGS[i].1 {
+            count += 1;
+        }
+        i += 1;
+    }
+    count
+};
+
+static ENABLED_FEATURES: [&str; ENABLED_FEATURE_COUNT] = {
+    let mut names = [""; ENABLED_FEATURE_COUNT];
+    let mut count = 0;
+    let mut i = 0;
+    while i < FEATURE_FLAGS.len() {
+        if FEATURE_FLAGS[i].1 {
+            names[count] = FEATURE_FLAGS[i].0;
+            count += 1;
+        }
+        i += 1;
+    }
+    names
+};
+
+/// Returns the cargo features this build of auto-allocator was compiled with
+///
+/// For bug reports and sanity checks: "mimalloc isn't being used" is often a missing
+/// feature rather than a selection decision. The list is computed at compile time,
+/// in `Cargo.toml` order. The allocator backends appear as `mimalloc` (default
+/// features), `mimalloc-secure` (`secure`) and `embedded-alloc`.
+/// [`AllocatorInfo::reason`] explains the decision itself.
+///
+/// # Example
+///
+/// ```rust
+/// let features = auto_allocator::enabled_features();
+/// if !features.contains(&"mimalloc") {
+///     println!("built without mimalloc (default features disabled?)");
+/// }
+/// println!("auto-allocator features: {}", features.join(", "));
+/// ```
+pub fn enabled_features() -> &'static [&'static str] {
+    &ENABLED_FEATURES
+}
+
+/// Returns the stable numeric ID of the active allocator
+///
+/// The mapping is part of the public API and will not change:
+///
+/// | ID | Allocator |
+/// |----|-----------|
+/// | `1` | system |
+/// | `2` | mimalloc |
+/// | `3` | reserved (jemalloc) |
+/// | `4` | embedded-alloc |
+/// | `5` | mimalloc-secure |
+/// | `6` | custom ([`register_custom_allocator()`]) |
+///
+/// `0` (not yet selected) is never returned - calling this performs selection if needed.
+/// Allocation-free and available in no_std, so it suits FFI and early logging.
+/// [`AllocatorType::from_id()`] maps the ID back to a type.
+///
+/// # Example
+///
+/// ```rust
+/// let id = auto_allocator::allocator_id();
+/// println!("allocator #{} ({})", id, auto_allocator::allocator_name());
+/// ```
+pub fn allocator_id() -> u8 {
+    RuntimeAllocator::get_allocator_id()
+}
+
+/// Returns the canonical short name of the active allocator
+///
+/// One of `"system"`, `"mimalloc"`, `"mimalloc-secure"`, `"embedded-alloc"` or `"custom"` - the
+/// names used in the selection log. Allocation-free and available in no_std.
+pub fn allocator_name() -> &'static str {
+    allocator_name_for_id(allocator_id())
+}
+
+/// Returns whether mimalloc's secure mode is actually in effect
+///
+/// `true` only when the runtime selection is mimalloc-secure (ID `5`) and
+/// auto-allocator is the global allocator. Enabling the `secure` feature asks for
+/// it, but debug builds, WASM, platforms without mimalloc, low memory, the deny
+/// list or a preference order can all end up with another allocator - assert this
+/// at startup instead of trusting the feature flag. Allocation-free and available in no_std.
+///
+/// # Example
+///
+/// ```rust
+/// if !auto_allocator::is_secure_mode_active() {
+///     eprintln!("heap hardening is off: {}", auto_allocator::get_allocator_info().reason);
+/// }
+/// ```
+pub fn is_secure_mode_active() -> bool {
+    global_allocator_is_auto() && allocator_id() == 5
+}
+
+/// Returns whether the system allocator is in use only because this is a debug build
+///
+/// `true` exactly when the active selection is the system allocator for the
+/// [`SelectionReason::Debug`] rule - not for platform policy, low memory or a
+/// fallback, and not on WASM or embedded targets, whose rules come first. A release
+/// build of the same code may pick mimalloc, so this is the precise condition for a
+/// "mimalloc is disabled in debug builds" startup warning. Reads build settings and
+/// the selected ID only: allocation-free, no hardware detection, available in no_std.
+///
+/// # Example
+///
+/// ```rust
+/// if auto_allocator::forced_system_by_debug() {
+///     eprintln!("note: debug build - using the system allocator, build with --release for mimalloc");
+/// }
+/// ```
+pub fn forced_system_by_debug() -> bool {
+    cfg!(debug_assertions)
+        && !is_embedded_target()
+        && !cfg!(target_arch = "wasm32")
+        && allocator_id() == 1
+}
+
+/// Returns why the active selection fell back to the system allocator, if it did
+///
+/// `Some` only when a platform that picks its allocator at runtime ended up on the
+/// system allocator because something was missing - cores, memory, mimalloc in the
+/// build, or a working mimalloc. Shorthand for
+/// `get_allocator_info().reason_detail.fallback_reason()`; see [`FallbackReason`].
+pub fn fallback_reason() -> Option<FallbackReason> {
+    get_allocator_info().reason_detail.fallback_reason()
+}
+
+/// Returns how long the first allocation spent selecting the allocator
+///
+/// Covers hardware detection, the deny list, backend tuning and the selection log,
+/// measured with a monotonic clock on the thread that won the selection. Use it to
+/// weigh the cold-start cost against targets whose allocator is fixed at compile
+/// time, where detection is skipped entirely.
+///
+/// `None` until the first allocation has finished selecting, when
+/// [`register_custom_allocator()`] bypassed selection, and on no_std and
+/// `wasm32-unknown-unknown`, which have no clock.
+///
+/// # Example
+///
+/// ```rust
+/// if let Some(elapsed) = auto_allocator::selection_duration() {
+///     println!("allocator selection took {:?}", elapsed);
+/// }
+/// ```
+pub fn selection_duration() -> Option<core::time::Duration> {
+    #[cfg(not(any(target_os = "none", all(target_arch = "wasm32", target_os = "unknown"))))]
+    {
+        SELECTION_DURATION.get().copied()
     }
 
-    #[cfg(all(target_arch = "arm", target_os = "none"))]
+    #[cfg(any(target_os = "none", all(target_arch = "wasm32", target_os = "unknown")))]
     {
-        return 16u64 << 10; // 16KB for ARM Cortex-M (conservative estimate, M0+ typically has this capacity)
+        None
     }
+}
 
-    // Default for unknown platforms
-    2u64 << 30
+/// Returns the OS name if this platform's native allocator is preferred by policy
+#[cfg(not(target_os = "none"))]
+fn native_allocator_platform(os_type: &str) -> Option<&'static str> {
+    ["android", "ios", "freebsd", "netbsd", "openbsd", "solaris", "illumos", "nto", "haiku"]
+        .into_iter()
+        .find(|os| *os == os_type)
 }
 
-// No_std versions of log functions
-#[cfg(target_os = "none")]
-fn smart_try_flush_log() {
-    // No logging in no_std
+/// Whether `malloc` resolves to libumem (linked with `-lumem` or preloaded) rather than libc
+///
+/// Solaris and illumos ship libumem, but processes only use it when it is linked in;
+/// otherwise the default libc malloc is in effect. When `dladdr` cannot name the object
+/// providing `malloc`, fall back to probing the global scope for libumem's `umem_alloc`.
+#[cfg(any(target_os = "solaris", target_os = "illumos"))]
+fn libumem_linked() -> bool {
+    unsafe {
+        let mut info: libc::Dl_info = core::mem::zeroed();
+        if libc::dladdr(libc::malloc as *const libc::c_void, &mut info) == 0 || info.dli_fname.is_null() {
+            return !libc::dlsym(libc::RTLD_DEFAULT, c"umem_alloc".as_ptr()).is_null();
+        }
+        core::ffi::CStr::from_ptr(info.dli_fname)
+            .to_bytes()
+            .windows(b"libumem".len())
+            .any(|window| window == b"libumem")
+    }
 }
 
-// ========== Runtime Allocator Information ==========
+#[cfg(not(any(target_os = "solaris", target_os = "illumos")))]
+fn libumem_linked() -> bool {
+    false
+}
 
+/// Builds the structured reason for an allocator ID chosen by `select_allocator_by_hardware`
 #[cfg(not(target_os = "none"))]
-static ALLOCATOR_INFO: Lazy<AllocatorInfo> = Lazy::new(|| {
-    let system_info = collect_system_info();
-    let allocator_id = RUNTIME_ALLOCATOR_ID.load(Ordering::Acquire);
-
-    // If not yet initialized, trigger allocator selection once
-    let final_allocator_id = if allocator_id == 0 {
-        RuntimeAllocator::get_allocator_id()
-    } else {
-        allocator_id
-    };
-
-    let (_, mut reason) = get_allocator_selection_result(&system_info);
-
-    // Determine type based on actually selected allocator ID (may differ due to feature disable)
-    let allocator_type = match final_allocator_id {
-        5 => AllocatorType::MimallocSecure,
-        2 => AllocatorType::Mimalloc,
-        4 => AllocatorType::EmbeddedHeap,
-        _ => AllocatorType::System,
-    };
-
-    // Add "selected by runtime analysis" prefix to actual allocator info, extract hardware info part
-    let hardware_info = if reason.contains('(') && reason.contains(')') {
-        reason
-            .split_once('(')
-            .and_then(|(_prefix, suffix)| suffix.split_once(')').map(|(info, _)| info))
-            .unwrap_or("")
-    } else {
-        ""
-    };
-
-    reason = match final_allocator_id {
-        5 => format!(
-            "mimalloc-secure selected by runtime hardware analysis ({})",
-            hardware_info
-        ),
-        2 => format!(
-            "mimalloc selected by runtime hardware analysis ({})",
-            hardware_info
-        ),
-        4 => {
-            // For embedded allocator, preserve the original compile-time selection info
-            reason
+fn selection_reason_for_id(allocator_id: u8, system_info: &SystemInfo) -> SelectionReason {
+    let profile = get_allocator_profile();
+    let memory_constrained = is_memory_constrained(profile, system_info.effective_memory_bytes);
+    let preferred_id = select_preferred_allocator(profile, system_info.effective_memory_bytes);
+    match allocator_id {
+        4 => SelectionReason::Embedded,
+        6 => SelectionReason::Custom,
+        1 if MIMALLOC_PROBE_FAILED.load(Ordering::Relaxed) => SelectionReason::ProbeFailed,
+        _ if preferred_id != allocator_id && denied_allocator_mask() != 0 => {
+            SelectionReason::Denied {
+                skipped: allocator_name_for_id(preferred_id),
+            }
+        }
+        _ if get_compile_time_allocator().is_none()
+            && select_by_preference(system_info.cpu_cores, memory_constrained)
+                == Some(allocator_id) =>
+        {
+            SelectionReason::Preference
+        }
+        5 | 2 => match mimalloc_opt_in_override() {
+            Some(source) => SelectionReason::Override { source },
+            None => SelectionReason::MultiCore {
+                cores: system_info.cpu_cores,
+                total_memory_bytes: system_info.total_memory_bytes,
+                cpu_quota_limited: system_info.cpu_quota_cores.is_some(),
+            },
         },
         _ => {
-            // For system allocator, preserve the original detailed reason as-is
-            // (already includes correct "compile-time selected" or platform-specific info)
-            reason
-        },
-    };
-
-    AllocatorInfo {
-        allocator_type,
-        reason,
-        system_info,
+            // System allocator - determine reason based on compile-time platform detection
+            if cfg!(debug_assertions) {
+                SelectionReason::Debug
+            } else if cfg!(target_arch = "wasm32") {
+                SelectionReason::Wasm
+            } else if let Some(os) = native_allocator_platform(std::env::consts::OS) {
+                SelectionReason::PlatformPolicy { os }
+            } else if memory_constrained {
+                SelectionReason::LowMemory {
+                    effective_memory_bytes: system_info.effective_memory_bytes,
+                }
+            } else if profile == AllocatorProfile::Footprint {
+                SelectionReason::Footprint
+            } else {
+                // High-performance platforms that fell back to system (single-core or mimalloc unavailable)
+                SelectionReason::Fallback {
+                    cores: system_info.cpu_cores,
+                    total_memory_bytes: system_info.total_memory_bytes,
+                    cpu_quota_limited: system_info.cpu_quota_cores.is_some(),
+                }
+            }
+        }
     }
-});
-
-// Simplified allocator info for no_std
-#[cfg(target_os = "none")]
-static mut EMBEDDED_ALLOCATOR_INFO: Option<AllocatorInfo> = None;
+}
 
-// ========== Public API ==========
+/// Get allocator selection result and reason, honoring the deny list (internal function)
+#[cfg(not(target_os = "none"))]
+fn get_allocator_selection_result(
+    system_info: &SystemInfo,
+    profile: AllocatorProfile,
+) -> (AllocatorType, SelectionReason) {
+    let (preferred, reason) = get_preferred_selection_result(system_info, profile);
+    let preferred_id = preferred.id();
+    match next_allowed_allocator(preferred_id, denied_allocator_mask()) {
+        // mimalloc can't allocate in this process, whatever the hardware
+        2 | 5 if MIMALLOC_PROBE_FAILED.load(Ordering::Relaxed) => {
+            (AllocatorType::System, SelectionReason::ProbeFailed)
+        }
+        allowed_id if allowed_id == preferred_id => (preferred, reason),
+        allowed_id => (
+            if allowed_id == 2 { AllocatorType::Mimalloc } else { AllocatorType::System },
+            SelectionReason::Denied {
+                skipped: allocator_name_for_id(preferred_id),
+            },
+        ),
+    }
+}
 
-/// Ensure allocator information is ready
-/// Internal function, ensures ALLOCATOR_INFO has been computed
+/// Recommended allocator before the deny list is applied
 #[cfg(not(target_os = "none"))]
-fn ensure_allocator_info_ready() {
-    let _ = std::panic::catch_unwind(|| {
-        Lazy::force(&ALLOCATOR_INFO);
-    });
+fn get_preferred_selection_result(
+    system_info: &SystemInfo,
+    profile: AllocatorProfile,
+) -> (AllocatorType, SelectionReason) {
+    let memory_constrained = is_memory_constrained(profile, system_info.effective_memory_bytes);
+    if RUNTIME_ALLOCATOR_ID.load(Ordering::Acquire) == 6 {
+        // An explicitly registered allocator is the user's choice on every platform
+        (AllocatorType::Custom, SelectionReason::Custom)
+    } else if system_info.is_wasm {
+        (AllocatorType::System, SelectionReason::Wasm)
+    } else if system_info.is_debug {
+        (AllocatorType::System, SelectionReason::Debug)
+    } else if is_embedded_target() || system_info.os_type == "embedded" {
+        // os_type is "embedded" on no_std targets, and in simulated ones
+        (AllocatorType::EmbeddedHeap, SelectionReason::Embedded)
+    } else if let (Some(source), true) = (mimalloc_opt_in_override(), can_use_mimalloc()) {
+        (AllocatorType::Mimalloc, SelectionReason::Override { source })
+    } else if let Some(os) = native_allocator_platform(&system_info.os_type) {
+        (AllocatorType::System, SelectionReason::PlatformPolicy { os })
+    } else if let Some(allocator_id) = select_by_preference(system_info.cpu_cores, memory_constrained) {
+        let allocator_type = AllocatorType::from_id(allocator_id).unwrap_or(AllocatorType::System);
+        (allocator_type, SelectionReason::Preference)
+    } else if memory_constrained {
+        (
+            AllocatorType::System,
+            SelectionReason::LowMemory {
+                effective_memory_bytes: system_info.effective_memory_bytes,
+            },
+        )
+    } else if profile == AllocatorProfile::Footprint {
+        (AllocatorType::System, SelectionReason::Footprint)
+    } else if system_info.cpu_cores >= MIN_MIMALLOC_CORES {
+        (
+            AllocatorType::Mimalloc,
+            SelectionReason::MultiCore {
+                cores: system_info.cpu_cores,
+                total_memory_bytes: system_info.total_memory_bytes,
+                cpu_quota_limited: system_info.cpu_quota_cores.is_some(),
+            },
+        )
+    } else {
+        (
+            AllocatorType::System,
+            SelectionReason::Fallback {
+                cores: system_info.cpu_cores,
+                total_memory_bytes: system_info.total_memory_bytes,
+                cpu_quota_limited: system_info.cpu_quota_cores.is_some(),
+            },
+        )
+    }
 }
 
+/// Simplified allocator selection for no_std environments
 #[cfg(target_os = "none")]
-fn ensure_allocator_info_ready() {
-    unsafe {
-        if EMBEDDED_ALLOCATOR_INFO.is_none() {
-            let system_info = collect_system_info();
-            EMBEDDED_ALLOCATOR_INFO = Some(AllocatorInfo {
-                allocator_type: AllocatorType::EmbeddedHeap,
-                reason: "embedded-alloc selected for no_std environment",
-                system_info,
-            });
-        }
-    }
+fn get_allocator_selection_result(
+    _system_info: &SystemInfo,
+    _profile: AllocatorProfile,
+) -> (AllocatorType, &'static str) {
+    (AllocatorType::EmbeddedHeap, "embedded-alloc selected for no_std environment")
 }
 
-/// Returns information about the automatically selected allocator
+/// Get recommended allocator for current runtime environment (fresh detection)
 ///
-/// Provides allocator type, selection rationale, and system information.
-/// First call triggers hardware detection; subsequent calls return cached results.
+/// Based on current system hardware and environment re-analysis, returns recommended allocator type and selection reason.
+/// Unlike [`get_allocator_info()`], this function re-performs hardware detection and analysis every time;
+/// use [`get_recommended_allocator_cached()`] when polling.
 ///
-/// # Example
+/// # Return Value
+///
+/// Returns a tuple `(AllocatorType, String)`:
+/// - First element: recommended allocator type
+/// - Second element: recommendation reason, including hardware information
+///
+/// # Usage
+///
+/// This function is mainly used for:
+/// - Performance analysis and optimization recommendations
+/// - Verifying if current allocator selection is optimal
+/// - Re-evaluation after runtime environment changes
+///
+/// # Examples
 ///
 /// ```rust
 /// use auto_allocator;
 ///
-/// let info = auto_allocator::get_allocator_info();
-/// println!("Using: {:?}", info.allocator_type);
-/// println!("Reason: {}", info.reason);
+/// let (recommended_type, reason) = auto_allocator::get_recommended_allocator();
+///
+/// println!("Recommended allocator: {:?}", recommended_type);
+/// println!("Recommendation reason: {}", reason);
+///
+/// // Compare with current allocator
+/// let current_type = auto_allocator::get_allocator_type();
+/// if current_type == recommended_type {
+///     println!("Current allocator is already optimal");
+/// } else {
+///     println!("Suggest switching to: {:?}", recommended_type);
+/// }
 /// ```
+///
+/// # Performance Notes
+///
+/// This function re-performs system hardware detection, with slightly higher overhead than [`get_allocator_info()`].
 #[cfg(not(target_os = "none"))]
-pub fn get_allocator_info() -> &'static AllocatorInfo {
+pub fn get_recommended_allocator() -> (AllocatorType, String) {
     smart_try_flush_log();
-    ensure_allocator_info_ready();
-    &ALLOCATOR_INFO
+    let system_info = collect_system_info();
+    let (allocator_type, reason) = get_allocator_selection_result(&system_info, get_allocator_profile());
+    (allocator_type, reason.to_string())
 }
 
-#[cfg(target_os = "none")]
-pub fn get_allocator_info() -> &'static AllocatorInfo {
-    ensure_allocator_info_ready();
-    unsafe { EMBEDDED_ALLOCATOR_INFO.as_ref().unwrap() }
+/// Get recommended allocator from the startup hardware snapshot
+///
+/// Same result shape as [`get_recommended_allocator()`], but evaluates the selection
+/// rules against the [`SystemInfo`] captured in [`get_allocator_info()`] instead of
+/// re-running `sysinfo`/`sysctl`/cgroup detection, so it is cheap enough for
+/// monitoring loops. The active profile, preference order and deny list are still
+/// read on every call; hardware changes after startup (CPU hotplug, a resized cgroup)
+/// are not seen - call [`get_recommended_allocator()`] for that.
+///
+/// # Example
+///
+/// ```rust
+/// let (cached_type, _) = auto_allocator::get_recommended_allocator_cached();
+/// let (fresh_type, _) = auto_allocator::get_recommended_allocator();
+/// println!("cached: {:?}, fresh: {:?}", cached_type, fresh_type);
+/// ```
+#[cfg(not(target_os = "none"))]
+pub fn get_recommended_allocator_cached() -> (AllocatorType, String) {
+    smart_try_flush_log();
+    let (allocator_type, reason) =
+        get_allocator_selection_result(&get_allocator_info().system_info, get_allocator_profile());
+    (allocator_type, reason.to_string())
 }
 
-/// Get current allocator type
+/// Whether the hardware differs from the snapshot taken at allocator selection
 ///
-/// Returns the currently used allocator type, this is a simplified version of [`get_allocator_info()`].
-/// If you only need to know the allocator type without other information, using this function is more concise.
+/// Re-runs hardware detection and compares it with [`get_allocator_info()`]'s
+/// [`SystemInfo`] - see [`SystemInfo::diff()`] for what counts as a change. A long-running
+/// service that may be live-migrated or have its cgroup resized can poll this; once it
+/// returns `true`, [`get_recommended_allocator()`] may disagree with the allocator in use,
+/// which stays in place until the process restarts.
 ///
-/// # Return Value
+/// # Example
 ///
-/// Returns [`AllocatorType`] enum value, possible values:
-/// - [`AllocatorType::Mimalloc`] - Microsoft-developed high-performance allocator
-/// - [`AllocatorType::EmbeddedHeap`] - Embedded systems specific allocator
-/// - [`AllocatorType::System`] - System default allocator
+/// ```rust
+/// if auto_allocator::environment_changed_since_init() {
+///     let (recommended, reason) = auto_allocator::get_recommended_allocator();
+///     println!("hardware changed; would now pick {:?}: {}", recommended, reason);
+/// }
+/// ```
+#[cfg(not(target_os = "none"))]
+pub fn environment_changed_since_init() -> bool {
+    !get_allocator_info().system_info.diff(&collect_system_info()).is_empty()
+}
+
+/// Runs the selection rules against the given hardware description
+///
+/// Same decision as [`get_recommended_allocator()`] - honoring the active profile,
+/// preference order and deny list - but for a caller-supplied [`SystemInfo`] instead
+/// of the detected one. Useful for capacity planning and for testing how a deployment
+/// target (say, a 256MB container on a 64-core host) would be treated.
 ///
 /// # Example
 ///
 /// ```rust
-/// use auto_allocator;
+/// use auto_allocator::AllocatorType;
 ///
-/// let allocator_type = auto_allocator::get_allocator_type();
+/// let mut target = auto_allocator::get_allocator_info().system_info.clone();
+/// target.is_debug = false;
+/// target.effective_memory_bytes = 256 << 20;
 ///
-/// // Simple allocator type check
-/// if allocator_type == auto_allocator::AllocatorType::Mimalloc {
-///     println!("Using high-performance mimalloc allocator");
-/// }
+/// let (allocator_type, reason) = auto_allocator::get_recommended_allocator_for(&target);
+/// assert_eq!(allocator_type, AllocatorType::System);
+/// println!("{}", reason);
+/// ```
+#[cfg(not(target_os = "none"))]
+pub fn get_recommended_allocator_for(system_info: &SystemInfo) -> (AllocatorType, SelectionReason) {
+    get_allocator_selection_result(system_info, get_allocator_profile())
+}
+
+/// Runs the startup selection against the real environment without acting on it
 ///
-/// // Or use match statement
-/// match allocator_type {
-///     auto_allocator::AllocatorType::Mimalloc => {
-///         println!("mimalloc - optimal performance");
-///     }
-///     auto_allocator::AllocatorType::System => {
-///         println!("system - maximum compatibility");
-///     }
-///     _ => println!("other allocator"),
-/// }
+/// For inspection tools linked with `no-global`: returns what auto-allocator would pick
+/// as the global allocator here, using the same detection and rules as the first
+/// allocation (and the same reason [`get_allocator_info()`] would report). Nothing is
+/// latched - profile, preference and deny-list setters keep working, and every call
+/// re-detects the hardware. A registered custom allocator is not considered.
+///
+/// # Example
+///
+/// ```rust
+/// # #[cfg(feature = "no-global")]
+/// # {
+/// let (allocator_type, reason) = auto_allocator::dry_run_selection();
+/// println!("would select {:?}: {}", allocator_type, reason);
+/// # }
 /// ```
+#[cfg(all(feature = "no-global", not(target_os = "none")))]
+pub fn dry_run_selection() -> (AllocatorType, SelectionReason) {
+    let allocator_id = detect_allocator_id();
+    let allocator_type = AllocatorType::from_id(allocator_id).unwrap_or(AllocatorType::System);
+    (allocator_type, selection_reason_for_id(allocator_id, &collect_system_info()))
+}
+
+/// Helpers for testing selection decisions in-process (`testing` feature)
 ///
-/// # Performance Notes
+/// The live selection latches once per process, so a test binary can't re-run it
+/// for another scenario. The selection rules are a pure function of a
+/// [`SystemInfo`] and the settings, though: describe the scenario as a
+/// `SystemInfo`, ask [`select_for()`](testing::select_for) what would be chosen,
+/// and call [`reset_selection_overrides()`](testing::reset_selection_overrides)
+/// between scenarios that change the deny list or preference order. Nothing here
+/// changes the allocator actually serving the process;
+/// [`set_selection_barrier()`](testing::set_selection_barrier) only delays its
+/// selection, to test the first-allocation race.
 ///
-/// This function is slightly faster than [`get_allocator_info()`] because it only returns type information.
-pub fn get_allocator_type() -> AllocatorType {
-    smart_try_flush_log();
-    ensure_allocator_info_ready();
-    get_allocator_info().allocator_type
+/// | Scenario | `SystemInfo` change |
+/// |----------|---------------------|
+/// | release / debug build | `is_debug` |
+/// | WASM | `is_wasm: true` |
+/// | no_std (embedded) target | `os_type: "embedded"` |
+/// | another OS | `os_type`, e.g. `"android"` |
+/// | container limits | `effective_memory_bytes`, `cpu_cores` |
+///
+/// # Example
+///
+/// ```rust
+/// # #[cfg(feature = "testing")]
+/// # {
+/// use auto_allocator::{testing, AllocatorProfile, AllocatorType};
+///
+/// let mut wasm = auto_allocator::get_allocator_info().system_info.clone();
+/// wasm.is_wasm = true;
+/// let (allocator, _) = testing::select_for(&wasm, AllocatorProfile::Throughput);
+/// assert_eq!(allocator, AllocatorType::System);
+/// # }
+/// ```
+#[cfg(all(feature = "testing", not(target_os = "none")))]
+pub mod testing {
+    use crate::{AllocatorProfile, AllocatorType, SelectionReason, SystemInfo};
+    use core::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Allocator the rules would choose for `system_info` under `profile`
+    ///
+    /// Same rules as the live selection and [`get_recommended_allocator_for()`](crate::get_recommended_allocator_for),
+    /// including the current preference order and deny list, but with the profile
+    /// passed in rather than taken from `AUTO_ALLOCATOR_PROFILE` /
+    /// [`set_allocator_profile()`](crate::set_allocator_profile). Which allocators
+    /// are compiled in still comes from this build's features.
+    pub fn select_for(
+        system_info: &SystemInfo,
+        profile: AllocatorProfile,
+    ) -> (AllocatorType, SelectionReason) {
+        crate::get_allocator_selection_result(system_info, profile)
+    }
+
+    /// Clears the profile, deny list and preference order set through the setters
+    ///
+    /// Afterwards the `AUTO_ALLOCATOR_*` environment variables apply again, as at
+    /// startup. Meant for the start of each test that sets them; the allocator
+    /// already selected for the process is unaffected.
+    pub fn reset_selection_overrides() {
+        crate::PROFILE_OVERRIDE.store(0, Ordering::Release);
+        crate::DENY_OVERRIDE.store(0, Ordering::Release);
+        crate::PREFERENCE_OVERRIDE.store(0, Ordering::Release);
+    }
+
+    // 0 = no barrier, otherwise a `fn()` to run before the selection is published
+    static SELECTION_BARRIER: AtomicUsize = AtomicUsize::new(0);
+
+    /// Runs `barrier` between the first-allocation selection and its publication
+    ///
+    /// Widens the window in which several threads race to select the allocator, so
+    /// thread or loom tests can force interleavings of that race: a barrier that
+    /// parks until a second thread arrives makes both threads compute a selection
+    /// before either stores it. Only the selection on the first allocation is
+    /// affected; once an allocator is selected the barrier never runs again.
+    ///
+    /// The barrier runs inside the global allocator, so it must not allocate - an
+    /// allocation there would re-enter the selection and the barrier. Spin on
+    /// atomics or call `std::thread::yield_now()` instead of using `Mutex`/`Barrier`.
+    pub fn set_selection_barrier(barrier: fn()) {
+        SELECTION_BARRIER.store(barrier as usize, Ordering::Release);
+    }
+
+    /// Removes the barrier set with [`set_selection_barrier()`]
+    pub fn clear_selection_barrier() {
+        SELECTION_BARRIER.store(0, Ordering::Release);
+    }
+
+    #[inline]
+    pub(crate) fn run_selection_barrier() {
+        let barrier = SELECTION_BARRIER.load(Ordering::Acquire);
+        if barrier != 0 {
+            let barrier: fn() = unsafe { core::mem::transmute(barrier) };
+            barrier();
+        }
+    }
 }
 
-/// Get allocator selection result and reason (internal function)
+/// Describes every selection check in order and which one decided, for support and debugging
+///
+/// Answers "why did it pick system on my 32-core box?" - usually a debug build. Walks
+/// the same rules as [`get_recommended_allocator()`] against the startup
+/// [`SystemInfo`]: custom allocator, WASM, debug build, embedded target, opt-in
+/// features, platform policy, preference order, memory thresholds, profile and core
+/// count, then the deny list. Each line shows the detected value; checks after the
+/// deciding one are marked as not reached. The format is meant for humans and may
+/// change - match on [`SelectionReason`] in code.
+///
+/// # Example
+///
+/// ```rust
+/// let explanation = auto_allocator::explain_selection();
+/// assert!(explanation.contains("Recommended:"));
+/// println!("{}", explanation);
+/// ```
 #[cfg(not(target_os = "none"))]
-fn get_allocator_selection_result(system_info: &SystemInfo) -> (AllocatorType, String) {
-    let total_mem = format_memory_size(system_info.total_memory_bytes);
+pub fn explain_selection() -> String {
+    use core::fmt::Write;
+
+    let info = get_allocator_info();
+    let system_info = &info.system_info;
+    let profile = get_allocator_profile();
+    let memory_constrained = is_memory_constrained(profile, system_info.effective_memory_bytes);
+    let cores = match system_info.cpu_quota_cores {
+        Some(quota) => format!("{} cores, limited by cgroup CPU quota", quota),
+        None => format!("{} cores", system_info.cpu_cores),
+    };
+    let preference = select_by_preference(system_info.cpu_cores, memory_constrained);
 
-    if system_info.is_wasm {
-        (
-            AllocatorType::System,
-            format!("system allocator - WASM environment ({} total RAM)", total_mem),
-        )
-    } else if system_info.is_debug {
-        (
-            AllocatorType::System,
-            format!(
-                "system allocator - debug build ({} cores, {} total RAM)",
-                system_info.cpu_cores, total_mem
-            ),
-        )
-    } else if is_embedded_target() {
-        (
-            AllocatorType::EmbeddedHeap,
-            format!("embedded-alloc allocator - embedded environment ({} total RAM)", total_mem),
-        )
-    } else if system_info.os_type == "android" {
-        (
-            AllocatorType::System,
-            format!(
-                "Android platform - Scudo allocator (security-first, use-after-free protection) ({} cores, {} total RAM)",
-                system_info.cpu_cores, total_mem
-            ),
-        )
-    } else if system_info.os_type == "ios" {
-        (
-            AllocatorType::System,
-            format!(
-                "iOS platform - libmalloc allocator (Apple-optimized, memory pressure handling) ({} cores, {} total RAM)",
-                system_info.cpu_cores, total_mem
-            ),
-        )
-    } else if system_info.os_type == "freebsd" || system_info.os_type == "netbsd" {
-        (
-            AllocatorType::System,
-            format!(
-                "BSD platform - native jemalloc (highly optimized, deep system integration) ({} cores, {} total RAM)",
-                system_info.cpu_cores, total_mem
-            ),
-        )
-    } else if system_info.os_type == "openbsd" {
+    let checks: [(&str, String, bool); 11] = [
         (
-            AllocatorType::System,
-            format!(
-                "OpenBSD platform - security-hardened allocator (exploit mitigation, aggressive hardening) ({} cores, {} total RAM)",
-                system_info.cpu_cores, total_mem
-            ),
-        )
-    } else if system_info.os_type == "solaris" || system_info.os_type == "illumos" {
+            "custom allocator registered",
+            String::new(),
+            RUNTIME_ALLOCATOR_ID.load(Ordering::Acquire) == 6,
+        ),
+        ("WASM target", system_info.target_arch.clone(), system_info.is_wasm),
+        ("debug build", String::new(), system_info.is_debug),
+        ("embedded (no_std) target", String::new(), is_embedded_target()),
         (
-            AllocatorType::System,
-            format!(
-                "Solaris platform - libumem allocator (NUMA-aware, enterprise-grade performance) ({} cores, {} total RAM)",
-                system_info.cpu_cores, total_mem
-            ),
-        )
-    } else if system_info.cpu_cores >= 2 {
+            "mimalloc opt-in feature (android-mimalloc / bsd-mimalloc)",
+            String::new(),
+            mimalloc_opt_in_override().is_some() && can_use_mimalloc(),
+        ),
         (
-            AllocatorType::Mimalloc,
-            format!(
-                "mimalloc allocator - high-performance multi-threaded environment ({} cores, {} total RAM)",
-                system_info.cpu_cores, total_mem
-            ),
-        )
-    } else {
+            "platform prefers its native allocator",
+            system_info.os_type.clone(),
+            native_allocator_platform(&system_info.os_type).is_some(),
+        ),
         (
-            AllocatorType::System,
+            "preference order (AUTO_ALLOCATOR_PREFER / set_allocator_preference)",
+            if preference_order() == 0 { "not set".to_string() } else { "set".to_string() },
+            preference.is_some(),
+        ),
+        (
+            "memory constrained",
             format!(
-                "system allocator - low-performance environment ({} cores, {} total RAM)",
-                system_info.cpu_cores, total_mem
+                "{} effective, minimum for mimalloc {}{}",
+                format_memory_size(system_info.effective_memory_bytes),
+                format_memory_size(MIN_MIMALLOC_MEMORY_BYTES),
+                if profile == AllocatorProfile::LowMemory {
+                    format!(", low-memory profile below {}", format_memory_size(LOW_MEMORY_PROFILE_THRESHOLD_BYTES))
+                } else {
+                    String::new()
+                }
             ),
-        )
+            memory_constrained,
+        ),
+        ("footprint profile", String::new(), profile == AllocatorProfile::Footprint),
+        (
+            if MIN_MIMALLOC_CORES == 1 { "core count (1+ cores, allow-single-core-mimalloc)" } else { "multi-core (2+ cores)" },
+            cores.clone(),
+            system_info.cpu_cores >= MIN_MIMALLOC_CORES,
+        ),
+        ("fallback", cores, true),
+    ];
+
+    let mut out = String::new();
+    let _ = writeln!(out, "Allocator selection (profile: {})", profile.name());
+    let _ = writeln!(
+        out,
+        "  target: {} ({}-bit, {}-endian)",
+        system_info.target_triple,
+        system_info.pointer_width,
+        system_info.endian.name()
+    );
+    let _ = writeln!(out, "  features: {}", enabled_features().join(", "));
+    let mut decided = false;
+    for (index, (label, detail, fired)) in checks.iter().enumerate() {
+        let detail = if detail.is_empty() { String::new() } else { format!(" [{}]", detail) };
+        let verdict = if decided {
+            "not reached"
+        } else if *fired {
+            decided = true;
+            "yes - decides"
+        } else {
+            "no"
+        };
+        let _ = writeln!(out, "  {:>2}. {}{}: {}", index + 1, label, detail, verdict);
     }
-}
 
-/// Simplified allocator selection for no_std environments
-#[cfg(target_os = "none")]
-fn get_allocator_selection_result(_system_info: &SystemInfo) -> (AllocatorType, &'static str) {
-    (AllocatorType::EmbeddedHeap, "embedded-alloc selected for no_std environment")
+    let (preferred, _) = get_preferred_selection_result(system_info, profile);
+    let (recommended, reason) = get_allocator_selection_result(system_info, profile);
+    let denied = denied_allocator_mask();
+    let _ = writeln!(
+        out,
+        "  deny list (AUTO_ALLOCATOR_DENY / set_denied_allocators): {}",
+        if denied == 0 {
+            "empty".to_string()
+        } else if recommended != preferred {
+            format!("skipped {}", allocator_name_for_id(preferred.id()))
+        } else {
+            "no effect".to_string()
+        }
+    );
+    let _ = writeln!(out, "Recommended: {} - {}", allocator_name_for_id(recommended.id()), reason);
+    let _ = write!(out, "Active: {} - {}", allocator_name_for_id(info.allocator_type.id()), info.reason);
+    out
 }
 
-/// Get recommended allocator for current runtime environment
+/// The raw values the allocator selector reads, returned by [`selection_inputs()`]
 ///
-/// Based on current system hardware and environment re-analysis, returns recommended allocator type and selection reason.
-/// Unlike [`get_allocator_info()`], this function re-performs hardware detection and analysis every time.
-///
-/// # Return Value
-///
-/// Returns a tuple `(AllocatorType, String)`:
-/// - First element: recommended allocator type
-/// - Second element: recommendation reason, including hardware information
-///
-/// # Usage
+/// Unlike [`SystemInfo`], which describes the machine, this is what the decision was
+/// made from: the core count the selector itself detects (online CPUs clamped to the
+/// cgroup quota) next to `available_parallelism()`, the memory figures after overrides
+/// and cgroup limits, and the compile-time gates. Include it in bug reports when the
+/// choice looks wrong.
+#[cfg(not(target_os = "none"))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SelectionInputs {
+    /// Selection profile in effect
+    pub profile: AllocatorProfile,
+
+    /// Cores as the selector counts them, compared against the multi-core threshold
+    ///
+    /// Online CPUs (or the affinity mask with `no-libc`) clamped to the cgroup CPU quota.
+    pub selector_cores: usize,
+
+    /// `std::thread::available_parallelism()`, which follows the CPU affinity mask
+    ///
+    /// `SystemInfo::cpu_cores` starts from this value; a difference from `selector_cores`
+    /// usually means the process is pinned to fewer CPUs (`taskset`, cpusets).
+    pub available_parallelism: usize,
+
+    /// Core count allowed by the cgroup CPU quota, `None` without a quota or off Linux
+    pub cpu_quota_cores: Option<usize>,
+
+    /// Fewest cores mimalloc needs (1 with `allow-single-core-mimalloc`, else 2)
+    pub min_mimalloc_cores: usize,
+
+    /// Total memory in bytes, after `AUTO_ALLOCATOR_TOTAL_MEMORY`
+    pub total_memory_bytes: u64,
+
+    /// Memory the selector compares against its thresholds (total capped by the cgroup limit)
+    pub effective_memory_bytes: u64,
+
+    /// Whether `effective_memory_bytes` is below the profile's threshold for mimalloc
+    pub memory_constrained: bool,
+
+    /// Allocator fixed by the build (debug, WASM, embedded, platform policy, opt-in
+    /// features), `None` when runtime detection decides
+    pub compile_time_allocator: Option<AllocatorType>,
+
+    /// `cfg!(debug_assertions)`
+    pub is_debug: bool,
+
+    /// `cfg!(target_arch = "wasm32")`
+    pub is_wasm: bool,
+
+    /// `cfg!(target_os = "none")`
+    pub is_embedded: bool,
+
+    /// Whether mimalloc is compiled in and usable on this target in this build mode
+    pub mimalloc_available: bool,
+
+    /// Whether mimalloc in secure mode (`secure` feature) is usable
+    pub mimalloc_secure_available: bool,
+
+    /// Whether `android-mimalloc` / `bsd-mimalloc` applies to this target
+    pub mimalloc_opt_in: bool,
+}
+
+/// Captures the values the selector decides from, detected now
 ///
-/// This function is mainly used for:
-/// - Performance analysis and optimization recommendations
-/// - Verifying if current allocator selection is optimal
-/// - Re-evaluation after runtime environment changes
+/// Runs the same detection functions as the selection on the first allocation, so
+/// comparing this with [`get_allocator_info()`] shows whether the inputs changed since
+/// startup. See [`SelectionInputs`] for how it differs from [`SystemInfo`].
 ///
-/// # Examples
+/// # Example
 ///
 /// ```rust
-/// use auto_allocator;
+/// let inputs = auto_allocator::selection_inputs();
+/// println!(
+///     "{} cores (parallelism {}), {} effective, compile-time choice {:?}",
+///     inputs.selector_cores,
+///     inputs.available_parallelism,
+///     auto_allocator::format_memory_size(inputs.effective_memory_bytes),
+///     inputs.compile_time_allocator
+/// );
+/// ```
+#[cfg(not(target_os = "none"))]
+pub fn selection_inputs() -> SelectionInputs {
+    let profile = get_allocator_profile();
+    let effective_memory_bytes = get_effective_memory_safe();
+    SelectionInputs {
+        profile,
+        selector_cores: get_cpu_cores_safe(),
+        available_parallelism: std::thread::available_parallelism().map_or(1, |n| n.get()),
+        cpu_quota_cores: cgroup_cpu_quota_cores(),
+        min_mimalloc_cores: MIN_MIMALLOC_CORES,
+        total_memory_bytes: get_total_memory_safe(),
+        effective_memory_bytes,
+        memory_constrained: is_memory_constrained(profile, effective_memory_bytes),
+        compile_time_allocator: get_compile_time_allocator().and_then(AllocatorType::from_id),
+        is_debug: cfg!(debug_assertions),
+        is_wasm: cfg!(target_arch = "wasm32"),
+        is_embedded: is_embedded_target(),
+        mimalloc_available: can_use_mimalloc(),
+        mimalloc_secure_available: can_use_mimalloc_secure(),
+        mimalloc_opt_in: is_mimalloc_opt_in(),
+    }
+}
+
+/// Exports the startup [`SystemInfo`] and the active allocator as flat `KEY=value` pairs
 ///
-/// let (recommended_type, reason) = auto_allocator::get_recommended_allocator();
+/// For shell scripts, container init and healthchecks that want plain variables
+/// rather than JSON. Every key is always present, prefixed with `AUTO_ALLOC_`:
+/// numbers are decimal (bytes for memory sizes), booleans are `1`/`0`, and
+/// undetected optional values are empty. Values are not shell-quoted -
+/// `AUTO_ALLOC_REASON` contains spaces, so quote when building `export` lines.
 ///
-/// println!("Recommended allocator: {:?}", recommended_type);
-/// println!("Recommendation reason: {}", reason);
+/// # Example
 ///
-/// // Compare with current allocator
-/// let current_type = auto_allocator::get_allocator_type();
-/// if current_type == recommended_type {
-///     println!("Current allocator is already optimal");
-/// } else {
-///     println!("Suggest switching to: {:?}", recommended_type);
+/// ```rust
+/// for (key, value) in auto_allocator::system_info_env() {
+///     println!("export {}='{}'", key, value.replace('\'', "'\\''"));
 /// }
 /// ```
-///
-/// # Performance Notes
-///
-/// This function re-performs system hardware detection, with slightly higher overhead than [`get_allocator_info()`].
 #[cfg(not(target_os = "none"))]
-pub fn get_recommended_allocator() -> (AllocatorType, String) {
-    smart_try_flush_log();
-    let system_info = collect_system_info();
-    get_allocator_selection_result(&system_info)
+pub fn system_info_env() -> Vec<(String, String)> {
+    let info = get_allocator_info();
+    let sys = &info.system_info;
+    let flag = |value: bool| if value { "1" } else { "0" }.to_string();
+    let optional = |value: Option<String>| value.unwrap_or_default();
+
+    [
+        ("TYPE", allocator_name_for_id(info.allocator_type.id()).to_string()),
+        ("ID", info.allocator_type.id().to_string()),
+        ("REASON", info.reason.to_string()),
+        ("OS", sys.os_type.clone()),
+        ("ARCH", sys.target_arch.clone()),
+        ("TARGET", sys.target_triple.to_string()),
+        ("POINTER_WIDTH", sys.pointer_width.to_string()),
+        ("ENDIAN", sys.endian.name().to_string()),
+        ("CORES", sys.cpu_cores.to_string()),
+        ("CPU_QUOTA", optional(sys.cpu_quota_cores.map(|cores| cores.to_string()))),
+        ("PERF_CORES", sys.performance_cores.to_string()),
+        ("EFFICIENCY_CORES", sys.efficiency_cores.to_string()),
+        ("TOTAL_MEM", sys.total_memory_bytes.to_string()),
+        ("EFFECTIVE_MEM", sys.effective_memory_bytes.to_string()),
+        ("AVAILABLE_MEM", sys.available_memory_bytes.to_string()),
+        ("TOTAL_SWAP", sys.total_swap_bytes.to_string()),
+        ("FREE_SWAP", sys.free_swap_bytes.to_string()),
+        ("PAGE_SIZE", sys.page_size.to_string()),
+        ("HUGE_PAGES", flag(sys.huge_pages_available)),
+        (
+            "THP",
+            optional(sys.thp_mode.map(|mode| {
+                match mode {
+                    ThpMode::Always => "always",
+                    ThpMode::Madvise => "madvise",
+                    ThpMode::Never => "never",
+                }
+                .to_string()
+            })),
+        ),
+        ("HYPERVISOR", optional(sys.hypervisor.map(str::to_string))),
+        ("PID1", flag(sys.is_pid1)),
+        ("DEBUG", flag(sys.is_debug)),
+        ("WASM", flag(sys.is_wasm)),
+    ]
+    .into_iter()
+    .map(|(key, value)| (format!("AUTO_ALLOC_{}", key), value))
+    .collect()
 }
 
 #[cfg(target_os = "none")]
 pub fn get_recommended_allocator() -> (AllocatorType, &'static str) {
     let system_info = collect_system_info();
-    get_allocator_selection_result(&system_info)
+    get_allocator_selection_result(&system_info, get_allocator_profile())
 }
 
 /// Check if current allocator is optimal for current environment
@@ -1456,6 +8057,411 @@ pub fn check_allocator_optimization() -> (bool, Option<&'static str>) {
     (true, None)
 }
 
+/// Result of [`check_allocator_optimization_detailed()`]
+///
+/// # Example
+///
+/// ```rust
+/// let report = auto_allocator::check_allocator_optimization_detailed();
+/// println!("{:?}: {}", report.current, report.current_reason);
+/// if let Some(advice) = &report.advice {
+///     println!("to improve: {}", advice);
+/// }
+/// ```
+#[cfg(not(target_os = "none"))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OptimizationReport {
+    /// Allocator in use
+    pub current: AllocatorType,
+
+    /// Why the current allocator was selected at startup
+    pub current_reason: SelectionReason,
+
+    /// Allocator the selection rules pick now, same as [`get_recommended_allocator()`]
+    pub recommended: AllocatorType,
+
+    /// Why the rules pick `recommended`
+    pub recommended_reason: SelectionReason,
+
+    /// `current == recommended`, the verdict of [`check_allocator_optimization()`]
+    pub is_optimal: bool,
+
+    /// What would get a faster allocator, when something in the build or the
+    /// configuration holds one back - e.g. "debug build - recompile with --release".
+    /// `None` when the current choice is what this platform should use anyway
+    /// (platform policy, WASM, embedded, a custom or opt-in allocator, mimalloc).
+    pub advice: Option<String>,
+}
+
+/// Like [`check_allocator_optimization()`], but says why the current choice is what it is
+///
+/// Turns the [`SelectionReason`] behind the active allocator into actionable advice
+/// (rebuild in release mode, enable a feature, revisit the deny list or profile) and
+/// also reports when the recommendation has drifted from the startup choice, e.g.
+/// after a setter or a cgroup change - which takes a restart to apply.
+#[cfg(not(target_os = "none"))]
+pub fn check_allocator_optimization_detailed() -> OptimizationReport {
+    smart_try_flush_log();
+    let info = get_allocator_info();
+    let system_info = collect_system_info();
+    let (recommended, recommended_reason) =
+        get_allocator_selection_result(&system_info, get_allocator_profile());
+    let is_optimal = info.allocator_type == recommended;
+
+    let advice = optimization_advice(&info.reason_detail, &info.system_info).or_else(|| {
+        (!is_optimal).then(|| {
+            format!(
+                "recommendation changed since startup ({:?}: {}) - restart the process to apply it",
+                recommended, recommended_reason
+            )
+        })
+    });
+
+    OptimizationReport {
+        current: info.allocator_type,
+        current_reason: info.reason_detail,
+        recommended,
+        recommended_reason,
+        is_optimal,
+        advice,
+    }
+}
+
+/// What would lift the restriction behind `reason`, `None` if nothing holds mimalloc back
+#[cfg(not(target_os = "none"))]
+fn optimization_advice(reason: &SelectionReason, system_info: &SystemInfo) -> Option<String> {
+    let advice = match reason {
+        SelectionReason::Debug => {
+            "debug build - recompile with --release (debug builds always use the system allocator)".to_string()
+        }
+        SelectionReason::Fallback { cores, .. } if reason.fallback_reason() == Some(FallbackReason::SingleCore) => format!(
+            "{} core{} - mimalloc needs {}+ (the allow-single-core-mimalloc feature lifts this)",
+            cores,
+            if *cores == 1 { "" } else { "s" },
+            MIN_MIMALLOC_CORES
+        ),
+        SelectionReason::Fallback { .. } => {
+            "mimalloc feature disabled - enable auto-allocator's default features or `secure`".to_string()
+        }
+        SelectionReason::LowMemory { effective_memory_bytes } => format!(
+            "{} effective RAM - mimalloc needs {}{}",
+            format_memory_size(*effective_memory_bytes),
+            format_memory_size(MIN_MIMALLOC_MEMORY_BYTES),
+            if system_info.effective_memory_bytes < system_info.total_memory_bytes {
+                " (raise the cgroup memory limit)"
+            } else {
+                ""
+            }
+        ),
+        SelectionReason::Footprint => {
+            "footprint profile - AUTO_ALLOCATOR_PROFILE=throughput lets mimalloc be selected".to_string()
+        }
+        SelectionReason::Denied { skipped } => {
+            format!("{} is on the deny list (AUTO_ALLOCATOR_DENY / set_denied_allocators())", skipped)
+        }
+        SelectionReason::Preference => {
+            "chosen by the preference order (AUTO_ALLOCATOR_PREFER / set_allocator_preference())".to_string()
+        }
+        SelectionReason::ProbeFailed => {
+            "mimalloc could not allocate at startup - check seccomp / address-space limits".to_string()
+        }
+        SelectionReason::Wasm
+        | SelectionReason::Embedded
+        | SelectionReason::PlatformPolicy { .. }
+        | SelectionReason::MultiCore { .. }
+        | SelectionReason::Custom
+        | SelectionReason::Override { .. } => return None,
+    };
+    Some(advice)
+}
+
+/// Returns the version of the active allocator backend, when it reports one
+///
+/// With mimalloc or mimalloc-secure active, this is the linked library's runtime
+/// `mi_version()` formatted as `x.y.z` (e.g. `"1.7.9"`). Returns `None` for the
+/// system and embedded allocators. Useful for support tickets and regression triage.
+///
+/// # Example
+///
+/// ```rust
+/// use auto_allocator;
+///
+/// match auto_allocator::backend_version() {
+///     Some(version) => println!("mimalloc {}", version),
+///     None => println!("{:?} (no version reported)", auto_allocator::get_allocator_type()),
+/// }
+/// ```
+#[cfg(not(target_os = "none"))]
+pub fn backend_version() -> Option<String> {
+    match get_allocator_type() {
+        AllocatorType::Mimalloc | AllocatorType::MimallocSecure => mimalloc_version(),
+        _ => None,
+    }
+}
+
+#[cfg(auto_allocator_mimalloc)]
+fn mimalloc_version() -> Option<String> {
+    extern "C" {
+        // Not bound by mimalloc-rust-sys; encoded as major*100 + minor*10 + patch
+        fn mi_version() -> core::ffi::c_int;
+    }
+    let version = unsafe { mi_version() };
+    Some(format!("{}.{}.{}", version / 100, version / 10 % 10, version % 10))
+}
+
+#[cfg(all(not(target_os = "none"), not(auto_allocator_mimalloc)))]
+fn mimalloc_version() -> Option<String> {
+    None
+}
+
+/// Returns the process's current resident set size (RSS) in bytes
+///
+/// This is the OS's view of memory use, including allocator overhead and
+/// fragmentation - complementing the requested-byte counters of the `stats` feature.
+/// Read from `/proc/self/statm` on Linux/Android, `task_info(MACH_TASK_BASIC_INFO)`
+/// on macOS and `GetProcessMemoryInfo()` on Windows.
+///
+/// Returns `None` on other platforms or if the query fails. Performs a system call
+/// (and a small file read on Linux), so keep it off hot paths.
+///
+/// # Example
+///
+/// ```rust
+/// if let Some(rss) = auto_allocator::process_memory_usage() {
+///     println!("RSS: {}", auto_allocator::format_memory_size(rss));
+/// }
+/// ```
+#[cfg(not(target_os = "none"))]
+pub fn process_memory_usage() -> Option<u64> {
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    {
+        // statm fields are in pages: size resident shared text lib data dt
+        let statm = std::fs::read_to_string("/proc/self/statm").ok()?;
+        let resident_pages: u64 = statm.split_whitespace().nth(1)?.parse().ok()?;
+        return Some(resident_pages * get_page_size_safe() as u64);
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        #[allow(deprecated)] // libc points to the mach2 crate, but this one call doesn't justify a dependency
+        unsafe {
+            let mut info: libc::mach_task_basic_info = core::mem::zeroed();
+            let mut count = libc::MACH_TASK_BASIC_INFO_COUNT;
+            let result = libc::task_info(
+                libc::mach_task_self(),
+                libc::MACH_TASK_BASIC_INFO,
+                &mut info as *mut _ as libc::task_info_t,
+                &mut count,
+            );
+            return (result == libc::KERN_SUCCESS).then_some(info.resident_size);
+        }
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        use winapi::um::processthreadsapi::GetCurrentProcess;
+        use winapi::um::psapi::{GetProcessMemoryInfo, PROCESS_MEMORY_COUNTERS};
+        unsafe {
+            let mut counters: PROCESS_MEMORY_COUNTERS = core::mem::zeroed();
+            counters.cb = core::mem::size_of::<PROCESS_MEMORY_COUNTERS>() as u32;
+            if GetProcessMemoryInfo(GetCurrentProcess(), &mut counters, counters.cb) != 0 {
+                return Some(counters.WorkingSetSize as u64);
+            }
+            return None;
+        }
+    }
+
+    #[allow(unreachable_code)]
+    None
+}
+
+/// How many more bytes this process can use before it hits a memory ceiling
+///
+/// Inside a cgroup with a memory limit, that is the limit minus the process's
+/// current RSS ([`process_memory_usage()`]); otherwise it is the system's available
+/// memory from [`memory_snapshot()`]. When both are known, the smaller one wins -
+/// a generous container limit doesn't help on a host that is already short.
+/// For sizing caches adaptively: re-read it when deciding, it's a live value.
+///
+/// Returns `None` when the platform can't report available memory, or when a
+/// cgroup limit is set but the process's RSS can't be read. Reads `/proc` files
+/// on Linux, so keep it off hot paths.
+///
+/// # Example
+///
+/// ```rust
+/// if let Some(headroom) = auto_allocator::memory_headroom_bytes() {
+///     // Let the cache grow into at most a quarter of what's left
+///     let cache_budget = headroom / 4;
+///     println!("cache budget: {}", auto_allocator::format_memory_size(cache_budget));
+/// }
+/// ```
+#[cfg(not(target_os = "none"))]
+pub fn memory_headroom_bytes() -> Option<u64> {
+    let available = Some(memory_snapshot().available_bytes).filter(|&bytes| bytes > 0);
+    let Some(limit) = cgroup_memory_limit_bytes() else {
+        return available;
+    };
+    let cgroup_headroom = limit.saturating_sub(process_memory_usage()?);
+    Some(available.map_or(cgroup_headroom, |available| available.min(cgroup_headroom)))
+}
+
+/// Allocates `size` bytes aligned to `align` through the selected allocator
+///
+/// Convenience wrapper around [`Layout::from_size_align()`] for quick aligned buffers
+/// (DMA, SIMD). The memory comes from the same backend - and passes through the same
+/// optional layers - as every other allocation. Contents are uninitialized.
+///
+/// Returns a null pointer if `size` is zero, `align` is not a nonzero power of two,
+/// the layout overflows, or the allocator is out of memory.
+///
+/// # Example
+///
+/// ```rust
+/// let ptr = auto_allocator::alloc_aligned(4096, 64);
+/// assert!(!ptr.is_null());
+/// assert_eq!(ptr as usize % 64, 0);
+/// unsafe { auto_allocator::dealloc_aligned(ptr, 4096, 64) };
+///
+/// assert!(auto_allocator::alloc_aligned(64, 3).is_null());
+/// ```
+pub fn alloc_aligned(size: usize, align: usize) -> *mut u8 {
+    match Layout::from_size_align(size, align) {
+        Ok(layout) if size != 0 => unsafe { GLOBAL.alloc(layout) },
+        _ => core::ptr::null_mut(),
+    }
+}
+
+/// Frees memory returned by [`alloc_aligned()`]
+///
+/// Null pointers and invalid `size`/`align` combinations are ignored.
+///
+/// # Safety
+///
+/// `ptr` must come from [`alloc_aligned()`] with the same `size` and `align`,
+/// and must not be used after this call.
+pub unsafe fn dealloc_aligned(ptr: *mut u8, size: usize, align: usize) {
+    if ptr.is_null() {
+        return;
+    }
+    if let Ok(layout) = Layout::from_size_align(size, align) {
+        GLOBAL.dealloc(ptr, layout);
+    }
+}
+
+/// Pins the process's memory so allocator pages are never swapped out
+///
+/// Calls `mlockall(MCL_CURRENT | MCL_FUTURE)` on Linux, Android, FreeBSD and NetBSD,
+/// covering pages the allocator maps later too. On Windows, raises the minimum working
+/// set to the current working set (`SetProcessWorkingSetSize`), so pages in use now
+/// stay resident. Meant for latency-sensitive services; pair it with [`prefault_heap()`].
+///
+/// # Errors
+///
+/// Returns the OS error when locking is refused - typically `PermissionDenied` or
+/// `OutOfMemory` (Linux needs `CAP_IPC_LOCK` or a large enough `RLIMIT_MEMLOCK`) -
+/// and `Unsupported` on other platforms.
+///
+/// # Example
+///
+/// ```rust
+/// if let Err(err) = auto_allocator::lock_memory() {
+///     eprintln!("running without locked memory: {}", err);
+/// }
+/// ```
+#[cfg(not(target_os = "none"))]
+pub fn lock_memory() -> std::io::Result<()> {
+    #[cfg(auto_allocator_no_libc)]
+    {
+        // MCL_CURRENT | MCL_FUTURE
+        return raw_syscall::mlockall(1 | 2).map_err(std::io::Error::from_raw_os_error);
+    }
+
+    #[cfg(all(
+        any(target_os = "linux", target_os = "android", target_os = "freebsd", target_os = "netbsd"),
+        not(auto_allocator_no_libc)
+    ))]
+    {
+        if unsafe { libc::mlockall(libc::MCL_CURRENT | libc::MCL_FUTURE) } == 0 {
+            return Ok(());
+        }
+        return Err(std::io::Error::last_os_error());
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        use winapi::um::processthreadsapi::GetCurrentProcess;
+        use winapi::um::winbase::SetProcessWorkingSetSize;
+
+        let working_set = process_memory_usage().ok_or_else(std::io::Error::last_os_error)? as usize;
+        // Leave headroom above the minimum so the process can keep growing
+        let maximum = working_set.saturating_mul(2);
+        if unsafe { SetProcessWorkingSetSize(GetCurrentProcess(), working_set, maximum) } != 0 {
+            return Ok(());
+        }
+        return Err(std::io::Error::last_os_error());
+    }
+
+    #[allow(unreachable_code)]
+    Err(std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        "memory locking is not supported on this platform",
+    ))
+}
+
+/// Touches `bytes` of heap memory up front so later allocations don't page-fault
+///
+/// Allocates `bytes` through the selected allocator in 64KB blocks, writes one byte
+/// per OS page, then frees the blocks. Allocators that retain freed memory (mimalloc,
+/// or glibc below its trim threshold) serve later allocations from these already
+/// resident pages; call [`lock_memory()`] afterwards to keep them resident.
+///
+/// # Errors
+///
+/// Returns `OutOfMemory` if the allocator can't provide `bytes`; blocks allocated
+/// before the failure are freed.
+///
+/// # Example
+///
+/// ```rust
+/// auto_allocator::prefault_heap(4 << 20).expect("4MB should be available");
+/// ```
+#[cfg(not(target_os = "none"))]
+pub fn prefault_heap(bytes: usize) -> std::io::Result<()> {
+    const BLOCK_SIZE: usize = 64 << 10;
+
+    let page_size = get_page_size_safe().max(1);
+    let layout = Layout::from_size_align(BLOCK_SIZE, page_size.min(BLOCK_SIZE))
+        .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidInput, err))?;
+
+    let block_count = bytes.div_ceil(BLOCK_SIZE);
+    let mut blocks = Vec::new();
+    blocks
+        .try_reserve_exact(block_count)
+        .map_err(|err| std::io::Error::new(std::io::ErrorKind::OutOfMemory, err))?;
+
+    let mut result = Ok(());
+    for _ in 0..block_count {
+        let block = unsafe { GLOBAL.alloc(layout) };
+        if block.is_null() {
+            result = Err(std::io::Error::new(
+                std::io::ErrorKind::OutOfMemory,
+                "allocator could not provide the requested prefault size",
+            ));
+            break;
+        }
+        for offset in (0..BLOCK_SIZE).step_by(page_size) {
+            unsafe { block.add(offset).write_volatile(0) };
+        }
+        blocks.push(block);
+    }
+
+    for block in blocks {
+        unsafe { GLOBAL.dealloc(block, layout) };
+    }
+    result
+}
+
 // WASM environment initialization
 #[cfg(target_arch = "wasm32")]
 use wasm_bindgen::prelude::*;
@@ -0,0 +1,48 @@
+//! Bump allocation mode tests for auto-allocator
+//!
+//! Only built with the `bump` feature, and without `no-global` (the test allocates
+//! through `Vec`, which then bypasses auto-allocator): `cargo test --features bump`
+//!
+//! Bump mode is process-global and other harness threads allocate concurrently,
+//! so everything runs in a single test and `reset_bump()` is never called here.
+
+#![cfg(all(feature = "bump", not(feature = "no-global")))]
+
+use std::hint::black_box;
+
+#[test]
+fn test_bump_mode_lifecycle() {
+    const ARENA: usize = 1 << 20;
+
+    assert_eq!(auto_allocator::bump_arena_usage(), None);
+    assert!(auto_allocator::enable_bump_mode(ARENA));
+    assert!(!auto_allocator::enable_bump_mode(ARENA), "second enable must be rejected");
+
+    let (used_before, capacity) = auto_allocator::bump_arena_usage().unwrap();
+    assert_eq!(capacity, ARENA);
+
+    // Small allocations come from the arena and honor alignment
+    let data: Vec<u64> = black_box((0..128).collect());
+    assert_eq!(data.as_ptr() as usize % std::mem::align_of::<u64>(), 0);
+    let (used_after, _) = auto_allocator::bump_arena_usage().unwrap();
+    assert!(used_after - used_before >= 128 * 8);
+
+    // Freeing arena memory is a no-op
+    drop(data);
+    let (used_after_free, _) = auto_allocator::bump_arena_usage().unwrap();
+    assert!(used_after_free >= used_after);
+
+    // Oversized requests fall back to the selected allocator and still work
+    let big: Vec<u8> = black_box(vec![7; ARENA * 2]);
+    assert!(big.iter().all(|&b| b == 7));
+    drop(big);
+
+    // Over-aligned requests are aligned inside the arena
+    let layout = std::alloc::Layout::from_size_align(64, 256).unwrap();
+    unsafe {
+        let ptr = std::alloc::alloc(layout);
+        assert!(!ptr.is_null());
+        assert_eq!(ptr as usize % 256, 0);
+        std::alloc::dealloc(ptr, layout);
+    }
+}
@@ -0,0 +1,18 @@
+//! Shared fixtures for the auto-allocator integration tests
+
+use auto_allocator::SystemInfo;
+
+/// A release build on a Linux host with `cores` CPUs and `memory_bytes` of RAM
+///
+/// No cgroup CPU or memory limit, so the effective memory is the total.
+pub fn linux_host(cores: usize, memory_bytes: u64) -> SystemInfo {
+    let mut info = auto_allocator::get_allocator_info().system_info.clone();
+    info.os_type = "linux".to_string();
+    info.is_debug = false;
+    info.is_wasm = false;
+    info.cpu_cores = cores;
+    info.cpu_quota_cores = None;
+    info.total_memory_bytes = memory_bytes;
+    info.effective_memory_bytes = memory_bytes;
+    info
+}
@@ -0,0 +1,69 @@
+//! Configuration builder tests for auto-allocator
+//!
+//! By the time a test runs the allocator has been selected, so `apply()` can only
+//! report `AlreadySelected`; validation is exercised through `validate()`.
+
+use auto_allocator::{
+    is_allocator_available, AllocatorProfile, AllocatorType, AutoAllocatorConfig, ConfigError,
+};
+
+#[test]
+fn test_empty_config_is_valid() {
+    assert_eq!(AutoAllocatorConfig::new().validate(), Ok(()));
+}
+
+#[test]
+fn test_apply_after_first_allocation_fails() {
+    // With `no-global` the runtime's allocations bypass auto-allocator; run selection here
+    let _ = auto_allocator::get_allocator_info();
+    let profile_before = auto_allocator::get_allocator_profile();
+    let config = AutoAllocatorConfig::new()
+        .profile(AllocatorProfile::Footprint)
+        .prefer(&[AllocatorType::System]);
+    assert_eq!(config.apply(), Err(ConfigError::AlreadySelected));
+
+    // Nothing was applied
+    assert_eq!(auto_allocator::get_allocator_profile(), profile_before);
+}
+
+#[test]
+fn test_unavailable_preference_rejected() {
+    let config = AutoAllocatorConfig::new().prefer(&[AllocatorType::Custom, AllocatorType::System]);
+    assert_eq!(
+        config.validate(),
+        Err(ConfigError::Unavailable(AllocatorType::Custom))
+    );
+
+    if !is_allocator_available(AllocatorType::Mimalloc) {
+        let config = AutoAllocatorConfig::new().prefer(&[AllocatorType::Mimalloc]);
+        assert_eq!(
+            config.validate(),
+            Err(ConfigError::Unavailable(AllocatorType::Mimalloc))
+        );
+    }
+}
+
+#[test]
+fn test_preferred_and_denied_rejected() {
+    if !is_allocator_available(AllocatorType::Mimalloc) {
+        return; // only reachable when mimalloc is compiled in (release builds)
+    }
+    let config = AutoAllocatorConfig::new()
+        .deny(&[AllocatorType::Mimalloc])
+        .prefer(&[AllocatorType::Mimalloc, AllocatorType::System]);
+    assert_eq!(
+        config.validate(),
+        Err(ConfigError::PreferredAndDenied(AllocatorType::Mimalloc))
+    );
+}
+
+#[test]
+fn test_too_many_preferences_rejected() {
+    let config = AutoAllocatorConfig::new().prefer(&[AllocatorType::System; 8]);
+    assert_eq!(config.validate(), Err(ConfigError::TooManyPreferences(8)));
+    assert!(config
+        .validate()
+        .unwrap_err()
+        .to_string()
+        .contains("at most 7"));
+}
@@ -0,0 +1,67 @@
+//! Custom allocator backend tests for auto-allocator
+//!
+//! Registration has to beat the Rust runtime's first allocation, so it runs from an
+//! `.init_array` constructor - Linux only. Kept in its own test binary because the
+//! registered backend is process-global.
+
+#![cfg(target_os = "linux")]
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+struct CountingAllocator;
+
+static CUSTOM_ALLOCS: AtomicUsize = AtomicUsize::new(0);
+static REGISTERED: AtomicBool = AtomicBool::new(false);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        CUSTOM_ALLOCS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+static COUNTING: CountingAllocator = CountingAllocator;
+
+extern "C" fn register_before_main() {
+    REGISTERED.store(
+        auto_allocator::register_custom_allocator(&COUNTING),
+        Ordering::SeqCst,
+    );
+}
+
+#[used]
+#[link_section = ".init_array"]
+static REGISTER_BEFORE_MAIN: extern "C" fn() = register_before_main;
+
+#[test]
+fn test_custom_allocator_backend() {
+    assert!(REGISTERED.load(Ordering::SeqCst));
+
+    let info = auto_allocator::get_allocator_info();
+    assert_eq!(info.allocator_type, auto_allocator::AllocatorType::Custom);
+    assert_eq!(info.reason_detail, auto_allocator::SelectionReason::Custom);
+    assert_eq!(auto_allocator::allocator_id(), 6);
+    assert_eq!(auto_allocator::allocator_name(), "custom");
+
+    // Allocations are delegated to the registered allocator; called directly so this
+    // also holds under `no-global`, where `Vec` doesn't go through auto-allocator
+    let before = CUSTOM_ALLOCS.load(Ordering::Relaxed);
+    let layout = Layout::from_size_align(1024, 8).unwrap();
+    unsafe {
+        let ptr = auto_allocator::RuntimeAllocator.alloc(layout);
+        assert!(!ptr.is_null());
+        assert!(CUSTOM_ALLOCS.load(Ordering::Relaxed) > before);
+        auto_allocator::RuntimeAllocator.dealloc(ptr, layout);
+    }
+
+    // A second registration is rejected
+    assert!(!auto_allocator::register_custom_allocator(&COUNTING));
+
+    let (is_optimal, _) = auto_allocator::check_allocator_optimization();
+    assert!(is_optimal);
+}
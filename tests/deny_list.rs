@@ -0,0 +1,38 @@
+//! Allocator deny-list tests for auto-allocator
+//!
+//! Kept in their own test binary because `set_denied_allocators` is process-global.
+
+use auto_allocator::{set_denied_allocators, AllocatorType, SelectionReason};
+
+#[test]
+fn test_denied_allocators_are_never_recommended() {
+    // With `no-global` the runtime's allocations bypass auto-allocator; run selection here
+    let _ = auto_allocator::get_allocator_info();
+
+    // Too late for the startup selection; still applied to recommendations
+    assert!(!set_denied_allocators(&[
+        AllocatorType::MimallocSecure,
+        AllocatorType::Mimalloc
+    ]));
+
+    let (recommended, reason) = auto_allocator::get_recommended_allocator();
+    assert_ne!(recommended, AllocatorType::Mimalloc);
+    assert_ne!(recommended, AllocatorType::MimallocSecure);
+
+    // Where mimalloc would have been recommended, the reason names the skipped allocator
+    if reason.contains("deny list") {
+        assert_eq!(recommended, AllocatorType::System);
+        assert!(reason.starts_with("mimalloc"));
+    }
+
+    // The system allocator is the final fallback and ignores the deny list
+    set_denied_allocators(&[AllocatorType::System]);
+    let (_, reason) = auto_allocator::get_recommended_allocator();
+    assert!(!reason.contains("deny list"));
+
+    set_denied_allocators(&[]);
+    assert!(!matches!(
+        auto_allocator::get_allocator_info().reason_detail,
+        SelectionReason::Denied { .. }
+    ));
+}
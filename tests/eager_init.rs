@@ -0,0 +1,24 @@
+//! Pre-main selection tests for auto-allocator
+//!
+//! Only built with the `eager-init` and `no-global` features:
+//! `cargo test --features eager-init,no-global`
+//!
+//! With the global allocator installed, the runtime's own pre-main allocations select
+//! anyway; under `no-global` nothing allocates through auto-allocator, so only the
+//! constructor can have selected before the test runs.
+
+#![cfg(all(feature = "eager-init", feature = "no-global"))]
+
+#[test]
+fn test_selection_done_before_main() {
+    // Read before anything in this process can trigger selection: selection_duration()
+    // only reports, and this is the binary's only test
+    assert!(auto_allocator::selection_duration().is_some());
+
+    // Selection already happened in the constructor, so registering now is too late
+    static SYSTEM: std::alloc::System = std::alloc::System;
+    assert!(!auto_allocator::register_custom_allocator(&SYSTEM));
+
+    let info = auto_allocator::get_allocator_info();
+    assert_eq!(info.allocator_type, auto_allocator::get_allocator_type());
+}
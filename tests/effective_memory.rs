@@ -0,0 +1,46 @@
+//! Effective-memory selection tests for auto-allocator
+//!
+//! Uses synthetic `SystemInfo` values so a large host with a small container limit
+//! can be exercised on any machine.
+
+use auto_allocator::{AllocatorType, SelectionReason, SystemInfo};
+
+mod common;
+
+/// A release-mode 64-core Linux host with 256GB of RAM
+fn large_host() -> SystemInfo {
+    common::linux_host(64, 256u64 << 30)
+}
+
+#[test]
+fn test_large_host_without_limit_prefers_mimalloc() {
+    let (allocator_type, reason) = auto_allocator::get_recommended_allocator_for(&large_host());
+    assert_eq!(allocator_type, AllocatorType::Mimalloc);
+    assert!(matches!(
+        reason,
+        SelectionReason::MultiCore { cores: 64, .. }
+    ));
+}
+
+#[test]
+fn test_small_container_on_large_host_uses_system() {
+    let mut info = large_host();
+    info.effective_memory_bytes = 256u64 << 20;
+
+    let (allocator_type, reason) = auto_allocator::get_recommended_allocator_for(&info);
+    assert_eq!(allocator_type, AllocatorType::System);
+    assert_eq!(
+        reason,
+        SelectionReason::LowMemory {
+            effective_memory_bytes: 256u64 << 20
+        }
+    );
+    assert!(reason.to_string().contains("256MB"));
+}
+
+#[test]
+fn test_effective_memory_never_exceeds_total() {
+    let info = &auto_allocator::get_allocator_info().system_info;
+    assert!(info.effective_memory_bytes <= info.total_memory_bytes);
+    assert!(info.effective_memory_bytes > 0);
+}
@@ -0,0 +1,97 @@
+//! Selection log format tests for auto-allocator
+//!
+//! The selection line is written on the first allocation, before `main`, so each
+//! test re-runs this test binary (`--list` only) with the format set and reads its stderr.
+
+#![cfg(all(unix, not(feature = "no-log")))]
+
+use std::process::Command;
+
+fn selection_line(log_format: Option<&str>) -> String {
+    selection_line_with(log_format, None)
+}
+
+fn selection_line_with(log_format: Option<&str>, log: Option<&str>) -> String {
+    let mut command = Command::new(std::env::current_exe().unwrap());
+    command.arg("--list");
+    match log_format {
+        Some(format) => command.env("AUTO_ALLOCATOR_LOG_FORMAT", format),
+        None => command.env_remove("AUTO_ALLOCATOR_LOG_FORMAT"),
+    };
+    match log {
+        Some(log) => command.env("AUTO_ALLOCATOR_LOG", log),
+        None => command.env_remove("AUTO_ALLOCATOR_LOG"),
+    };
+    let output = command.output().unwrap();
+    String::from_utf8(output.stderr)
+        .unwrap()
+        .lines()
+        .next()
+        .unwrap_or_default()
+        .to_string()
+}
+
+#[test]
+fn test_default_log_format_is_human_readable() {
+    let line = selection_line(None);
+    assert!(line.starts_with("[INFO] Auto-allocator: "), "{}", line);
+}
+
+#[test]
+fn test_json_log_format() {
+    let line = selection_line(Some("json"));
+    assert!(line.starts_with("{\"allocator\":\""), "{}", line);
+    assert!(line.ends_with('}'), "{}", line);
+    assert!(line.contains(",\"reason\":\""), "{}", line);
+
+    let info = &auto_allocator::get_allocator_info().system_info;
+    assert!(
+        line.contains(&format!("\"cpu_cores\":{}", info.cpu_cores)),
+        "{}",
+        line
+    );
+    assert!(
+        line.contains(&format!("\"total_ram_bytes\":{}", info.total_memory_bytes)),
+        "{}",
+        line
+    );
+}
+
+#[test]
+fn test_log_disabled_by_env() {
+    for value in ["off", "0", "FALSE", "none"] {
+        let line = selection_line_with(None, Some(value));
+        assert!(!line.contains("Auto-allocator"), "{}: {}", value, line);
+        assert!(!line.contains("\"allocator\""), "{}: {}", value, line);
+    }
+    let line = selection_line_with(Some("json"), Some("off"));
+    assert!(!line.contains("\"allocator\""), "{}", line);
+
+    // Any other value keeps the default
+    let line = selection_line_with(None, Some("on"));
+    assert!(line.starts_with("[INFO] Auto-allocator: "), "{}", line);
+}
+
+#[test]
+fn test_errors_only_log_skips_routine_selection() {
+    // No probe failure here, so the selection is routine and nothing is written
+    let info = auto_allocator::get_allocator_info();
+    if info.reason_detail == auto_allocator::SelectionReason::ProbeFailed {
+        return;
+    }
+    for value in ["errors", "ERRORS"] {
+        let line = selection_line_with(None, Some(value));
+        assert!(!line.contains("Auto-allocator"), "{}: {}", value, line);
+    }
+}
+
+#[test]
+fn test_will_log_selection() {
+    // Follows the environment of this process; Unix always has the stderr line
+    let disabled = std::env::var("AUTO_ALLOCATOR_LOG").is_ok_and(|value| {
+        ["off", "0", "false", "none", "errors"]
+            .iter()
+            .any(|off| value.eq_ignore_ascii_case(off))
+    });
+    assert_eq!(auto_allocator::will_log_selection(), !disabled);
+}
@@ -0,0 +1,27 @@
+//! Memory locking tests for auto-allocator
+//!
+//! Kept in their own test binary because `lock_memory` pins the whole process.
+
+use std::io::ErrorKind;
+
+#[test]
+fn test_lock_memory_succeeds_or_reports_why() {
+    match auto_allocator::lock_memory() {
+        Ok(()) => {
+            // Locked pages keep working as ordinary heap memory
+            auto_allocator::prefault_heap(1 << 20).unwrap();
+            let data: Vec<u64> = (0..1000).collect();
+            assert_eq!(data.iter().sum::<u64>(), 499_500);
+        }
+        Err(err) => assert!(
+            matches!(
+                err.kind(),
+                ErrorKind::PermissionDenied
+                    | ErrorKind::OutOfMemory
+                    | ErrorKind::WouldBlock
+                    | ErrorKind::Unsupported
+            ),
+            "unexpected lock_memory error: {err}"
+        ),
+    }
+}
@@ -0,0 +1,27 @@
+//! mimalloc tunables tests for auto-allocator
+//!
+//! Only built with the `mimalloc-options` feature: `cargo test --features mimalloc-options`
+
+#![cfg(feature = "mimalloc-options")]
+
+use auto_allocator::{configure_mimalloc, get_allocator_info, AllocatorType, MimallocOptions};
+
+#[test]
+fn test_configure_mimalloc_follows_active_backend() {
+    let applied = configure_mimalloc(MimallocOptions {
+        eager_commit: Some(true),
+        reset_delay_ms: Some(250),
+        purge_delay_ms: None,
+        allow_large_os_pages: Some(false),
+    });
+
+    let is_mimalloc = matches!(
+        get_allocator_info().allocator_type,
+        AllocatorType::Mimalloc | AllocatorType::MimallocSecure
+    );
+    assert_eq!(applied, is_mimalloc);
+
+    // Allocations keep working with the new options
+    let data: Vec<u64> = (0..10_000).collect();
+    assert_eq!(data.iter().sum::<u64>(), 49_995_000);
+}
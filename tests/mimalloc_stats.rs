@@ -0,0 +1,51 @@
+//! mimalloc backend stats tests for auto-allocator
+//!
+//! Only built with the `mimalloc-stats` feature: `cargo test --features mimalloc-stats`
+
+#![cfg(feature = "mimalloc-stats")]
+
+use auto_allocator::{get_allocator_type, thread_backend_stats, AllocatorType, RuntimeAllocator};
+use std::alloc::{GlobalAlloc, Layout};
+use std::thread;
+
+fn mimalloc_active() -> bool {
+    matches!(
+        get_allocator_type(),
+        AllocatorType::Mimalloc | AllocatorType::MimallocSecure
+    )
+}
+
+#[test]
+fn test_thread_backend_stats_only_with_mimalloc() {
+    assert_eq!(thread_backend_stats().is_some(), mimalloc_active());
+}
+
+#[test]
+fn test_thread_backend_stats_reflect_calling_thread() {
+    if !mimalloc_active() {
+        return;
+    }
+
+    // A fresh thread's heap grows by what that thread keeps live; the blocks come from
+    // RuntimeAllocator directly, so this also holds under `no-global`
+    let (before, after) = thread::spawn(|| {
+        let layout = Layout::new::<[u8; 64]>();
+        let before = thread_backend_stats().unwrap();
+        let mut blocks = [core::ptr::null_mut(); 1000];
+        for block in blocks.iter_mut() {
+            *block = unsafe { RuntimeAllocator.alloc(layout) };
+            assert!(!block.is_null());
+        }
+        let after = thread_backend_stats().unwrap();
+        for &block in blocks.iter() {
+            unsafe { RuntimeAllocator.dealloc(block, layout) };
+        }
+        (before, after)
+    })
+    .join()
+    .unwrap();
+
+    assert!(after.block_count >= before.block_count + 1000);
+    assert!(after.used_bytes >= before.used_bytes + 64 * 1000);
+    assert!(after.committed_bytes >= after.used_bytes);
+}
@@ -0,0 +1,29 @@
+//! `no-global` feature tests for auto-allocator
+//!
+//! Only built with the `no-global` feature: `cargo test --features no-global`
+
+#![cfg(feature = "no-global")]
+
+#[test]
+fn test_no_global_reports_inactive_selection() {
+    assert!(!auto_allocator::global_allocator_is_auto());
+
+    let info = auto_allocator::get_allocator_info();
+    assert!(info.reason.contains("not the global allocator"));
+
+    // The explicit helpers still route through auto-allocator's backend
+    let ptr = auto_allocator::alloc_aligned(256, 32);
+    assert!(!ptr.is_null());
+    unsafe { auto_allocator::dealloc_aligned(ptr, 256, 32) };
+}
+
+#[test]
+fn test_dry_run_matches_reported_selection() {
+    let (allocator_type, reason) = auto_allocator::dry_run_selection();
+    let info = auto_allocator::get_allocator_info();
+    assert_eq!(allocator_type, info.allocator_type);
+    assert_eq!(reason, info.reason_detail);
+
+    // Nothing is latched: dry runs keep returning the same decision
+    assert_eq!(auto_allocator::dry_run_selection().0, allocator_type);
+}
@@ -0,0 +1,39 @@
+//! no-libc tests for auto-allocator
+//!
+//! Only built with the `no-libc` feature on Linux: `cargo test --features no-libc`
+
+#![cfg(all(feature = "no-libc", target_os = "linux"))]
+
+use auto_allocator::{enabled_features, get_allocator_info, memory_snapshot, selection_inputs};
+
+#[test]
+fn test_no_libc_feature_is_reported() {
+    assert!(enabled_features().contains(&"no-libc"));
+}
+
+#[test]
+fn test_selection_runs_on_raw_syscalls() {
+    // Selection went through the raw sched_getaffinity / sysinfo path
+    let data: Vec<u64> = (0..1024).collect();
+    assert_eq!(data.len(), 1024);
+
+    let info = get_allocator_info();
+    assert!(info.system_info.cpu_cores >= 1);
+    assert!(memory_snapshot().total_bytes > 0);
+}
+
+#[test]
+fn test_raw_core_count_follows_affinity_mask() {
+    // available_parallelism() also counts the affinity mask
+    let inputs = selection_inputs();
+    let expected = inputs
+        .cpu_quota_cores
+        .map_or(inputs.available_parallelism, |quota| quota.min(inputs.available_parallelism));
+    assert_eq!(inputs.selector_cores, expected);
+}
+
+#[test]
+fn test_raw_total_memory_matches_meminfo() {
+    // sysinfo's totalram and /proc/meminfo's MemTotal are the same kernel counter
+    assert_eq!(selection_inputs().total_memory_bytes, memory_snapshot().total_bytes);
+}
@@ -0,0 +1,35 @@
+//! `no-log` feature tests for auto-allocator
+//!
+//! Only built with the `no-log` feature: `cargo test --features no-log`
+
+#![cfg(feature = "no-log")]
+
+#[test]
+fn test_no_log_reports_summary_reason() {
+    assert!(!auto_allocator::will_log_selection());
+
+    let info = auto_allocator::get_allocator_info();
+    // Same field type as without `no-log`, so enabling it never breaks other crates
+    let reason: &String = &info.reason;
+    assert!(!reason.is_empty());
+
+    assert!(auto_allocator::enabled_features().contains(&"no-log"));
+
+    // Same decision as the structured reason, without its detected values
+    if info.reason_detail == auto_allocator::SelectionReason::Debug {
+        assert!(reason.starts_with("debug build - compile-time selected"), "{}", reason);
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn test_no_log_writes_nothing_to_stderr() {
+    // The selection line would be written before `main`; re-run this binary to see it
+    let output = std::process::Command::new(std::env::current_exe().unwrap())
+        .arg("--list")
+        .env_remove("AUTO_ALLOCATOR_LOG")
+        .output()
+        .unwrap();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(!stderr.contains("Auto-allocator:"), "{}", stderr);
+}
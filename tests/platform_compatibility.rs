@@ -5,6 +5,8 @@
 
 use auto_allocator::get_allocator_info;
 
+mod common;
+
 #[test]
 fn test_allocator_selection_consistency() {
     // Test that allocator selection is consistent across multiple calls
@@ -13,6 +15,13 @@ fn test_allocator_selection_consistency() {
 
     assert_eq!(info1.allocator_type, info2.allocator_type);
     assert_eq!(info1.reason, info2.reason);
+
+    // The human-readable reason is the Display form of the structured one
+    assert_eq!(info1.reason_detail, info2.reason_detail);
+    #[cfg(not(any(feature = "no-global", feature = "no-log")))]
+    assert_eq!(info1.reason, info1.reason_detail.to_string());
+    #[cfg(all(feature = "no-global", not(feature = "no-log")))]
+    assert!(info1.reason.starts_with(&info1.reason_detail.to_string()));
 }
 
 #[test]
@@ -32,6 +41,7 @@ fn test_platform_specific_allocator_selection() {
         // Debug builds should prefer system allocator for fast compilation
         assert_eq!(info.allocator_type, auto_allocator::AllocatorType::System);
         assert!(info.reason.contains("Debug") || info.reason.contains("debug"));
+        assert_eq!(info.reason_detail, auto_allocator::SelectionReason::Debug);
     }
 
     #[cfg(all(
@@ -75,6 +85,22 @@ fn test_platform_specific_allocator_selection() {
     }
 }
 
+#[cfg(all(target_os = "linux", target_env = "musl", not(debug_assertions)))]
+#[test]
+fn test_musl_release_allocator_selection() {
+    // musl release builds follow the same rules as glibc: mimalloc on multi-core systems
+    let info = get_allocator_info();
+
+    if info.system_info.cpu_cores >= 2 {
+        assert!(matches!(
+            info.allocator_type,
+            auto_allocator::AllocatorType::Mimalloc | auto_allocator::AllocatorType::MimallocSecure
+        ));
+    } else {
+        assert_eq!(info.allocator_type, auto_allocator::AllocatorType::System);
+    }
+}
+
 #[test]
 fn test_memory_allocation_basic() {
     // Test basic memory allocation works with selected allocator
@@ -136,6 +162,307 @@ fn test_system_info_detection() {
 
     // Verify WASM flag
     assert_eq!(info.system_info.is_wasm, cfg!(target_arch = "wasm32"));
+
+    // SSE2 is part of the x86_64 baseline, NEON of the aarch64 baseline
+    let features = info.system_info.cpu_features;
+    #[cfg(target_arch = "x86_64")]
+    assert!(features.sse2);
+    #[cfg(target_arch = "aarch64")]
+    assert!(features.neon);
+    #[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+    assert!(!features.sse2 && !features.avx && !features.avx2 && !features.avx512f);
+    #[cfg(not(target_arch = "aarch64"))]
+    assert!(!features.neon);
+
+    // Transparent huge pages are only detected on Linux
+    #[cfg(target_os = "linux")]
+    {
+        let thp = std::fs::read_to_string("/sys/kernel/mm/transparent_hugepage/enabled")
+            .map(|mode| !mode.contains("[never]"))
+            .unwrap_or(false);
+        assert_eq!(info.system_info.huge_pages_available, thp);
+
+        let mode = std::fs::read_to_string("/sys/kernel/mm/transparent_hugepage/enabled")
+            .ok()
+            .and_then(|modes| {
+                let active = modes.split_once('[')?.1.split_once(']')?.0.to_string();
+                Some(active)
+            });
+        let expected = match mode.as_deref() {
+            Some("always") => Some(auto_allocator::ThpMode::Always),
+            Some("madvise") => Some(auto_allocator::ThpMode::Madvise),
+            Some("never") => Some(auto_allocator::ThpMode::Never),
+            _ => None,
+        };
+        assert_eq!(info.system_info.thp_mode, expected);
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        assert!(!info.system_info.huge_pages_available);
+        assert_eq!(info.system_info.thp_mode, None);
+    }
+
+    // A cgroup CPU quota, when it applies, is what cpu_cores reports
+    if let Some(quota_cores) = info.system_info.cpu_quota_cores {
+        assert_eq!(info.system_info.cpu_cores, quota_cores);
+        assert!(quota_cores >= 1);
+    }
+    #[cfg(not(target_os = "linux"))]
+    assert!(info.system_info.cpu_quota_cores.is_none());
+
+    // Core topology split never exceeds the usable core count
+    let sys = &info.system_info;
+    assert!(sys.performance_cores >= 1);
+    assert!(sys.performance_cores + sys.efficiency_cores <= sys.cpu_cores);
+
+    // Hypervisor detection only exists on x86, and always names something
+    if let Some(name) = info.system_info.hypervisor {
+        assert!(!name.is_empty());
+    }
+    #[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+    assert!(info.system_info.hypervisor.is_none());
+
+    // PID 1 mirrors getpid() on Unix and is never reported elsewhere
+    assert_eq!(info.system_info.is_pid1, cfg!(unix) && std::process::id() == 1);
+
+    // Free swap can't exceed total swap
+    assert!(info.system_info.free_swap_bytes <= info.system_info.total_swap_bytes);
+
+    // Available memory and commit charge stay within their totals
+    assert!(info.system_info.available_memory_bytes <= info.system_info.total_memory_bytes);
+    assert!(info.system_info.available_commit_bytes <= info.system_info.commit_limit_bytes);
+    #[cfg(any(target_os = "linux", target_os = "macos"))]
+    assert!(info.system_info.available_memory_bytes > 0);
+    #[cfg(target_os = "windows")]
+    assert!(info.system_info.commit_limit_bytes >= info.system_info.total_memory_bytes);
+    #[cfg(not(target_os = "windows"))]
+    assert_eq!(info.system_info.commit_limit_bytes, 0);
+
+    // Cache hierarchy grows outward where all levels are reported
+    let caches = info.system_info.cache_sizes;
+    if let (Some(l1d), Some(l2)) = (caches.l1d, caches.l2) {
+        assert!(l1d > 0 && l1d <= l2);
+    }
+    #[cfg(any(target_os = "none", target_arch = "wasm32"))]
+    assert_eq!(caches, auto_allocator::CacheSizes::default());
+
+    // Page size is always a power of two, at least 4KB on hosted platforms
+    let page_size = info.system_info.page_size;
+    assert!(page_size.is_power_of_two());
+    #[cfg(not(target_os = "none"))]
+    assert!(page_size >= 4096);
+}
+
+#[test]
+fn test_backend_version() {
+    let version = auto_allocator::backend_version();
+    match auto_allocator::get_allocator_type() {
+        auto_allocator::AllocatorType::Mimalloc | auto_allocator::AllocatorType::MimallocSecure => {
+            let version = version.expect("mimalloc should report its version");
+            let parts: Vec<&str> = version.split('.').collect();
+            assert_eq!(parts.len(), 3, "unexpected version format: {}", version);
+            assert!(parts.iter().all(|p| p.parse::<u32>().is_ok()));
+        }
+        _ => assert!(version.is_none()),
+    }
+}
+
+#[test]
+fn test_format_memory_size_rounding() {
+    use auto_allocator::format_memory_size;
+
+    // Exact values and halves are unchanged
+    assert_eq!(format_memory_size(1023), "1023B");
+    assert_eq!(format_memory_size(1610612736), "1.5GB");
+
+    // One decimal, rounded to nearest rather than truncated
+    assert_eq!(format_memory_size(1932735283), "1.8GB"); // 1.79999...GB
+    assert_eq!(format_memory_size(1074), "1KB"); // 1.0488KB
+    assert_eq!(format_memory_size(1075), "1KB"); // 1.0498KB
+    assert_eq!(format_memory_size(1076), "1.1KB"); // 1.0508KB
+    assert_eq!(format_memory_size(2047), "2KB"); // 1.999KB
+
+    // Rounding up to 1024 of a unit moves to the next unit
+    assert_eq!(format_memory_size((1u64 << 20) - 1), "1MB");
+    assert_eq!(format_memory_size((1u64 << 30) - (1u64 << 20)), "1023MB");
+    assert_eq!(format_memory_size(u64::MAX), "16384PB");
+}
+
+#[test]
+fn test_process_memory_usage() {
+    let rss = auto_allocator::process_memory_usage();
+
+    #[cfg(any(target_os = "linux", target_os = "android", target_os = "macos", target_os = "windows"))]
+    {
+        let rss = rss.expect("RSS should be available on this platform");
+        assert!(rss > 0);
+        assert!(rss < auto_allocator::get_allocator_info().system_info.total_memory_bytes);
+    }
+    #[cfg(not(any(target_os = "linux", target_os = "android", target_os = "macos", target_os = "windows")))]
+    assert!(rss.is_none());
+}
+
+#[test]
+fn test_memory_headroom() {
+    let headroom = auto_allocator::memory_headroom_bytes();
+
+    #[cfg(target_os = "linux")]
+    assert!(headroom.is_some(), "headroom should be available on Linux");
+
+    // Capped by available memory, so never above physical memory
+    if let Some(headroom) = headroom {
+        assert!(headroom <= auto_allocator::memory_snapshot().total_bytes);
+    }
+}
+
+#[test]
+fn test_aligned_allocation() {
+    for align in [8, 64, 4096] {
+        let ptr = auto_allocator::alloc_aligned(1000, align);
+        assert!(!ptr.is_null());
+        assert_eq!(ptr as usize % align, 0);
+        unsafe {
+            ptr.write_bytes(0xAB, 1000);
+            assert_eq!(*ptr.add(999), 0xAB);
+            auto_allocator::dealloc_aligned(ptr, 1000, align);
+        }
+    }
+
+    // Invalid requests return null instead of panicking
+    assert!(auto_allocator::alloc_aligned(64, 0).is_null());
+    assert!(auto_allocator::alloc_aligned(64, 48).is_null());
+    assert!(auto_allocator::alloc_aligned(0, 64).is_null());
+    assert!(auto_allocator::alloc_aligned(usize::MAX, 64).is_null());
+}
+
+#[test]
+fn test_prefault_heap() {
+    assert!(auto_allocator::prefault_heap(0).is_ok());
+    assert!(auto_allocator::prefault_heap(4 << 20).is_ok());
+
+    let err = auto_allocator::prefault_heap(usize::MAX).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::OutOfMemory);
+}
+
+#[test]
+fn test_realloc_preserves_contents() {
+    use std::alloc::{alloc, dealloc, realloc, Layout};
+
+    unsafe {
+        let layout = Layout::from_size_align(100, 64).unwrap();
+        let ptr = alloc(layout);
+        assert!(!ptr.is_null());
+        for i in 0..100 {
+            *ptr.add(i) = i as u8;
+        }
+
+        // Grow: contents and alignment survive, in place or moved
+        let grown = realloc(ptr, layout, 10_000);
+        assert!(!grown.is_null());
+        assert_eq!(grown as usize % 64, 0);
+        assert!((0..100).all(|i| *grown.add(i) == i as u8));
+
+        // Shrink: the retained prefix survives
+        let grown_layout = Layout::from_size_align(10_000, 64).unwrap();
+        let shrunk = realloc(grown, grown_layout, 40);
+        assert!(!shrunk.is_null());
+        assert_eq!(shrunk as usize % 64, 0);
+        assert!((0..40).all(|i| *shrunk.add(i) == i as u8));
+
+        dealloc(shrunk, Layout::from_size_align(40, 64).unwrap());
+    }
+
+    // Typical Vec growth and shrink_to_fit go through the same path
+    let mut values: Vec<u32> = Vec::with_capacity(1);
+    values.extend(0..50_000);
+    values.truncate(10);
+    values.shrink_to_fit();
+    assert_eq!(values, (0..10).collect::<Vec<u32>>());
+}
+
+#[test]
+fn test_allocator_id_and_name() {
+    let (expected_id, expected_name) = match auto_allocator::get_allocator_type() {
+        auto_allocator::AllocatorType::System => (1, "system"),
+        auto_allocator::AllocatorType::Mimalloc => (2, "mimalloc"),
+        auto_allocator::AllocatorType::EmbeddedHeap => (4, "embedded-alloc"),
+        auto_allocator::AllocatorType::MimallocSecure => (5, "mimalloc-secure"),
+        auto_allocator::AllocatorType::Custom => (6, "custom"),
+    };
+    assert_eq!(auto_allocator::allocator_id(), expected_id);
+    assert_eq!(auto_allocator::allocator_name(), expected_name);
+}
+
+#[test]
+fn test_allocator_type_id_round_trip() {
+    use auto_allocator::AllocatorType;
+
+    for allocator_type in [
+        AllocatorType::System,
+        AllocatorType::Mimalloc,
+        AllocatorType::EmbeddedHeap,
+        AllocatorType::MimallocSecure,
+        AllocatorType::Custom,
+    ] {
+        assert_eq!(AllocatorType::from_id(allocator_type.id()), Some(allocator_type));
+    }
+
+    // Uninitialized, reserved (jemalloc) and unknown IDs don't map to a type
+    assert_eq!(AllocatorType::from_id(0), None);
+    assert_eq!(AllocatorType::from_id(3), None);
+    assert_eq!(AllocatorType::from_id(7), None);
+
+    assert_eq!(
+        AllocatorType::from_id(auto_allocator::allocator_id()),
+        Some(auto_allocator::get_allocator_type())
+    );
+}
+
+#[cfg(not(feature = "no-global"))]
+#[test]
+fn test_global_allocator_is_auto() {
+    assert!(auto_allocator::global_allocator_is_auto());
+    assert!(!auto_allocator::get_allocator_info().reason.contains("warning"));
+}
+
+#[test]
+fn test_secure_mode_active() {
+    let secure =
+        auto_allocator::get_allocator_type() == auto_allocator::AllocatorType::MimallocSecure;
+    assert_eq!(
+        auto_allocator::is_secure_mode_active(),
+        secure && auto_allocator::global_allocator_is_auto()
+    );
+
+    // Debug builds always use the system allocator, whatever the features ask for
+    #[cfg(debug_assertions)]
+    assert!(!auto_allocator::is_secure_mode_active());
+}
+
+#[test]
+fn test_selection_duration() {
+    // Allocate so the first-allocation selection has certainly run
+    let _data = std::hint::black_box(Box::new([0u8; 64]));
+
+    let first = auto_allocator::selection_duration();
+    #[cfg(not(target_arch = "wasm32"))]
+    assert!(first.is_some(), "selection happened during startup");
+    assert!(first.unwrap_or_default() < std::time::Duration::from_secs(5));
+
+    // Measured once, not on every call
+    assert_eq!(auto_allocator::selection_duration(), first);
+}
+
+#[test]
+fn test_explain_selection() {
+    let explanation = auto_allocator::explain_selection();
+
+    // Exactly one check decides, and the summary names the active allocator
+    assert_eq!(explanation.matches("yes - decides").count(), 1);
+    assert!(explanation.contains(&format!("Active: {}", auto_allocator::allocator_name())));
+
+    #[cfg(debug_assertions)]
+    assert!(explanation.contains("debug build: yes - decides"));
 }
 
 #[test]
@@ -179,6 +506,16 @@ fn test_allocator_optimization_check() {
     }
 }
 
+#[test]
+fn test_recommended_allocator_cached() {
+    let (cached_type, cached_reason) = auto_allocator::get_recommended_allocator_cached();
+    let (snapshot_type, snapshot_reason) =
+        auto_allocator::get_recommended_allocator_for(&get_allocator_info().system_info);
+
+    assert_eq!(cached_type, snapshot_type);
+    assert_eq!(cached_reason, snapshot_reason.to_string());
+}
+
 #[test]
 fn test_concurrent_access() {
     use std::thread;
@@ -207,3 +544,243 @@ fn test_concurrent_access() {
         .iter()
         .all(|&allocator| allocator == first_allocator));
 }
+
+#[test]
+fn test_qnx_uses_native_allocator() {
+    let mut info = common::linux_host(8, 16 << 30);
+    info.os_type = "nto".to_string();
+
+    let (allocator_type, reason) = auto_allocator::get_recommended_allocator_for(&info);
+    assert_eq!(allocator_type, auto_allocator::AllocatorType::System);
+    assert_eq!(
+        reason,
+        auto_allocator::SelectionReason::PlatformPolicy { os: "nto" }
+    );
+    assert!(reason.to_string().starts_with("QNX Neutrino platform"));
+}
+
+#[test]
+fn test_haiku_and_solaris_reasons() {
+    let mut info = common::linux_host(8, 16 << 30);
+
+    info.os_type = "haiku".to_string();
+    let (allocator_type, reason) = auto_allocator::get_recommended_allocator_for(&info);
+    assert_eq!(allocator_type, auto_allocator::AllocatorType::System);
+    assert!(reason.to_string().starts_with("Haiku platform"));
+
+    // The libumem check inspects this process, which on non-Solaris hosts never has it
+    info.os_type = "illumos".to_string();
+    let (_, reason) = auto_allocator::get_recommended_allocator_for(&info);
+    #[cfg(not(any(target_os = "solaris", target_os = "illumos")))]
+    assert!(reason.to_string().contains("libumem not linked"));
+    #[cfg(any(target_os = "solaris", target_os = "illumos"))]
+    assert!(reason.to_string().contains("libumem"));
+}
+
+#[test]
+fn test_system_info_env() {
+    let env = auto_allocator::system_info_env();
+    let value = |key: &str| {
+        env.iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.clone())
+            .unwrap_or_else(|| panic!("missing {}", key))
+    };
+    let sys = &get_allocator_info().system_info;
+
+    assert!(env.iter().all(|(key, _)| key.starts_with("AUTO_ALLOC_")));
+    assert_eq!(value("AUTO_ALLOC_TYPE"), auto_allocator::allocator_name());
+    assert_eq!(value("AUTO_ALLOC_OS"), sys.os_type);
+    assert_eq!(value("AUTO_ALLOC_CORES"), sys.cpu_cores.to_string());
+    assert_eq!(value("AUTO_ALLOC_TOTAL_MEM"), sys.total_memory_bytes.to_string());
+    assert_eq!(value("AUTO_ALLOC_DEBUG"), if cfg!(debug_assertions) { "1" } else { "0" });
+    assert_eq!(
+        value("AUTO_ALLOC_CPU_QUOTA").is_empty(),
+        sys.cpu_quota_cores.is_none()
+    );
+    assert_eq!(value("AUTO_ALLOC_PID1"), if sys.is_pid1 { "1" } else { "0" });
+}
+
+#[test]
+fn test_selected_allocator_has_dispatch_path() {
+    // Selection never settles on an allocator whose backend was compiled out
+    let selected = auto_allocator::get_allocator_type();
+    assert!(
+        auto_allocator::is_allocator_available(selected),
+        "{:?} was selected but has no dispatch arm in this build",
+        selected
+    );
+
+    // A value allocated through it is really usable
+    let boxed = std::hint::black_box(Box::new([7u8; 256]));
+    assert!(boxed.iter().all(|&b| b == 7));
+}
+
+#[test]
+fn test_memory_snapshot_is_coherent() {
+    let memory = auto_allocator::memory_snapshot();
+    assert!(memory.total_bytes > 0);
+    assert!(memory.available_bytes <= memory.total_bytes);
+
+    // SystemInfo is populated from the same snapshot
+    let sys = &get_allocator_info().system_info;
+    assert!(sys.available_memory_bytes <= sys.total_memory_bytes);
+    #[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
+    assert_eq!(memory.total_bytes, sys.total_memory_bytes);
+}
+
+#[test]
+fn test_enabled_features() {
+    let features = auto_allocator::enabled_features();
+    assert_eq!(features.contains(&"stats"), cfg!(feature = "stats"));
+    assert_eq!(features.contains(&"log"), cfg!(feature = "log"));
+    assert_eq!(features.contains(&"no-global"), cfg!(feature = "no-global"));
+    assert!(!features.contains(&""));
+
+    let explanation = auto_allocator::explain_selection();
+    assert!(explanation.contains(&format!("features: {}", features.join(", "))));
+}
+
+#[test]
+fn test_mimalloc_probe_passes_in_normal_environment() {
+    // Only a sandbox that blocks mimalloc's first arena reports the probe failure
+    let info = get_allocator_info();
+    assert_ne!(
+        info.reason_detail,
+        auto_allocator::SelectionReason::ProbeFailed
+    );
+    assert_eq!(
+        auto_allocator::SelectionReason::ProbeFailed.to_string(),
+        "mimalloc init probe failed - using system"
+    );
+}
+
+#[test]
+fn test_system_info_diff() {
+    let before = get_allocator_info().system_info.clone();
+    assert!(before.diff(&before).is_empty());
+
+    let mut after = before.clone();
+    after.effective_memory_bytes = before.effective_memory_bytes / 2;
+    after.available_memory_bytes = 0;
+    let diff = before.diff(&after);
+    assert_eq!(
+        diff.effective_memory_bytes,
+        Some((before.effective_memory_bytes, after.effective_memory_bytes))
+    );
+    // Point-in-time values don't count as an environment change
+    assert_eq!(diff.cpu_cores, None);
+    assert!(!diff.os_changed);
+    assert!(!diff.is_empty());
+
+    // Nothing migrates the test process between hosts
+    assert!(!auto_allocator::environment_changed_since_init());
+}
+
+#[test]
+fn test_forced_system_by_debug_matches_reason() {
+    let info = get_allocator_info();
+    assert_eq!(
+        auto_allocator::forced_system_by_debug(),
+        info.reason_detail == auto_allocator::SelectionReason::Debug
+    );
+    assert_eq!(
+        auto_allocator::forced_system_by_debug(),
+        cfg!(debug_assertions) && info.allocator_type == auto_allocator::AllocatorType::System
+    );
+}
+
+#[test]
+fn test_target_triple() {
+    let sys = &get_allocator_info().system_info;
+    assert!(sys.target_triple.contains('-'));
+    #[cfg(target_os = "linux")]
+    assert!(sys.target_triple.contains("linux"));
+    #[cfg(target_env = "musl")]
+    assert!(sys.target_triple.ends_with("musl"));
+
+    assert!(auto_allocator::explain_selection().contains(&format!("target: {}", sys.target_triple)));
+    let env = auto_allocator::system_info_env();
+    assert!(env.contains(&("AUTO_ALLOC_TARGET".to_string(), sys.target_triple.to_string())));
+}
+
+#[test]
+fn test_target_const() {
+    assert!(!auto_allocator::TARGET.is_empty());
+    assert_eq!(
+        auto_allocator::TARGET,
+        get_allocator_info().system_info.target_triple
+    );
+    #[cfg(all(target_arch = "x86_64", target_os = "linux"))]
+    assert!(auto_allocator::TARGET.starts_with("x86_64-"));
+}
+
+#[test]
+fn test_check_allocator_optimization_detailed() {
+    let report = auto_allocator::check_allocator_optimization_detailed();
+    let (is_optimal, _) = auto_allocator::check_allocator_optimization();
+    assert_eq!(report.is_optimal, is_optimal);
+    assert_eq!(report.current, get_allocator_info().allocator_type);
+    assert_eq!(report.current_reason, get_allocator_info().reason_detail);
+
+    if report.current_reason == auto_allocator::SelectionReason::Debug {
+        assert!(report.advice.unwrap().contains("--release"));
+    }
+}
+
+#[test]
+fn test_pointer_width_and_endian() {
+    let sys = &get_allocator_info().system_info;
+    assert_eq!(sys.pointer_width as usize, std::mem::size_of::<usize>() * 8);
+    #[cfg(target_endian = "little")]
+    assert_eq!(sys.endian, auto_allocator::Endianness::Little);
+    #[cfg(target_endian = "big")]
+    assert_eq!(sys.endian, auto_allocator::Endianness::Big);
+
+    let env = auto_allocator::system_info_env();
+    assert!(env.contains(&("AUTO_ALLOC_POINTER_WIDTH".to_string(), sys.pointer_width.to_string())));
+    assert!(env.contains(&("AUTO_ALLOC_ENDIAN".to_string(), sys.endian.name().to_string())));
+}
+
+#[test]
+fn test_selection_inputs() {
+    let inputs = auto_allocator::selection_inputs();
+    let sys = &get_allocator_info().system_info;
+    assert!(inputs.selector_cores >= 1);
+    assert!(inputs.available_parallelism >= 1);
+    assert!(inputs.min_mimalloc_cores >= 1);
+    assert!(inputs.effective_memory_bytes <= inputs.total_memory_bytes);
+    assert_eq!(inputs.is_debug, sys.is_debug);
+    assert_eq!(inputs.is_wasm, sys.is_wasm);
+    assert!(!inputs.is_embedded);
+
+    // Debug builds are decided at compile time, before any hardware detection
+    if inputs.is_debug {
+        assert_eq!(inputs.compile_time_allocator, Some(auto_allocator::AllocatorType::System));
+        assert!(!inputs.mimalloc_available);
+    }
+}
+
+#[test]
+fn test_fallback_reason() {
+    use auto_allocator::{FallbackReason, SelectionReason};
+
+    let fallback = |cores| SelectionReason::Fallback {
+        cores,
+        total_memory_bytes: 8 << 30,
+        cpu_quota_limited: false,
+    };
+    if !cfg!(feature = "allow-single-core-mimalloc") {
+        assert_eq!(fallback(1).fallback_reason(), Some(FallbackReason::SingleCore));
+    }
+    assert_eq!(fallback(8).fallback_reason(), Some(FallbackReason::MimallocUnavailable));
+    assert!(fallback(8).to_string().contains("mimalloc unavailable"));
+    assert_eq!(SelectionReason::ProbeFailed.fallback_reason(), Some(FallbackReason::ProbeFailed));
+    assert_eq!(SelectionReason::Debug.fallback_reason(), None);
+    assert_eq!(SelectionReason::Footprint.fallback_reason(), None);
+
+    assert_eq!(
+        auto_allocator::fallback_reason(),
+        get_allocator_info().reason_detail.fallback_reason()
+    );
+}
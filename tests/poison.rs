@@ -0,0 +1,68 @@
+//! Memory poisoning tests for auto-allocator
+//!
+//! Only built with the `poison-alloc` feature in debug builds:
+//! `cargo test --features poison-alloc`
+
+#![cfg(all(feature = "poison-alloc", debug_assertions))]
+
+// Calls RuntimeAllocator directly: through std::alloc the compiler may assume fresh
+// memory is uninitialized and fold the reads below away
+use auto_allocator::RuntimeAllocator;
+use std::alloc::{GlobalAlloc, Layout};
+
+#[test]
+fn test_fresh_allocation_is_poisoned() {
+    let layout = Layout::from_size_align(256, 8).unwrap();
+    unsafe {
+        let ptr = RuntimeAllocator.alloc(layout);
+        assert!(!ptr.is_null());
+        let bytes = std::slice::from_raw_parts(ptr, layout.size());
+        assert!(bytes.iter().all(|&b| b == 0xAA));
+        RuntimeAllocator.dealloc(ptr, layout);
+    }
+}
+
+#[test]
+fn test_zeroed_allocation_still_zero() {
+    let layout = Layout::from_size_align(256, 8).unwrap();
+    unsafe {
+        let ptr = RuntimeAllocator.alloc_zeroed(layout);
+        assert!(!ptr.is_null());
+        let bytes = std::slice::from_raw_parts(ptr, layout.size());
+        assert!(bytes.iter().all(|&b| b == 0));
+        RuntimeAllocator.dealloc(ptr, layout);
+    }
+}
+
+#[test]
+fn test_realloc_poisons_grown_part() {
+    let layout = Layout::from_size_align(16, 8).unwrap();
+    unsafe {
+        let ptr = RuntimeAllocator.alloc(layout);
+        std::ptr::write_bytes(ptr, 0x11, layout.size());
+
+        let grown = RuntimeAllocator.realloc(ptr, layout, 4096);
+        assert!(!grown.is_null());
+        let bytes = std::slice::from_raw_parts(grown, 4096);
+        assert!(bytes[..16].iter().all(|&b| b == 0x11), "contents preserved");
+        assert!(bytes[16..].iter().all(|&b| b == 0xAA));
+        RuntimeAllocator.dealloc(grown, Layout::from_size_align(4096, 8).unwrap());
+    }
+}
+
+#[test]
+fn test_realloc_shrink_keeps_contents() {
+    let layout = Layout::from_size_align(4096, 8).unwrap();
+    unsafe {
+        let ptr = RuntimeAllocator.alloc(layout);
+        for i in 0..layout.size() {
+            *ptr.add(i) = i as u8;
+        }
+
+        let shrunk = RuntimeAllocator.realloc(ptr, layout, 100);
+        assert!(!shrunk.is_null());
+        let bytes = std::slice::from_raw_parts(shrunk, 100);
+        assert!(bytes.iter().enumerate().all(|(i, &b)| b == i as u8), "kept bytes preserved");
+        RuntimeAllocator.dealloc(shrunk, Layout::from_size_align(100, 8).unwrap());
+    }
+}
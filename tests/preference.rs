@@ -0,0 +1,45 @@
+//! Allocator preference-order tests for auto-allocator
+//!
+//! Kept in their own test binary because `set_allocator_preference` is process-global.
+
+use auto_allocator::{
+    is_allocator_available, set_allocator_preference, AllocatorType, SelectionReason,
+};
+
+#[test]
+fn test_availability_matches_build() {
+    assert!(is_allocator_available(AllocatorType::System));
+    assert!(!is_allocator_available(AllocatorType::EmbeddedHeap));
+    assert!(!is_allocator_available(AllocatorType::Custom));
+    if cfg!(any(debug_assertions, not(feature = "_mimalloc"))) {
+        assert!(!is_allocator_available(AllocatorType::Mimalloc));
+    }
+}
+
+#[test]
+fn test_recommendation_follows_preference() {
+    // Entries that are unavailable in this build are passed over
+    // Too late for the startup selection; still applied to recommendations
+    assert!(!set_allocator_preference(&[
+        AllocatorType::EmbeddedHeap,
+        AllocatorType::System
+    ]));
+
+    let info = auto_allocator::get_allocator_info();
+    if info.reason_detail == SelectionReason::Preference {
+        let (recommended, _) = auto_allocator::get_recommended_allocator();
+        assert_eq!(recommended, AllocatorType::System);
+    } else {
+        // Compile-time decisions (debug, WASM, platform policy) take precedence
+        assert!(matches!(
+            info.reason_detail,
+            SelectionReason::Debug | SelectionReason::Wasm | SelectionReason::PlatformPolicy { .. }
+        ));
+    }
+
+    set_allocator_preference(&[]);
+    assert_ne!(
+        auto_allocator::get_recommended_allocator().1,
+        SelectionReason::Preference.to_string()
+    );
+}
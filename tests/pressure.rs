@@ -0,0 +1,43 @@
+//! Memory pressure monitoring tests for auto-allocator
+//!
+//! Only built with the `pressure` feature on Linux: `cargo test --features pressure`
+
+#![cfg(all(feature = "pressure", target_os = "linux"))]
+
+use auto_allocator::{current_memory_pressure, PressureLevel};
+
+#[test]
+fn test_current_pressure_matches_psi_availability() {
+    // PSI may be compiled out or disabled at boot; the cgroup fallback may still apply
+    let psi_readable = std::fs::read_to_string("/proc/pressure/memory").is_ok();
+    if psi_readable {
+        assert!(current_memory_pressure().is_some());
+    }
+}
+
+#[test]
+fn test_callback_registration_and_monitor_toggle() {
+    fn on_pressure(_level: PressureLevel) {}
+
+    auto_allocator::set_memory_pressure_callback(on_pressure);
+    let _ = auto_allocator::get_allocator_info(); // lazily starts the monitor
+    auto_allocator::set_memory_pressure_monitor_enabled(false);
+
+    assert!(PressureLevel::Critical > PressureLevel::Moderate);
+    assert!(PressureLevel::Moderate > PressureLevel::Normal);
+}
+
+#[test]
+fn test_cgroup_events_fixture() {
+    let quiet = "low 0\nhigh 0\nmax 0\noom 0\noom_kill 0\noom_group_kill 0\n";
+    assert_eq!(PressureLevel::from_cgroup_events(quiet), PressureLevel::Normal);
+
+    let throttled = "low 0\nhigh 37\nmax 0\noom 0\noom_kill 0\n";
+    assert_eq!(PressureLevel::from_cgroup_events(throttled), PressureLevel::Moderate);
+
+    // `oom_kill` must not be mistaken for `oom`
+    let limited = "low 0\nhigh 37\nmax 4\noom 0\noom_kill 0\n";
+    assert_eq!(PressureLevel::from_cgroup_events(limited), PressureLevel::Critical);
+    let oom = "low 0\nhigh 0\nmax 0\noom 1\noom_kill 1\n";
+    assert_eq!(PressureLevel::from_cgroup_events(oom), PressureLevel::Critical);
+}
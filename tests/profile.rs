@@ -0,0 +1,73 @@
+//! Allocator selection profile tests for auto-allocator
+//!
+//! Kept in their own test binary because `set_allocator_profile` is process-global.
+
+use auto_allocator::{get_allocator_profile, set_allocator_profile, AllocatorProfile};
+
+mod common;
+
+#[test]
+fn test_profile_name_parsing() {
+    assert_eq!(
+        AllocatorProfile::from_name("throughput"),
+        Some(AllocatorProfile::Throughput)
+    );
+    assert_eq!(
+        AllocatorProfile::from_name("Low-Latency"),
+        Some(AllocatorProfile::LowLatency)
+    );
+    assert_eq!(
+        AllocatorProfile::from_name(" memory "),
+        Some(AllocatorProfile::LowMemory)
+    );
+    assert_eq!(
+        AllocatorProfile::from_name("FOOTPRINT"),
+        Some(AllocatorProfile::Footprint)
+    );
+    assert_eq!(AllocatorProfile::from_name("fastest"), None);
+
+    for profile in [
+        AllocatorProfile::Throughput,
+        AllocatorProfile::LowLatency,
+        AllocatorProfile::LowMemory,
+        AllocatorProfile::Footprint,
+    ] {
+        assert_eq!(AllocatorProfile::from_name(profile.name()), Some(profile));
+    }
+}
+
+#[test]
+fn test_set_allocator_profile() {
+    // Selection already happened: too late for it, but recommendations follow
+    assert!(!set_allocator_profile(AllocatorProfile::LowMemory));
+    assert_eq!(get_allocator_profile(), AllocatorProfile::LowMemory);
+
+    // Recommendations follow the profile on constrained systems
+    assert_eq!(
+        auto_allocator::get_recommended_allocator_for(&common::linux_host(8, 1u64 << 30)),
+        (
+            auto_allocator::AllocatorType::System,
+            auto_allocator::SelectionReason::LowMemory {
+                effective_memory_bytes: 1u64 << 30
+            }
+        )
+    );
+
+    // LowLatency selects like Throughput; only the mimalloc tuning differs
+    set_allocator_profile(AllocatorProfile::LowLatency);
+    let (allocator_type, _) = auto_allocator::get_recommended_allocator_for(&common::linux_host(8, 16u64 << 30));
+    assert_eq!(allocator_type, auto_allocator::AllocatorType::Mimalloc);
+
+    // Footprint picks the system allocator even on a large multi-core release host
+    set_allocator_profile(AllocatorProfile::Footprint);
+    assert_eq!(
+        auto_allocator::get_recommended_allocator_for(&common::linux_host(64, 256u64 << 30)),
+        (
+            auto_allocator::AllocatorType::System,
+            auto_allocator::SelectionReason::Footprint
+        )
+    );
+
+    set_allocator_profile(AllocatorProfile::Throughput);
+    assert_eq!(get_allocator_profile(), AllocatorProfile::Throughput);
+}
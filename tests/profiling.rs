@@ -0,0 +1,98 @@
+//! Allocation profiling hook tests for auto-allocator
+//!
+//! Only built with the `profiling` feature, and without `no-global` (the events come
+//! from `Vec`/`Box` allocations, which then bypass auto-allocator):
+//! `cargo test --features profiling`
+
+#![cfg(all(feature = "profiling", not(feature = "no-global")))]
+
+use auto_allocator::{
+    backtrace_frames, resolve_backtrace, set_profiling_hook, AllocationEvent, AllocationEventKind,
+};
+use std::cell::Cell;
+use std::hint::black_box;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+// The hook is process-wide; tests taking it run one at a time
+static HOOK_LOCK: Mutex<()> = Mutex::new(());
+
+const TRACKED_SIZE: usize = 12_345;
+static ALLOCS: AtomicUsize = AtomicUsize::new(0);
+static DEALLOCS: AtomicUsize = AtomicUsize::new(0);
+static SITE: AtomicU64 = AtomicU64::new(0);
+
+fn on_event(event: &AllocationEvent) {
+    if event.size != TRACKED_SIZE {
+        return;
+    }
+    match event.kind {
+        AllocationEventKind::Alloc => {
+            ALLOCS.fetch_add(1, Ordering::Relaxed);
+            SITE.store(event.backtrace_id, Ordering::Relaxed);
+        }
+        AllocationEventKind::Dealloc => {
+            DEALLOCS.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+#[test]
+fn test_hook_sees_alloc_and_dealloc() {
+    let _guard = HOOK_LOCK.lock().unwrap();
+
+    set_profiling_hook(Some(on_event));
+    drop(black_box(vec![0u8; TRACKED_SIZE]));
+    set_profiling_hook(None);
+
+    assert_eq!(ALLOCS.load(Ordering::Relaxed), 1);
+    assert_eq!(DEALLOCS.load(Ordering::Relaxed), 1);
+
+    // No events once the hook is cleared
+    drop(black_box(vec![0u8; TRACKED_SIZE]));
+    assert_eq!(ALLOCS.load(Ordering::Relaxed), 1);
+
+    // no-libc (or a build without libc) leaves stack capture out on Linux
+    #[cfg(any(
+        all(target_os = "linux", target_env = "gnu", feature = "_libc", not(feature = "no-libc")),
+        target_os = "macos"
+    ))]
+    {
+        let site = SITE.load(Ordering::Relaxed);
+        assert_ne!(site, 0);
+        let frames = backtrace_frames(site).expect("site recorded");
+        assert!(!frames.is_empty());
+        assert_eq!(resolve_backtrace(site).len(), frames.len());
+    }
+}
+
+thread_local! {
+    // Per-thread so allocations of other test threads don't count
+    static NESTED: Cell<usize> = const { Cell::new(0) };
+}
+
+fn allocating_hook(_event: &AllocationEvent) {
+    // Allocations made by the hook itself must not be reported back to it
+    NESTED.with(|nested| nested.set(nested.get() + 1));
+    black_box(String::from("hook allocation"));
+}
+
+#[test]
+fn test_hook_allocations_not_reported() {
+    let _guard = HOOK_LOCK.lock().unwrap();
+
+    set_profiling_hook(Some(allocating_hook));
+    let before = NESTED.with(Cell::get);
+    drop(black_box(Box::new(1u64)));
+    let events = NESTED.with(Cell::get) - before;
+    set_profiling_hook(None);
+
+    // One alloc and one dealloc, not an unbounded recursion
+    assert_eq!(events, 2);
+}
+
+#[test]
+fn test_unknown_backtrace_id() {
+    assert!(backtrace_frames(0).is_none());
+    assert!(resolve_backtrace(0).is_empty());
+}
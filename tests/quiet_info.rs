@@ -0,0 +1,56 @@
+//! Quiet getter tests for auto-allocator
+//!
+//! Only built with the `log` feature (default) and without `no-log`; runs in its own process so the
+//! pending selection message is still unflushed when the test starts
+
+#![cfg(all(feature = "log", not(feature = "no-log")))]
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static RECORDS: AtomicUsize = AtomicUsize::new(0);
+
+struct CountingLogger;
+
+impl log::Log for CountingLogger {
+    fn enabled(&self, _metadata: &log::Metadata) -> bool {
+        true
+    }
+
+    fn log(&self, record: &log::Record) {
+        if record.args().to_string().contains("Auto-allocator") {
+            RECORDS.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+static LOGGER: CountingLogger = CountingLogger;
+
+#[test]
+fn test_quiet_getters_do_not_flush_log() {
+    log::set_logger(&LOGGER).unwrap();
+    log::set_max_level(log::LevelFilter::Info);
+
+    for _ in 0..3 {
+        let info = auto_allocator::get_allocator_info_quiet();
+        assert_eq!(
+            auto_allocator::get_allocator_type_quiet(),
+            info.allocator_type
+        );
+    }
+    assert_eq!(
+        RECORDS.load(Ordering::SeqCst),
+        0,
+        "quiet getters stay silent"
+    );
+
+    // The default getter still forwards the pending message, once
+    let info = auto_allocator::get_allocator_info();
+    assert_eq!(
+        info.allocator_type,
+        auto_allocator::get_allocator_type_quiet()
+    );
+    auto_allocator::get_allocator_type();
+    assert_eq!(RECORDS.load(Ordering::SeqCst), 1);
+}
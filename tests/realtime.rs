@@ -0,0 +1,50 @@
+//! Realtime mode tests for auto-allocator
+//!
+//! Only built with the `realtime` feature: `cargo test --features realtime`
+
+#![cfg(feature = "realtime")]
+
+use auto_allocator::{
+    enable_realtime_mode, is_realtime_mode_enabled, realtime_section, AllocatorType,
+    RealtimeOptions,
+};
+
+#[test]
+fn test_enable_realtime_mode_follows_backend() {
+    let options = RealtimeOptions::default();
+    assert_eq!(options.reserve_bytes, 64 << 20);
+    assert!(!options.lock_memory);
+
+    // Reservation needs mimalloc; other backends report false and stay out of the mode
+    let enabled = enable_realtime_mode(options);
+    let is_mimalloc = matches!(
+        auto_allocator::get_allocator_type(),
+        AllocatorType::Mimalloc | AllocatorType::MimallocSecure
+    );
+    assert_eq!(enabled, is_mimalloc);
+    assert_eq!(is_realtime_mode_enabled(), is_mimalloc);
+}
+
+#[test]
+fn test_warmed_up_section_does_not_fault() {
+    // Warmup: touch the size class the hot path uses
+    let warmup: Vec<Vec<u8>> = (0..64).map(|_| vec![0u8; 256]).collect();
+    drop(warmup);
+
+    let section = realtime_section();
+    let buffer = vec![1u8; 256];
+    assert_eq!(buffer.len(), 256);
+    drop(buffer);
+    drop(section);
+}
+
+// The check lives in auto-allocator's GlobalAlloc, which `no-global` leaves uninstalled
+#[cfg(all(debug_assertions, target_os = "linux", not(feature = "no-global")))]
+#[test]
+#[should_panic(expected = "realtime section")]
+fn test_cold_allocation_in_section_is_reported() {
+    let _section = realtime_section();
+    // Large blocks come straight from mmap, so writing their header faults
+    let buffer = vec![0u8; 8 << 20];
+    drop(buffer);
+}
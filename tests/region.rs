@@ -0,0 +1,78 @@
+//! Region arena tests for auto-allocator
+//!
+//! Only built with the `region` feature: `cargo test --features region`
+
+#![cfg(feature = "region")]
+
+use auto_allocator::Region;
+use std::alloc::Layout;
+
+#[test]
+fn test_region_alignment() {
+    let region = Region::with_chunk_size(1024);
+    let mut align = 1;
+    while align <= 4096 {
+        // Interleave odd-sized bytes so every aligned request has to pad
+        region.alloc(0u8);
+        let ptr = region.alloc_layout(Layout::from_size_align(3, align).unwrap());
+        assert_eq!(ptr.as_ptr() as usize % align, 0, "align {align}");
+        align <<= 1;
+    }
+
+    let value = region.alloc(0x1234_5678_9abc_def0u64);
+    assert_eq!(value as *mut u64 as usize % std::mem::align_of::<u64>(), 0);
+    assert_eq!(*value, 0x1234_5678_9abc_def0);
+}
+
+#[test]
+fn test_region_grows_across_chunks() {
+    let region = Region::with_chunk_size(256);
+    let values: Vec<&mut u64> = (0..200u64).map(|i| region.alloc(i)).collect();
+
+    // 200 * 8 bytes can't fit in one 256-byte chunk
+    assert!(region.chunk_count() > 1);
+    assert_eq!(region.allocated_bytes(), 200 * 8);
+    for (i, value) in values.iter().enumerate() {
+        assert_eq!(**value, i as u64, "earlier chunks stay intact");
+    }
+}
+
+#[test]
+fn test_region_oversized_allocation() {
+    let region = Region::with_chunk_size(64);
+    let data = region.alloc_slice_copy(&[7u8; 10_000]);
+    assert_eq!(data.len(), 10_000);
+    assert!(data.iter().all(|&b| b == 7));
+
+    let aligned = region.alloc_layout(Layout::from_size_align(128, 4096).unwrap());
+    assert_eq!(aligned.as_ptr() as usize % 4096, 0);
+}
+
+#[test]
+fn test_region_reset_frees_chunks() {
+    let mut region = Region::with_chunk_size(128);
+    assert_eq!(region.chunk_count(), 0, "nothing reserved up front");
+
+    for i in 0..100u32 {
+        region.alloc(i);
+    }
+    assert!(region.chunk_count() > 1);
+
+    region.reset();
+    assert_eq!(region.chunk_count(), 0);
+    assert_eq!(region.allocated_bytes(), 0);
+
+    // Reusable after reset
+    assert_eq!(*region.alloc(42u32), 42);
+    assert_eq!(region.chunk_count(), 1);
+}
+
+#[test]
+fn test_region_zero_sized() {
+    let region = Region::new();
+    let unit = region.alloc(());
+    assert_eq!(*unit, ());
+    let ptr = region.alloc_layout(Layout::from_size_align(0, 64).unwrap());
+    assert_eq!(ptr.as_ptr() as usize % 64, 0);
+    assert_eq!(region.chunk_count(), 0);
+}
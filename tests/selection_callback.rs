@@ -0,0 +1,40 @@
+//! Selection callback tests for auto-allocator
+//!
+//! Runs in its own process: the callback fires once per process, so one test
+//! covers registration, the single run and late registrations
+
+use auto_allocator::{on_allocator_selected, AllocatorType, SystemInfo};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+static CALLS: AtomicUsize = AtomicUsize::new(0);
+static SEEN: Mutex<Option<(AllocatorType, usize)>> = Mutex::new(None);
+
+fn record(allocator: AllocatorType, system: &SystemInfo) {
+    CALLS.fetch_add(1, Ordering::SeqCst);
+    // Runs outside the allocator, so taking a lock and allocating is fine
+    *SEEN.lock().unwrap() = Some((allocator, system.cpu_cores));
+    let _ = format!("{:?}", allocator);
+}
+
+fn never(_: AllocatorType, _: &SystemInfo) {
+    panic!("a second callback must not run");
+}
+
+#[test]
+fn test_callback_runs_exactly_once() {
+    // The test harness has allocated already, so this runs the callback right away
+    assert!(on_allocator_selected(record));
+    assert_eq!(CALLS.load(Ordering::SeqCst), 1);
+
+    let info = auto_allocator::get_allocator_info();
+    assert_eq!(
+        *SEEN.lock().unwrap(),
+        Some((info.allocator_type, info.system_info.cpu_cores))
+    );
+
+    // Later queries and registrations don't run anything again
+    auto_allocator::get_allocator_type_quiet();
+    assert!(!on_allocator_selected(never));
+    assert_eq!(CALLS.load(Ordering::SeqCst), 1);
+}
@@ -0,0 +1,169 @@
+//! Selection matrix tests for auto-allocator
+//!
+//! Only built with the `testing` feature: `cargo test --features testing`
+//!
+//! The live selection latches once per process; these tests check the decision for
+//! each scenario through `testing::select_for()` instead of spawning subprocesses.
+
+#![cfg(feature = "testing")]
+
+use auto_allocator::testing::{
+    clear_selection_barrier, reset_selection_overrides, select_for, set_selection_barrier,
+};
+use auto_allocator::{
+    get_allocator_info, set_allocator_preference, set_denied_allocators, AllocatorProfile,
+    AllocatorType, FallbackReason, SelectionReason, SystemInfo,
+};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+mod common;
+
+// Tests that change the deny list or preference order run one at a time
+static OVERRIDES: Mutex<()> = Mutex::new(());
+
+/// A release build on an 8-core, 16GB Linux server
+fn server() -> SystemInfo {
+    common::linux_host(8, 16 << 30)
+}
+
+fn select(info: &SystemInfo) -> (AllocatorType, SelectionReason) {
+    select_for(info, AllocatorProfile::Throughput)
+}
+
+#[test]
+fn test_build_and_target_scenarios() {
+    let _guard = OVERRIDES.lock().unwrap();
+    reset_selection_overrides();
+
+    let (allocator, reason) = select(&server());
+    assert_eq!(allocator, AllocatorType::Mimalloc);
+    assert!(matches!(
+        reason,
+        SelectionReason::MultiCore { cores: 8, .. }
+    ));
+
+    let debug = SystemInfo {
+        is_debug: true,
+        ..server()
+    };
+    assert_eq!(
+        select(&debug),
+        (AllocatorType::System, SelectionReason::Debug)
+    );
+
+    let wasm = SystemInfo {
+        is_wasm: true,
+        ..server()
+    };
+    assert_eq!(
+        select(&wasm),
+        (AllocatorType::System, SelectionReason::Wasm)
+    );
+
+    let embedded = SystemInfo {
+        os_type: "embedded".to_string(),
+        ..server()
+    };
+    assert_eq!(
+        select(&embedded),
+        (AllocatorType::EmbeddedHeap, SelectionReason::Embedded)
+    );
+
+    let android = SystemInfo {
+        os_type: "android".to_string(),
+        ..server()
+    };
+    let (allocator, reason) = select(&android);
+    assert_eq!(allocator, AllocatorType::System);
+    assert!(matches!(reason, SelectionReason::PlatformPolicy { .. }));
+}
+
+#[test]
+fn test_hardware_and_profile_scenarios() {
+    let _guard = OVERRIDES.lock().unwrap();
+    reset_selection_overrides();
+
+    let small_container = SystemInfo {
+        effective_memory_bytes: 256 << 20,
+        ..server()
+    };
+    let (allocator, reason) = select(&small_container);
+    assert_eq!(allocator, AllocatorType::System);
+    assert!(matches!(reason, SelectionReason::LowMemory { .. }));
+    assert_eq!(reason.fallback_reason(), Some(FallbackReason::LowMemory));
+
+    let one_gig = SystemInfo {
+        effective_memory_bytes: 1 << 30,
+        ..server()
+    };
+    assert_eq!(select(&one_gig).0, AllocatorType::Mimalloc);
+    assert_eq!(
+        select_for(&one_gig, AllocatorProfile::LowMemory).0,
+        AllocatorType::System
+    );
+
+    assert_eq!(
+        select_for(&server(), AllocatorProfile::Footprint),
+        (AllocatorType::System, SelectionReason::Footprint)
+    );
+
+    let single_core = SystemInfo {
+        cpu_cores: 1,
+        ..server()
+    };
+    let (allocator, reason) = select(&single_core);
+    if cfg!(feature = "allow-single-core-mimalloc") {
+        assert_eq!(allocator, AllocatorType::Mimalloc);
+    } else {
+        assert_eq!(allocator, AllocatorType::System);
+        assert!(matches!(reason, SelectionReason::Fallback { cores: 1, .. }));
+        assert_eq!(reason.fallback_reason(), Some(FallbackReason::SingleCore));
+        assert!(reason.to_string().starts_with("runtime fallback - single-core"));
+    }
+}
+
+#[test]
+fn test_override_scenarios() {
+    let _guard = OVERRIDES.lock().unwrap();
+    reset_selection_overrides();
+
+    // Setters still feed the rules after the live selection, until reset
+    set_denied_allocators(&[AllocatorType::Mimalloc]);
+    let (allocator, reason) = select(&server());
+    assert_eq!(allocator, AllocatorType::System);
+    assert!(matches!(
+        reason,
+        SelectionReason::Denied {
+            skipped: "mimalloc"
+        }
+    ));
+
+    reset_selection_overrides();
+    set_allocator_preference(&[AllocatorType::System]);
+    assert_eq!(
+        select(&server()),
+        (AllocatorType::System, SelectionReason::Preference)
+    );
+
+    reset_selection_overrides();
+    assert_eq!(select(&server()).0, AllocatorType::Mimalloc);
+}
+
+static BARRIER_CALLS: AtomicUsize = AtomicUsize::new(0);
+
+fn count_barrier() {
+    BARRIER_CALLS.fetch_add(1, Ordering::Relaxed);
+}
+
+#[test]
+fn test_selection_barrier_only_runs_before_selection() {
+    // The test harness allocated before this test ran, so the selection has latched
+    set_selection_barrier(count_barrier);
+    let allocated = vec![0u8; 64];
+    drop(allocated);
+    let _ = get_allocator_info();
+    clear_selection_barrier();
+
+    assert_eq!(BARRIER_CALLS.load(Ordering::Relaxed), 0);
+}
@@ -0,0 +1,39 @@
+//! Single-core mimalloc opt-in tests for auto-allocator
+//!
+//! Only built with the `allow-single-core-mimalloc` feature:
+//! `cargo test --features allow-single-core-mimalloc`
+
+#![cfg(feature = "allow-single-core-mimalloc")]
+
+use auto_allocator::{AllocatorType, SelectionReason};
+
+mod common;
+
+/// A release-mode single-core Linux VM with 4GB of RAM
+fn single_core_vm() -> auto_allocator::SystemInfo {
+    common::linux_host(1, 4u64 << 30)
+}
+
+#[test]
+fn test_single_core_recommends_mimalloc() {
+    let (allocator_type, reason) = auto_allocator::get_recommended_allocator_for(&single_core_vm());
+    assert_eq!(allocator_type, AllocatorType::Mimalloc);
+    assert!(matches!(
+        reason,
+        SelectionReason::MultiCore { cores: 1, .. }
+    ));
+}
+
+#[test]
+fn test_memory_gate_still_applies() {
+    let mut info = single_core_vm();
+    info.effective_memory_bytes = 256u64 << 20;
+    let (allocator_type, _) = auto_allocator::get_recommended_allocator_for(&info);
+    assert_eq!(allocator_type, AllocatorType::System);
+}
+
+#[test]
+fn test_optimization_check_agrees() {
+    let (is_optimal, suggestion) = auto_allocator::check_allocator_optimization();
+    assert!(is_optimal, "{:?}", suggestion);
+}
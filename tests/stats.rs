@@ -0,0 +1,238 @@
+//! Allocation statistics tests for auto-allocator
+//!
+//! Only built with the `stats` feature, and without `no-global` (the counted allocations
+//! come from std collections, which then bypass auto-allocator): `cargo test --features stats`
+
+#![cfg(all(feature = "stats", not(feature = "no-global")))]
+
+use auto_allocator::{
+    alignment_stats, allocation_rate, allocation_stats, measure, measure_thread, overhead_ratio,
+    size_class_stats, tag_scope, tag_stats, thread_allocation_stats, ALIGNMENT_CLASSES,
+    SIZE_CLASSES,
+};
+use std::hint::black_box;
+use std::thread;
+
+#[test]
+fn test_global_stats_track_allocations() {
+    let before = allocation_stats();
+    let data: Vec<u8> = black_box(vec![0; 4096]);
+    let after = allocation_stats();
+
+    assert!(after.alloc_count > before.alloc_count);
+    assert!(after.bytes_allocated - before.bytes_allocated >= 4096);
+
+    drop(data);
+    let freed = allocation_stats();
+    assert!(freed.bytes_deallocated - before.bytes_deallocated >= 4096);
+}
+
+#[test]
+fn test_thread_stats_are_independent() {
+    // Each thread performs a different number of allocations and should
+    // see exactly its own count, regardless of what the others do
+    let handles: Vec<_> = [10u64, 20, 30]
+        .into_iter()
+        .map(|count| {
+            thread::spawn(move || {
+                let before = thread_allocation_stats();
+                let boxes: Vec<Box<u64>> = (0..count).map(|i| black_box(Box::new(i))).collect();
+                let after = thread_allocation_stats();
+                drop(black_box(boxes));
+
+                // `count` boxes plus the Vec holding them
+                assert_eq!(after.alloc_count - before.alloc_count, count + 1);
+                assert!(after.bytes_allocated - before.bytes_allocated >= count * 8);
+                count
+            })
+        })
+        .collect();
+
+    for handle in handles {
+        handle.join().unwrap();
+    }
+}
+
+#[test]
+fn test_thread_stats_exclude_other_threads() {
+    let before = thread_allocation_stats();
+
+    thread::spawn(|| {
+        let _data: Vec<u8> = black_box(vec![0; 1 << 20]);
+    })
+    .join()
+    .unwrap();
+
+    // The 1MB allocation happened on another thread
+    let after = thread_allocation_stats();
+    assert!(after.bytes_allocated - before.bytes_allocated < 1 << 20);
+}
+
+#[test]
+fn test_measure_thread_attributes_region() {
+    let (data, delta) = measure_thread(|| black_box(vec![0u8; 8192]));
+    assert_eq!(delta.allocs, 1);
+    assert_eq!(delta.frees, 0);
+    assert_eq!(delta.net_bytes, 8192);
+
+    let ((), freed) = measure_thread(|| drop(data));
+    assert_eq!(freed.allocs, 0);
+    assert_eq!(freed.frees, 1);
+    assert_eq!(freed.net_bytes, -8192);
+}
+
+#[test]
+fn test_measure_includes_region_allocations() {
+    let (len, delta) = measure(|| black_box(vec![1u32; 1024]).len());
+    assert_eq!(len, 1024);
+    assert!(delta.allocs >= 1);
+    assert!(delta.frees >= 1);
+}
+
+#[test]
+fn test_allocation_rate() {
+    // First call only sets the baseline
+    assert_eq!(allocation_rate(), 0.0);
+
+    let boxes: Vec<Box<u64>> = (0..1000).map(|i| black_box(Box::new(i))).collect();
+    thread::sleep(std::time::Duration::from_millis(10));
+    let rate = allocation_rate();
+    drop(boxes);
+
+    assert!(rate > 0.0);
+    assert!(rate.is_finite());
+}
+
+#[test]
+fn test_size_classes_cover_all_sizes() {
+    let classes: Vec<_> = size_class_stats().collect();
+    assert_eq!(classes.len(), SIZE_CLASSES);
+    assert_eq!(classes[0].min_size, 0);
+    assert_eq!(classes[0].max_size, 8);
+    assert_eq!(classes[SIZE_CLASSES - 1].max_size, usize::MAX);
+    for pair in classes.windows(2) {
+        assert_eq!(pair[1].min_size, pair[0].max_size + 1);
+    }
+}
+
+#[test]
+fn test_size_class_stats_track_live_blocks() {
+    // 3MB is a size no other test in this file allocates
+    const SIZE: usize = 3 << 20;
+    let class_of = || {
+        size_class_stats()
+            .find(|class| (class.min_size..=class.max_size).contains(&SIZE))
+            .unwrap()
+    };
+
+    let before = class_of();
+    let block: Vec<u8> = black_box(Vec::with_capacity(SIZE));
+    let during = class_of();
+    drop(block);
+    let after = class_of();
+
+    assert_eq!(during.live_count, before.live_count + 1);
+    assert!(during.bytes_allocated - before.bytes_allocated >= SIZE as u64);
+    assert_eq!(after.live_count, before.live_count);
+    assert_eq!(after.bytes_allocated, during.bytes_allocated);
+}
+
+#[test]
+fn test_alignment_stats_count_over_aligned_requests() {
+    #[repr(align(256))]
+    struct Aligned256([u8; 256]);
+
+    let count_at = |align: usize| {
+        alignment_stats()
+            .find(|class| class.align == align)
+            .unwrap()
+            .alloc_count
+    };
+
+    let classes: Vec<_> = alignment_stats().collect();
+    assert_eq!(classes.len(), ALIGNMENT_CLASSES);
+    assert_eq!(classes[0].align, 1);
+    assert_eq!(classes[ALIGNMENT_CLASSES - 1].align, 4096);
+
+    let before = count_at(256);
+    let boxes: Vec<Box<Aligned256>> = (0..3)
+        .map(|_| black_box(Box::new(Aligned256([0; 256]))))
+        .collect();
+    assert_eq!(count_at(256) - before, 3);
+    assert!(boxes.iter().all(|aligned| aligned.0[0] == 0));
+}
+
+#[test]
+fn test_realloc_counts_in_place_and_moved() {
+    use std::alloc::{alloc, dealloc, realloc, Layout};
+
+    let before = allocation_stats();
+    unsafe {
+        let layout = Layout::from_size_align(64, 8).unwrap();
+        let ptr = alloc(layout);
+        assert!(!ptr.is_null());
+
+        // Shrinking a small block keeps it in place, growing it to 4MB has to move it
+        // (debug poison-alloc builds move every shrink, to poison the old block)
+        let shrunk = realloc(ptr, layout, 32);
+        assert!(!shrunk.is_null());
+        #[cfg(not(all(feature = "poison-alloc", debug_assertions)))]
+        assert_eq!(shrunk, ptr);
+        let grown = realloc(shrunk, Layout::from_size_align(32, 8).unwrap(), 4 << 20);
+        assert!(!grown.is_null());
+        assert_ne!(grown, shrunk);
+        dealloc(grown, Layout::from_size_align(4 << 20, 8).unwrap());
+    }
+    let after = allocation_stats();
+
+    #[cfg(not(all(feature = "poison-alloc", debug_assertions)))]
+    assert!(after.realloc_in_place > before.realloc_in_place);
+    assert!(after.realloc_moved > before.realloc_moved);
+
+    // Per-thread snapshots don't track reallocs
+    let thread = thread_allocation_stats();
+    assert_eq!((thread.realloc_in_place, thread.realloc_moved), (0, 0));
+}
+
+#[test]
+fn test_overhead_ratio() {
+    // Touched pages, so they count towards RSS
+    let data: Vec<u8> = black_box(vec![1; 32 << 20]);
+    let ratio = overhead_ratio();
+    #[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
+    assert!(ratio.expect("RSS available") > 0.0);
+    if let Some(ratio) = ratio {
+        assert!(ratio.is_finite());
+    }
+    drop(data);
+}
+
+#[test]
+fn test_tag_scope_attributes_until_freed() {
+    let data = tag_scope("tenant-a", || black_box(vec![0u8; 10_000]));
+    assert!(tag_stats()["tenant-a"] >= 10_000);
+
+    // Freed outside the scope, on another thread: still debited from the tag
+    thread::spawn(move || drop(data)).join().unwrap();
+    assert_eq!(tag_stats()["tenant-a"], 0);
+}
+
+#[test]
+fn test_tag_scope_nests_and_keeps_tag_on_realloc() {
+    let (outer, inner) = tag_scope("tenant-outer", || {
+        let inner = tag_scope("tenant-inner", || black_box(vec![0u8; 3_000]));
+        (black_box(vec![0u8; 5_000]), inner)
+    });
+    assert_eq!(tag_stats()["tenant-outer"], 5_000);
+    assert_eq!(tag_stats()["tenant-inner"], 3_000);
+
+    // Growing outside the scope still counts towards the original tag
+    let mut inner = inner;
+    inner.reserve_exact(7_000);
+    assert_eq!(tag_stats()["tenant-inner"], 10_000);
+
+    drop(outer);
+    drop(inner);
+    assert_eq!(tag_stats()["tenant-outer"], 0);
+    assert_eq!(tag_stats()["tenant-inner"], 0);
+}
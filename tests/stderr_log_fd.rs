@@ -0,0 +1,43 @@
+//! Selection log descriptor tests for auto-allocator
+//!
+//! The descriptor has to be set before the Rust runtime's first allocation, so it is
+//! set from an `.init_array` constructor - Linux only. The test re-runs this binary
+//! (`--list` only) and checks which stream the selection line went to.
+
+#![cfg(all(target_os = "linux", not(feature = "no-log")))]
+
+use std::process::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static REDIRECTED: AtomicBool = AtomicBool::new(false);
+
+extern "C" fn log_to_stdout_before_main() {
+    REDIRECTED.store(auto_allocator::set_stderr_log_fd(1), Ordering::SeqCst);
+}
+
+#[used]
+#[link_section = ".init_array"]
+static LOG_TO_STDOUT_BEFORE_MAIN: extern "C" fn() = log_to_stdout_before_main;
+
+#[test]
+fn test_selection_line_goes_to_configured_fd() {
+    assert!(REDIRECTED.load(Ordering::SeqCst));
+
+    let output = Command::new(std::env::current_exe().unwrap())
+        .arg("--list")
+        .env_remove("AUTO_ALLOCATOR_LOG")
+        .env_remove("AUTO_ALLOCATOR_LOG_FORMAT")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stdout.starts_with("[INFO] Auto-allocator: "), "{}", stdout);
+    assert!(!stderr.contains("Auto-allocator:"), "{}", stderr);
+}
+
+#[test]
+fn test_set_stderr_log_fd_after_selection() {
+    // Selection has run and logged by now; the setting is rejected and left alone
+    assert!(!auto_allocator::set_stderr_log_fd(2));
+    assert!(!auto_allocator::set_stderr_log_fd(-1));
+}
@@ -0,0 +1,50 @@
+//! `AUTO_ALLOCATOR_TOTAL_MEMORY` tests for auto-allocator
+//!
+//! Kept in their own test binary because the override is a process-wide environment variable.
+
+use auto_allocator::{
+    environment_changed_since_init, get_allocator_info, memory_snapshot, parse_memory_size,
+};
+
+#[test]
+fn test_total_memory_override() {
+    let detected = get_allocator_info().system_info.total_memory_bytes;
+    // An odd size no real machine reports
+    let override_bytes = 123 << 20;
+    std::env::set_var("AUTO_ALLOCATOR_TOTAL_MEMORY", "123MB");
+
+    // Fresh detection sees the override
+    let memory = memory_snapshot();
+    assert_eq!(memory.total_bytes, override_bytes);
+    assert!(memory.available_bytes <= override_bytes);
+    assert!(environment_changed_since_init());
+
+    // Invalid values are ignored
+    std::env::set_var("AUTO_ALLOCATOR_TOTAL_MEMORY", "a lot");
+    assert_eq!(memory_snapshot().total_bytes, detected);
+    std::env::remove_var("AUTO_ALLOCATOR_TOTAL_MEMORY");
+}
+
+#[test]
+fn test_parse_memory_size_formats() {
+    assert_eq!(parse_memory_size("0"), Some(0));
+    assert_eq!(parse_memory_size("512B"), Some(512));
+    assert_eq!(parse_memory_size("1KB"), Some(1024));
+    assert_eq!(parse_memory_size("1.5KB"), Some(1536));
+    assert_eq!(parse_memory_size(" 2 gib "), Some(2 << 30));
+    assert_eq!(parse_memory_size("2G"), Some(2 << 30));
+    assert_eq!(parse_memory_size("1TB"), Some(1 << 40));
+    assert_eq!(parse_memory_size("1.5"), None);
+    assert_eq!(parse_memory_size("MB"), None);
+    assert_eq!(parse_memory_size("12XB"), None);
+    assert_eq!(parse_memory_size("5é"), None);
+    assert_eq!(parse_memory_size("1µB"), None);
+    assert_eq!(parse_memory_size("1.2.3GB"), None);
+    assert_eq!(parse_memory_size("99999999999PB"), None);
+
+    // Round-trips what format_memory_size produces
+    for bytes in [1u64 << 10, 3 << 29, 16 << 30, 5 << 40] {
+        let text = auto_allocator::format_memory_size(bytes);
+        assert_eq!(parse_memory_size(&text), Some(bytes));
+    }
+}
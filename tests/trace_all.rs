@@ -0,0 +1,48 @@
+//! Allocation trace tests for auto-allocator
+//!
+//! Only built with the `trace-all` feature: `cargo test --features trace-all`
+
+#![cfg(feature = "trace-all")]
+
+use auto_allocator::{dump_alloc_trace, AllocTraceKind, RuntimeAllocator, ALLOC_TRACE_CAPACITY};
+use std::alloc::{GlobalAlloc, Layout};
+use std::hint::black_box;
+
+const TRACKED_SIZE: usize = 23_456;
+
+#[test]
+fn test_trace_records_alloc_and_free() {
+    // Through RuntimeAllocator directly, so this also holds under `no-global`
+    let layout = Layout::from_size_align(TRACKED_SIZE, 1).unwrap();
+    let address = unsafe {
+        let ptr = RuntimeAllocator.alloc(layout);
+        assert!(!ptr.is_null());
+        RuntimeAllocator.dealloc(ptr, layout);
+        ptr as usize
+    };
+
+    let trace = dump_alloc_trace();
+    let alloc = trace
+        .iter()
+        .rev()
+        .find(|e| e.kind == AllocTraceKind::Alloc && e.address == address && e.size == TRACKED_SIZE)
+        .expect("allocation traced");
+    let free = trace
+        .iter()
+        .rev()
+        .find(|e| e.kind == AllocTraceKind::Dealloc && e.address == address && e.size == TRACKED_SIZE)
+        .expect("free traced");
+    assert!(free.seq > alloc.seq);
+    assert!(alloc.to_string().contains("alloc 23456 bytes"));
+}
+
+#[test]
+fn test_trace_is_bounded_and_ordered() {
+    for i in 0..ALLOC_TRACE_CAPACITY * 2 {
+        drop(black_box(Box::new(i)));
+    }
+
+    let trace = dump_alloc_trace();
+    assert!(trace.len() <= ALLOC_TRACE_CAPACITY);
+    assert!(trace.windows(2).all(|pair| pair[0].seq < pair[1].seq));
+}